@@ -0,0 +1,201 @@
+//anchors and aliases, so a block that a document needs in several places only has to be written once. Opt-in, as `&` and `*` are otherwise ordinary characters in leaves
+
+use super::*;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnchorErrorKind {
+	/// there's no anchor with the alias's name
+	Undefined,
+	/// there's already an anchor with this name
+	Duplicate,
+	/// the anchor has nothing after it
+	Empty,
+	/// the anchored node contains an alias to itself, through the anchors named, which begin and end with it
+	Cycle(Vec<String>),
+	/// the wood would have come to more nodes than the limit given to resolve_anchors_limited
+	TooLarge(usize),
+}
+
+/// line and column are those of the anchor or alias the problem is with, name its name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorError {
+	pub line: isize,
+	pub column: isize,
+	pub name: String,
+	pub kind: AnchorErrorKind,
+}
+impl Display for AnchorError {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+		let msg = match self.kind {
+			AnchorErrorKind::Undefined=> format!("there's no anchor named \"{}\"", self.name),
+			AnchorErrorKind::Duplicate=> format!("there's already an anchor named \"{}\"", self.name),
+			AnchorErrorKind::Empty=> format!("the anchor \"{}\" has nothing after it", self.name),
+			AnchorErrorKind::Cycle(ref chain)=> format!("\"{}\" contains itself, through {}", self.name, chain.join(" -> ")),
+			AnchorErrorKind::TooLarge(n)=> format!("expanding \"{}\" would make more than {} nodes", self.name, n),
+		};
+		write_located(f, None, self.line, self.column, &msg)
+	}
+}
+impl Error for AnchorError {
+	fn description(&self) -> &str { "couldn't resolve an anchor" }
+}
+
+struct Anchor {
+	path: Vec<usize>,
+	resolved: Option<(Wood, usize)>, //and how many nodes are in it
+}
+
+struct Resolver {
+	anchors: HashMap<String, Anchor>,
+	resolving: Vec<String>,
+	max_nodes: usize,
+}
+
+fn anchor_name(w:&Wood)-> Option<&str> {
+	match *w {
+		Branchv(ref b)=> match b.v.first() {
+			Some(&Leafv(ref l)) if l.v.len() > 1 && l.v.starts_with('&')=> Some(&l.v[1..]),
+			_=> None,
+		},
+		Leafv(_)=> None,
+	}
+}
+fn alias_name(w:&Wood)-> Option<&str> {
+	match *w {
+		Leafv(ref l) if l.v.len() > 1 && l.v.starts_with('*')=> Some(&l.v[1..]),
+		_=> None,
+	}
+}
+
+fn error<T>(at:&Wood, name:&str, kind:AnchorErrorKind)-> Result<T, AnchorError> {
+	let (line, column) = at.line_and_col();
+	Err(AnchorError{ line, column, name:name.to_string(), kind })
+}
+
+//takes the anchors out of the wood, remembering where the anchored nodes are
+fn strip(w:&mut Wood, path:&mut Vec<usize>, anchors:&mut HashMap<String, Anchor>)-> Result<(), AnchorError> {
+	while let Some(name) = anchor_name(w).map(String::from) {
+		if anchors.contains_key(&name) { return error(&w[0], &name, AnchorErrorKind::Duplicate); }
+		let mut b = match replace(w, Wood::leaf(String::new())) { Branchv(b)=> b, Leafv(_)=> unreachable!() };
+		let anchor = b.v.remove(0);
+		*w = match b.v.len() {
+			0=> return error(&anchor, &name, AnchorErrorKind::Empty),
+			1=> b.v.pop().unwrap(),
+			_=> Branchv(b),
+		};
+		anchors.insert(name, Anchor{ path:path.clone(), resolved:None });
+	}
+	if let Branchv(ref mut b) = *w {
+		for (i, c) in b.v.iter_mut().enumerate() {
+			path.push(i);
+			strip(c, path, anchors)?;
+			path.pop();
+		}
+	}
+	Ok(())
+}
+
+impl Resolver {
+	//the anchored node, with the aliases in it resolved, and its size
+	fn resolve(&mut self, name:&str, alias:&Wood, tree:&Wood)-> Result<usize, AnchorError> {
+		let path = match self.anchors.get(name) {
+			None=> return error(alias, name, AnchorErrorKind::Undefined),
+			Some(&Anchor{ resolved:Some((_, n)), .. })=> return Ok(n),
+			Some(a)=> a.path.clone(),
+		};
+		if let Some(i) = self.resolving.iter().position(|r| r == name) {
+			let mut chain = self.resolving[i..].to_vec();
+			chain.push(name.to_string());
+			return error(alias, name, AnchorErrorKind::Cycle(chain));
+		}
+		self.resolving.push(name.to_string());
+		let mut node = path.iter().fold(tree, |w, &i| match *w { Branchv(ref b)=> &b.v[i], Leafv(_)=> unreachable!() }).clone();
+		let n = self.substitute(&mut node, tree)?;
+		self.resolving.pop();
+		self.anchors.get_mut(name).unwrap().resolved = Some((node, n));
+		Ok(n)
+	}
+	//replaces the aliases in w, returning how many nodes it has after
+	fn substitute(&mut self, w:&mut Wood, tree:&Wood)-> Result<usize, AnchorError> {
+		if let Some(name) = alias_name(w).map(String::from) {
+			let n = self.resolve(&name, w, tree)?;
+			if n > self.max_nodes { return error(w, &name, AnchorErrorKind::TooLarge(self.max_nodes)); }
+			*w = self.anchors[&name].resolved.as_ref().unwrap().0.clone();
+			return Ok(n);
+		}
+		let mut total = 1;
+		if let Branchv(ref mut b) = *w {
+			for c in b.v.iter_mut() {
+				total += self.substitute(c, tree)?;
+				if total > self.max_nodes {
+					let name = alias_name(c).unwrap_or("").to_string();
+					return error(c, &name, AnchorErrorKind::TooLarge(self.max_nodes));
+				}
+			}
+		}
+		Ok(total)
+	}
+}
+
+/// resolves anchors and aliases. A branch that starts with a leaf like `&name` is an anchor, and is replaced with what comes after that leaf, or with the branch of everything after it if there's more than one thing. Any leaf like `*name` is an alias, and is replaced with a copy of the anchored node, positions and all, wherever it is in the document. Anchors can be nested and contain aliases to other anchors, but not to themselves. EG
+///
+/// ```text
+/// &common
+///   limits
+///     timeout 30
+/// prod
+///   *common
+/// ```
+///
+/// becomes `(limits (timeout 30)) (prod (limits (timeout 30)))`. As with any line followed by an indented block, `&common limits` followed by `timeout 30` would have anchored only `limits`. Every copy is a whole copy, so a document with aliases of aliases of aliases can get very large, see resolve_anchors_limited
+pub fn resolve_anchors(w:Wood)-> Result<Wood, AnchorError> {
+	resolve_anchors_limited(w, usize::max_value())
+}
+
+/// resolve_anchors, failing with TooLarge before the wood would come to more than max_nodes nodes, for documents that might be trying to exhaust memory
+pub fn resolve_anchors_limited(mut w:Wood, max_nodes:usize)-> Result<Wood, AnchorError> {
+	let mut anchors = HashMap::new();
+	strip(&mut w, &mut Vec::new(), &mut anchors)?;
+	if anchors.is_empty() && w.iter_dfs().all(|(n, _, _)| alias_name(n).is_none()) { return Ok(w); }
+	let mut r = Resolver{ anchors, resolving:Vec::new(), max_nodes };
+	let tree = w.clone(); //the anchored nodes as they were before anything was substituted into them
+	r.substitute(&mut w, &tree)?;
+	Ok(w)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn anchoring() {
+		let w = parse_multiline_termpose("&common\n  limits\n    timeout 30\n    retries *tries\nprod\n  *common\n  port (&p 80)\nstaging port:*p\ntries (&tries 3)").unwrap();
+		assert_eq!(resolve_anchors(w).unwrap(), parse_multiline_termpose("limits\n  timeout 30\n  retries 3\nprod\n  limits\n    timeout 30\n    retries 3\n  port 80\nstaging port:80\ntries 3").unwrap());
+		let w = resolve_anchors(parse_multiline_termpose("&a &b x y\nz *a *b").unwrap()).unwrap();
+		assert_eq!(w, wood!{(x y) (z (x y) (x y))});
+		assert_eq!(w[1][1].line_and_col(), w[0].line_and_col());
+		let plain = parse_multiline_termpose("a & * \"*\"").unwrap();
+		assert_eq!(resolve_anchors(plain.clone()).unwrap(), plain);
+	}
+	
+	#[test]
+	fn anchor_errors() {
+		let fails = |s:&str| resolve_anchors(parse_multiline_termpose(s).unwrap()).unwrap_err();
+		let e = fails("a\n  b *nowhere");
+		assert_eq!((e.line, e.column, e.name.as_str(), e.kind.clone()), (1, 5, "nowhere", AnchorErrorKind::Undefined));
+		assert_eq!(e.to_string(), "2:5: there's no anchor named \"nowhere\"");
+		assert_eq!(fails("&a x\n&a y").kind, AnchorErrorKind::Duplicate);
+		assert_eq!(fails("x (&a)").kind, AnchorErrorKind::Empty);
+		assert_eq!(fails("&a\n  x\n    y *b\n&b z *a").kind, AnchorErrorKind::Cycle(vec!("b".into(), "a".into(), "b".into())));
+		assert_eq!(fails("&a x *a").kind, AnchorErrorKind::Cycle(vec!("a".into(), "a".into())));
+		//each level doubles the size
+		let mut bomb = "&l0 x y\n".to_string();
+		for i in 1..40 { bomb.push_str(&format!("&l{} *l{} *l{}\n", i, i - 1, i - 1)); }
+		let e = resolve_anchors_limited(parse_multiline_termpose(&bomb).unwrap(), 10000).unwrap_err();
+		assert_eq!(e.kind, AnchorErrorKind::TooLarge(10000));
+		assert!(resolve_anchors_limited(parse_multiline_termpose("&a x y\nb *a *a").unwrap(), 12).is_ok());
+		assert!(resolve_anchors_limited(parse_multiline_termpose("&a x y\nb *a *a").unwrap(), 11).is_err());
+	}
+}
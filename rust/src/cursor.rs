@@ -0,0 +1,131 @@
+//a zipper over wood, for editing it in place while moving around
+
+use super::*;
+
+//what's around the focus at one level: the parent, with its contents taken out, and the focus's siblings. right is kept reversed, so that moving right is a pop
+#[derive(Debug, Clone)]
+struct Crumb {
+	parent: Branch,
+	left: Vec<Wood>,
+	right: Vec<Wood>,
+}
+
+/// takes ownership of a wood and moves a focus through it. The focus can be edited or replaced wherever it is, and root() puts everything back together. The moves return false, and leave the cursor where it was, if there's nowhere to go
+#[derive(Debug, Clone)]
+pub struct WoodCursor {
+	focus: Wood,
+	crumbs: Vec<Crumb>,
+}
+
+impl WoodCursor {
+	pub fn new(root:Wood)-> Self { WoodCursor{ focus:root, crumbs:Vec::new() } }
+	pub fn focus(&self)-> &Wood { &self.focus }
+	pub fn focus_mut(&mut self)-> &mut Wood { &mut self.focus }
+	/// puts v at the focus, returning what was there
+	pub fn replace(&mut self, v:Wood)-> Wood { replace(&mut self.focus, v) }
+	/// how many levels down from the root the focus is
+	pub fn depth(&self)-> usize { self.crumbs.len() }
+	/// the focus's position among its siblings
+	pub fn index(&self)-> usize { self.crumbs.last().map_or(0, |c| c.left.len()) }
+	/// the index taken at each level on the way down from the root to the focus
+	pub fn path(&self)-> Vec<usize> { self.crumbs.iter().map(|c| c.left.len()).collect() }
+	
+	/// to the first child of the focus
+	pub fn down(&mut self)-> bool { self.child(0) }
+	/// to the ith child of the focus
+	pub fn child(&mut self, i:usize)-> bool {
+		match self.focus {
+			Branchv(ref b) if i < b.v.len() => {}
+			_=> return false,
+		}
+		let mut parent = match replace(&mut self.focus, Wood::branch(Vec::new())) {
+			Branchv(b)=> b,
+			Leafv(_)=> unreachable!(),
+		};
+		let mut v = replace(&mut parent.v, Vec::new());
+		let mut right = v.split_off(i + 1);
+		right.reverse();
+		self.focus = v.pop().unwrap();
+		self.crumbs.push(Crumb{ parent, left:v, right });
+		true
+	}
+	/// to the parent of the focus
+	pub fn up(&mut self)-> bool {
+		match self.crumbs.pop() {
+			Some(Crumb{ mut parent, mut left, mut right })=> {
+				right.reverse();
+				left.push(replace(&mut self.focus, Wood::branch(Vec::new())));
+				left.extend(right);
+				parent.v = left;
+				self.focus = Branchv(parent);
+				true
+			}
+			None=> false,
+		}
+	}
+	/// to the previous sibling of the focus
+	pub fn left(&mut self)-> bool {
+		match self.crumbs.last_mut() {
+			Some(c)=> {
+				match c.left.pop() {
+					Some(l)=> {
+						c.right.push(replace(&mut self.focus, l));
+						true
+					}
+					None=> false,
+				}
+			}
+			None=> false,
+		}
+	}
+	/// to the next sibling of the focus
+	pub fn right(&mut self)-> bool {
+		match self.crumbs.last_mut() {
+			Some(c)=> {
+				match c.right.pop() {
+					Some(r)=> {
+						c.left.push(replace(&mut self.focus, r));
+						true
+					}
+					None=> false,
+				}
+			}
+			None=> false,
+		}
+	}
+	/// goes all the way up and returns the whole, edited, wood
+	pub fn root(mut self)-> Wood {
+		while self.up() {}
+		self.focus
+	}
+}
+
+impl Wood {
+	pub fn cursor(self)-> WoodCursor { WoodCursor::new(self) }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn cursoring() {
+		let mut c = parse_termpose("server (host a) (port 80) (tags x y)").unwrap().cursor();
+		assert!(c.down());
+		assert!(!c.left());
+		assert!(c.right() && c.right());
+		assert_eq!(c.focus(), &wood!(port 80));
+		assert!(c.child(1));
+		assert_eq!(c.path(), vec!(2, 1));
+		assert_eq!(c.replace("81".into()), wood!(80));
+		assert!(!c.down());
+		assert!(c.up() && c.right());
+		c.focus_mut().push_child("z".into());
+		assert!(!c.right());
+		assert_eq!((c.depth(), c.index()), (1, 3));
+		let w = c.root();
+		assert_eq!(w, parse_termpose("server (host a) (port 81) (tags x y z)").unwrap());
+		assert_eq!(w.line_and_col(), (0, 1));
+	}
+}
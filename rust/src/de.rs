@@ -0,0 +1,335 @@
+//a serde Deserializer that reads Wood, expecting the layout that ser.rs produces. Struct and tuple struct tags are optional, so `(name a) (port 81)` will be read as a struct just as well as `Server (name a) (port 81)`
+
+use super::*;
+use serde::de::{self, Deserialize, DeserializeOwned, DeserializeSeed, Visitor, IntoDeserializer};
+
+impl de::Error for DewoodifyError {
+	fn custom<T:Display>(msg:T)-> Self {
+		DewoodifyError::located(-1, -1, msg.to_string())
+	}
+}
+
+/// reads anything that implements serde's Deserialize out of Wood. Leaves are borrowed from the wood where the type allows it
+pub fn from_wood<'de, T>(v:&'de Wood)-> Result<T, DewoodifyError> where T: Deserialize<'de> {
+	T::deserialize(Deserializer::new(v))
+}
+/// reads anything that implements serde's Deserialize out of termpose
+pub fn from_str<T>(v:&str)-> Result<T, WoodError> where T: DeserializeOwned {
+	match parse_termpose(v) {
+		Ok(t)=> from_wood(&t).map_err(|e| WoodError::DewoodifyError(e)),
+		Err(e)=> Err(WoodError::ParserError(e)),
+	}
+}
+
+/// so that wood can be embedded in other serde formats. Strings become leaves and sequences become branches. Numbers, bools and chars are accepted as leaves too, unit as an empty branch
+impl<'de> Deserialize<'de> for Wood {
+	fn deserialize<D>(d:D)-> Result<Wood, D::Error> where D: de::Deserializer<'de> {
+		d.deserialize_any(WoodVisitor)
+	}
+}
+
+struct WoodVisitor;
+impl<'de> Visitor<'de> for WoodVisitor {
+	type Value = Wood;
+	fn expecting(&self, f:&mut Formatter)-> std::fmt::Result {
+		f.write_str("a string or a sequence")
+	}
+	fn visit_str<E>(self, v:&str)-> Result<Wood, E> where E: de::Error { Ok(v.into()) }
+	fn visit_string<E>(self, v:String)-> Result<Wood, E> where E: de::Error { Ok(Wood::leaf(v)) }
+	fn visit_bool<E>(self, v:bool)-> Result<Wood, E> where E: de::Error { Ok(v.woodify()) }
+	fn visit_i64<E>(self, v:i64)-> Result<Wood, E> where E: de::Error { Ok(v.woodify()) }
+	fn visit_u64<E>(self, v:u64)-> Result<Wood, E> where E: de::Error { Ok(v.woodify()) }
+	fn visit_f64<E>(self, v:f64)-> Result<Wood, E> where E: de::Error { Ok(v.woodify()) }
+	fn visit_char<E>(self, v:char)-> Result<Wood, E> where E: de::Error { Ok(v.woodify()) }
+	fn visit_unit<E>(self)-> Result<Wood, E> where E: de::Error { Ok(branch!()) }
+	fn visit_seq<A>(self, mut seq:A)-> Result<Wood, A::Error> where A: de::SeqAccess<'de> {
+		let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+		while let Some(e) = seq.next_element()? {
+			v.push(e);
+		}
+		Ok(Wood::branch(v))
+	}
+}
+
+//errors raised by visitors, EG, about missing fields, don't know where they happened. This places them at the wood that was being visited
+fn located(mut e:DewoodifyError, v:&Wood)-> DewoodifyError {
+	if e.line == -1 {
+		let (line, column) = v.line_and_col();
+		e.line = line;
+		e.column = column;
+	}
+	if e.provenance.is_none() {
+		e.provenance = v.provenance_arc().cloned();
+	}
+	e
+}
+
+//if the wood starts with the given tag, what comes after it, otherwise, all of its contents
+fn untagged<'de>(v:&'de Wood, tag:&str)-> &'de [Wood] {
+	match *v {
+		Branchv(ref b)=> {
+			match b.v.first() {
+				Some(&Leafv(ref l)) if l.v == tag => &b.v[1..],
+				_=> &b.v[..],
+			}
+		}
+		Leafv(_)=> v.contents().as_slice(),
+	}
+}
+
+pub struct Deserializer<'de> {
+	v:&'de Wood,
+}
+impl<'de> Deserializer<'de> {
+	pub fn new(v:&'de Wood)-> Self { Deserializer{ v } }
+}
+
+macro_rules! deserialize_via_dewoodable {
+	($method:ident, $visit:ident, $Type:ident) => (
+		fn $method<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+			visitor.$visit($Type::dewoodify(self.v)?).map_err(|e| located(e, self.v))
+		}
+	)
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+	type Error = DewoodifyError;
+
+	fn deserialize_any<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		match *self.v {
+			Leafv(ref l)=> visitor.visit_borrowed_str(&l.v),
+			Branchv(ref b)=> visitor.visit_seq(SeqWalker{ it:b.v.iter() }),
+		}.map_err(|e| located(e, self.v))
+	}
+
+	deserialize_via_dewoodable!(deserialize_bool, visit_bool, bool);
+	deserialize_via_dewoodable!(deserialize_i8, visit_i8, i8);
+	deserialize_via_dewoodable!(deserialize_i16, visit_i16, i16);
+	deserialize_via_dewoodable!(deserialize_i32, visit_i32, i32);
+	deserialize_via_dewoodable!(deserialize_i64, visit_i64, i64);
+	deserialize_via_dewoodable!(deserialize_i128, visit_i128, i128);
+	deserialize_via_dewoodable!(deserialize_u8, visit_u8, u8);
+	deserialize_via_dewoodable!(deserialize_u16, visit_u16, u16);
+	deserialize_via_dewoodable!(deserialize_u32, visit_u32, u32);
+	deserialize_via_dewoodable!(deserialize_u64, visit_u64, u64);
+	deserialize_via_dewoodable!(deserialize_u128, visit_u128, u128);
+	deserialize_via_dewoodable!(deserialize_f32, visit_f32, f32);
+	deserialize_via_dewoodable!(deserialize_f64, visit_f64, f64);
+	deserialize_via_dewoodable!(deserialize_char, visit_char, char);
+
+	fn deserialize_str<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		match *self.v {
+			Leafv(ref l)=> visitor.visit_borrowed_str(&l.v).map_err(|e| located(e, self.v)),
+			Branchv(_)=> Err(DewoodifyError::new(self.v, "expected a leaf here, but found a branch".into())),
+		}
+	}
+	fn deserialize_string<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		self.deserialize_str(visitor)
+	}
+	fn deserialize_bytes<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_byte_buf(Vec::<u8>::dewoodify(self.v)?).map_err(|e| located(e, self.v))
+	}
+	fn deserialize_byte_buf<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		self.deserialize_bytes(visitor)
+	}
+	fn deserialize_option<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		let v = self.v;
+		match *v {
+			Leafv(ref l) if l.v == "none" => visitor.visit_none(),
+			Branchv(ref b) if b.v.len() == 2 && b.v[0].is_leaf() && b.v[0].initial_str() == "some" => {
+				visitor.visit_some(Deserializer::new(&b.v[1]))
+			}
+			//anything else is taken to be the value itself
+			_=> visitor.visit_some(self),
+		}.map_err(|e| located(e, v))
+	}
+	fn deserialize_unit<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_unit().map_err(|e| located(e, self.v))
+	}
+	fn deserialize_unit_struct<V>(self, _name:&'static str, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		self.deserialize_unit(visitor)
+	}
+	fn deserialize_newtype_struct<V>(self, _name:&'static str, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_newtype_struct(self)
+	}
+	fn deserialize_seq<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_seq(SeqWalker{ it:self.v.contents() }).map_err(|e| located(e, self.v))
+	}
+	fn deserialize_tuple<V>(self, _len:usize, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		self.deserialize_seq(visitor)
+	}
+	fn deserialize_tuple_struct<V>(self, name:&'static str, _len:usize, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_seq(SeqWalker{ it:untagged(self.v, name).iter() }).map_err(|e| located(e, self.v))
+	}
+	fn deserialize_map<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_map(MapWalker{ it:self.v.contents(), value:None }).map_err(|e| located(e, self.v))
+	}
+	fn deserialize_struct<V>(self, name:&'static str, _fields:&'static [&'static str], visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_map(MapWalker{ it:untagged(self.v, name).iter(), value:None }).map_err(|e| located(e, self.v))
+	}
+	fn deserialize_enum<V>(self, _name:&'static str, _variants:&'static [&'static str], visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		let (tag, rest):(&Wood, &[Wood]) = match *self.v {
+			Leafv(_)=> (self.v, &[]),
+			Branchv(ref b)=> {
+				match b.v.first() {
+					Some(t)=> (t, &b.v[1..]),
+					None=> return Err(DewoodifyError::new(self.v, "expected a variant, but the branch was empty".into())),
+				}
+			}
+		};
+		visitor.visit_enum(VariantWalker{ v:self.v, tag, rest }).map_err(|e| located(e, self.v))
+	}
+	fn deserialize_identifier<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		self.deserialize_str(visitor)
+	}
+	fn deserialize_ignored_any<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_unit()
+	}
+}
+
+struct SeqWalker<'de> {
+	it:std::slice::Iter<'de, Wood>,
+}
+impl<'de> de::SeqAccess<'de> for SeqWalker<'de> {
+	type Error = DewoodifyError;
+	fn next_element_seed<T>(&mut self, seed:T)-> Result<Option<T::Value>, DewoodifyError> where T: DeserializeSeed<'de> {
+		match self.it.next() {
+			Some(w)=> seed.deserialize(Deserializer::new(w)).map(Some),
+			None=> Ok(None),
+		}
+	}
+	fn size_hint(&self)-> Option<usize> { Some(self.it.len()) }
+}
+
+struct MapWalker<'de> {
+	it:std::slice::Iter<'de, Wood>,
+	value:Option<&'de Wood>,
+}
+impl<'de> de::MapAccess<'de> for MapWalker<'de> {
+	type Error = DewoodifyError;
+	fn next_key_seed<K>(&mut self, seed:K)-> Result<Option<K::Value>, DewoodifyError> where K: DeserializeSeed<'de> {
+		match self.it.next() {
+			Some(w)=> {
+				match *w {
+					Branchv(ref b) if b.v.len() == 2 => {
+						self.value = Some(&b.v[1]);
+						seed.deserialize(Deserializer::new(&b.v[0])).map(Some)
+					}
+					_=> Err(DewoodifyError::new(w, "expected a pair of a key and a value here".into())),
+				}
+			}
+			None=> Ok(None),
+		}
+	}
+	fn next_value_seed<V>(&mut self, seed:V)-> Result<V::Value, DewoodifyError> where V: DeserializeSeed<'de> {
+		match self.value.take() {
+			Some(w)=> seed.deserialize(Deserializer::new(w)),
+			None=> Err(de::Error::custom("next_value_seed called before next_key_seed")),
+		}
+	}
+	fn size_hint(&self)-> Option<usize> { Some(self.it.len()) }
+}
+
+struct VariantWalker<'de> {
+	v:&'de Wood,
+	tag:&'de Wood,
+	rest:&'de [Wood],
+}
+impl<'de> de::EnumAccess<'de> for VariantWalker<'de> {
+	type Error = DewoodifyError;
+	type Variant = Self;
+	fn variant_seed<V>(self, seed:V)-> Result<(V::Value, Self), DewoodifyError> where V: DeserializeSeed<'de> {
+		let tag:&str = match *self.tag {
+			Leafv(ref l)=> &l.v,
+			Branchv(_)=> return Err(DewoodifyError::new(self.tag, "expected a variant name here, but found a branch".into())),
+		};
+		let val = seed.deserialize(tag.into_deserializer()).map_err(|e:DewoodifyError| located(e, self.tag))?;
+		Ok((val, self))
+	}
+}
+impl<'de> de::VariantAccess<'de> for VariantWalker<'de> {
+	type Error = DewoodifyError;
+	fn unit_variant(self)-> Result<(), DewoodifyError> {
+		if self.rest.len() == 0 {
+			Ok(())
+		}else{
+			Err(DewoodifyError::new(self.v, format!("\"{}\" is a unit variant, so nothing should follow it", self.tag.initial_str())))
+		}
+	}
+	fn newtype_variant_seed<T>(self, seed:T)-> Result<T::Value, DewoodifyError> where T: DeserializeSeed<'de> {
+		if self.rest.len() == 1 {
+			seed.deserialize(Deserializer::new(&self.rest[0]))
+		}else{
+			Err(DewoodifyError::new(self.v, format!("expected one value after \"{}\", but there were {}", self.tag.initial_str(), self.rest.len())))
+		}
+	}
+	fn tuple_variant<V>(self, _len:usize, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_seq(SeqWalker{ it:self.rest.iter() })
+	}
+	fn struct_variant<V>(self, _fields:&'static [&'static str], visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		visitor.visit_map(MapWalker{ it:self.rest.iter(), value:None })
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ser::to_wood;
+	use std::collections::BTreeMap;
+
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	struct Server { name:String, port:u16, tags:Vec<String>, backup:Option<u16> }
+
+	#[derive(Serialize, Deserialize, Debug, PartialEq)]
+	enum Shape { Empty, Circle(f64), Rect(f64, f64), Named{ name:String } }
+
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Borrowing<'a> { name:&'a str }
+
+	#[test]
+	fn deserializing() {
+		let s:Server = from_str("Server name:a port:81 tags:(x y) backup:(some 3)").unwrap();
+		assert_eq!(s, Server{ name:"a".into(), port:81, tags:vec!("x".into(), "y".into()), backup:Some(3) });
+		//the tag is optional
+		let s2:Server = from_str("(name a) (port 81) (tags ()) (backup none)").unwrap();
+		assert_eq!(s2.backup, None);
+		for sh in vec!(Shape::Empty, Shape::Circle(1.5), Shape::Rect(1.0, 2.0), Shape::Named{ name:"n".into() }) {
+			assert_eq!(sh, from_wood(&to_wood(&sh).unwrap()).unwrap());
+		}
+		let mut m = BTreeMap::new();
+		m.insert("k".to_string(), (1u8, true));
+		assert_eq!(m, from_wood(&to_wood(&m).unwrap()).unwrap());
+		let w = parse_termpose("Borrowing name:b").unwrap();
+		let b:Borrowing = from_wood(&w).unwrap();
+		assert_eq!(b.name, "b");
+	}
+
+	#[test]
+	fn deserialization_errors() {
+		let w = parse_termpose("Server name:a port:lots tags:() backup:none").unwrap();
+		let e = from_wood::<Server>(&w).unwrap_err();
+		assert_eq!((e.line, e.column), (0, 20));
+		let w = parse_termpose("Server name:a tags:() backup:none").unwrap();
+		let e = from_wood::<Server>(&w).unwrap_err();
+		assert_eq!(e.msg, "missing field `port`");
+		assert_eq!((e.line, e.column), (0, 1));
+		let e = from_wood::<Shape>(&parse_termpose("Triangle 1 2 3").unwrap()).unwrap_err();
+		assert!(e.msg.starts_with("unknown variant `Triangle`"));
+	}
+	
+	#[derive(Deserialize)]
+	struct Embedding { name:String, layout:Wood }
+	
+	#[test]
+	fn deserializing_wood() {
+		let e:Embedding = from_str("Embedding name:a layout:(row (w 3) b)").unwrap();
+		assert_eq!(e.name, "a");
+		assert_eq!(e.layout.to_string(), "(row (w 3) b)");
+		//from a format that has its own numbers
+		let n = Wood::deserialize(de::value::U64Deserializer::<DewoodifyError>::new(7)).unwrap();
+		assert_eq!(n.to_string(), "7");
+		let w = parse_termpose("a (b c) ()").unwrap();
+		assert_eq!(from_wood::<Wood>(&w).unwrap(), w);
+	}
+}
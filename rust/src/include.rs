@@ -0,0 +1,317 @@
+//expanding `include "other.term"` directives, so that a document can be split across files. Each include is replaced with the roots of the file it names, which keep the positions they had in that file and are given a Provenance::Source naming it, so errors about them can still be traced
+
+use super::*;
+use std::io;
+use std::collections::HashMap;
+use std::path::Component;
+
+/// where an IncludeResolver gets the files it's asked to include from
+pub trait IncludeLoader {
+	/// the name the file included as name, by the file named from (None at the top), is to be known by. Cycles are found by comparing these, and errors show them. By default, names are taken as they are
+	fn resolve(&self, from:Option<&str>, name:&str)-> String {
+		let _ = from;
+		name.to_string()
+	}
+	/// the text of a file, by its resolved name
+	fn load(&mut self, name:&str)-> io::Result<String>;
+}
+
+impl<F> IncludeLoader for F where F: FnMut(&str)-> io::Result<String> {
+	fn load(&mut self, name:&str)-> io::Result<String> { self(name) }
+}
+
+/// loads files from the filesystem. Names are paths, relative to the directory of the file that includes them, or to base at the top
+#[derive(Debug, Clone)]
+pub struct FsLoader {
+	pub base: PathBuf,
+}
+impl FsLoader {
+	pub fn new<P:Into<PathBuf>>(base:P)-> Self { FsLoader{ base:base.into() } }
+}
+impl IncludeLoader for FsLoader {
+	fn resolve(&self, from:Option<&str>, name:&str)-> String {
+		let dir = match from {
+			Some(f)=> Path::new(f).parent().map_or(PathBuf::new(), |p| p.to_path_buf()),
+			None=> self.base.clone(),
+		};
+		//normalized without touching the filesystem, so that `a/../b.term` and `b.term` are seen to be the same file
+		let mut out = PathBuf::new();
+		for c in dir.join(name).components() {
+			match c {
+				Component::CurDir=> {}
+				Component::ParentDir=> match out.components().next_back() {
+					Some(Component::Normal(_))=> { out.pop(); }
+					_=> out.push(".."),
+				},
+				c=> out.push(c.as_os_str()),
+			}
+		}
+		out.display().to_string()
+	}
+	fn load(&mut self, name:&str)-> io::Result<String> { std::fs::read_to_string(name) }
+}
+
+/// loads files from a map of names to texts, for tests, or for documents that didn't come from files
+#[derive(Debug, Clone, Default)]
+pub struct MemoryLoader {
+	pub files: HashMap<String, String>,
+}
+impl MemoryLoader {
+	pub fn new()-> Self { MemoryLoader::default() }
+	pub fn with(mut self, name:&str, text:&str)-> Self {
+		self.files.insert(name.to_string(), text.to_string());
+		self
+	}
+}
+impl IncludeLoader for MemoryLoader {
+	fn load(&mut self, name:&str)-> io::Result<String> {
+		self.files.get(name).cloned().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "there's no such file"))
+	}
+}
+
+#[derive(Debug)]
+pub enum IncludeErrorKind {
+	/// the directive wasn't `include "name"`
+	Malformed,
+	/// the loader couldn't load the named file
+	Load{ name:String, error:io::Error },
+	/// the included file didn't parse. The error says which file
+	Parse(PositionedError),
+	/// a file includes itself, through the files named, which begin and end with it
+	Cycle(Vec<String>),
+	/// includes were nested deeper than the resolver's max_depth
+	TooDeep(usize),
+	/// the expanded wood would have had more than the resolver's max_nodes nodes
+	TooLarge(usize),
+}
+
+/// file, line and column are those of the include that couldn't be expanded. file is None if it was in wood given to expand without a name, and the line is -1 if the problem is with the file given to load
+#[derive(Debug)]
+pub struct IncludeError {
+	pub file: Option<String>,
+	pub line: isize,
+	pub column: isize,
+	pub kind: IncludeErrorKind,
+}
+impl Display for IncludeError {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+		let msg = match self.kind {
+			IncludeErrorKind::Parse(ref e)=> return Display::fmt(e, f),
+			IncludeErrorKind::Malformed=> "an include should be the directive and then the name of a file".to_string(),
+			IncludeErrorKind::Load{ ref name, ref error }=> format!("couldn't load \"{}\": {}", name, error),
+			IncludeErrorKind::Cycle(ref chain)=> format!("\"{}\" includes itself, through {}", chain[0], chain.join(" -> ")),
+			IncludeErrorKind::TooDeep(n)=> format!("includes were nested more than {} deep", n),
+			IncludeErrorKind::TooLarge(n)=> format!("expanding includes would make more than {} nodes", n),
+		};
+		match self.file {
+			Some(ref file)=> write_located(f, Some(file), self.line, self.column, &msg),
+			None if self.line < 0=> f.write_str(&msg),
+			None=> write_located(f, Some("input"), self.line, self.column, &msg),
+		}
+	}
+}
+impl Error for IncludeError {
+	fn description(&self) -> &str { "couldn't expand an include" }
+	fn cause(&self) -> Option<&Error> {
+		match self.kind {
+			IncludeErrorKind::Load{ ref error, .. }=> Some(error),
+			IncludeErrorKind::Parse(ref e)=> Some(e),
+			_=> None,
+		}
+	}
+}
+
+/// expands include directives, which are branches that start with the directive leaf (`include` unless changed) followed by the name of a file, EG `include "db.term"`. The roots of the named file, parsed as parse_multiline_termpose would, take the directive's place among its siblings, and any includes in them are expanded in turn. A file can be included any number of times, but not inside itself
+pub struct IncludeResolver<L> {
+	loader: L,
+	directive: String,
+	max_depth: usize,
+	max_nodes: usize,
+	stack: Vec<String>, //the files being expanded, outermost first
+	nodes: usize, //in everything parsed so far
+}
+
+impl<L:IncludeLoader> IncludeResolver<L> {
+	pub fn new(loader:L)-> Self {
+		IncludeResolver{ loader, directive:"include".into(), max_depth:32, max_nodes:1_000_000, stack:Vec::new(), nodes:0 }
+	}
+	/// the leaf that starts a directive
+	pub fn directive(mut self, d:&str)-> Self {
+		self.directive = d.to_string();
+		self
+	}
+	/// how many includes deep files can be nested. A cycle is always caught, but a loader whose names never repeat could otherwise go on forever
+	pub fn max_depth(mut self, n:usize)-> Self {
+		self.max_depth = n;
+		self
+	}
+	/// how many nodes the expanded wood can come to, counting every file each time it's included. A file that includes another twice, which includes another twice, and so on, doubles with every level, so max_depth alone doesn't stop a few small files from exhausting memory. Default a million
+	pub fn max_nodes(mut self, n:usize)-> Self {
+		self.max_nodes = n;
+		self
+	}
+	pub fn loader(&self)-> &L { &self.loader }
+	pub fn into_loader(self)-> L { self.loader }
+	
+	/// loads and parses the named file, expanding the includes in it. Returns the branch of its roots, as parse_multiline_termpose does
+	pub fn load(&mut self, name:&str)-> Result<Wood, IncludeError> {
+		self.stack.clear();
+		self.nodes = 0;
+		let resolved = self.loader.resolve(None, name);
+		self.load_resolved(resolved, None, -1, -1)
+	}
+	
+	/// expands the includes in w, which was parsed from the file named from, if it was, so that those are resolved relative to it. If w is itself an include, the branch of the included roots is returned
+	pub fn expand(&mut self, mut w:Wood, from:Option<&str>)-> Result<Wood, IncludeError> {
+		self.stack.clear();
+		self.nodes = w.iter_dfs().count();
+		if let Some(f) = from { self.stack.push(f.to_string()); }
+		if self.is_directive(&w) {
+			Ok(Wood::branch(self.include(&w, from)?))
+		}else{
+			self.expand_in(&mut w, from)?;
+			Ok(w)
+		}
+	}
+	
+	fn is_directive(&self, w:&Wood)-> bool {
+		match *w {
+			Branchv(ref b)=> b.v.first().map_or(false, |d| d.is_leaf() && d.initial_str() == self.directive),
+			Leafv(_)=> false,
+		}
+	}
+	
+	fn expand_in(&mut self, w:&mut Wood, from:Option<&str>)-> Result<(), IncludeError> {
+		if let Branchv(ref mut b) = *w {
+			let mut i = 0;
+			while i < b.v.len() {
+				if self.is_directive(&b.v[i]) {
+					let roots = self.include(&b.v[i], from)?;
+					let n = roots.len();
+					b.v.splice(i..i + 1, roots);
+					i += n;
+				}else{
+					self.expand_in(&mut b.v[i], from)?;
+					i += 1;
+				}
+			}
+		}
+		Ok(())
+	}
+	
+	//the expanded roots of the file the directive d names
+	fn include(&mut self, d:&Wood, from:Option<&str>)-> Result<Vec<Wood>, IncludeError> {
+		let (line, column) = d.line_and_col();
+		let name = match *d {
+			Branchv(ref b) if b.v.len() == 2 && b.v[1].is_leaf()=> b.v[1].initial_str(),
+			_=> return Err(IncludeError{ file:from.map(String::from), line, column, kind:IncludeErrorKind::Malformed }),
+		};
+		let resolved = self.loader.resolve(from, name);
+		let root = self.load_resolved(resolved, from, line, column)?;
+		match root { Branchv(b)=> Ok(b.v), l=> Ok(vec!(l)) }
+	}
+	
+	fn load_resolved(&mut self, name:String, from:Option<&str>, line:isize, column:isize)-> Result<Wood, IncludeError> {
+		let fail = |kind| IncludeError{ file:from.map(String::from), line, column, kind };
+		if let Some(i) = self.stack.iter().position(|s| *s == name) {
+			let mut chain = self.stack[i..].to_vec();
+			chain.push(name);
+			return Err(fail(IncludeErrorKind::Cycle(chain)));
+		}
+		if self.stack.len() > self.max_depth { return Err(fail(IncludeErrorKind::TooDeep(self.max_depth))); }
+		let text = match self.loader.load(&name) {
+			Ok(t)=> t,
+			Err(error)=> return Err(fail(IncludeErrorKind::Load{ name, error })),
+		};
+		let file:Arc<str> = name.as_str().into();
+		let mut root = parse_multiline_termpose(&text).map_err(|e| fail(IncludeErrorKind::Parse(e.in_file(file.clone()))))?;
+		self.nodes += root.iter_dfs().count();
+		if self.nodes > self.max_nodes { return Err(fail(IncludeErrorKind::TooLarge(self.max_nodes))); }
+		mark_source(&mut root, &file);
+		self.stack.push(name);
+		let r = self.expand_in(&mut root, Some(&file));
+		self.stack.pop();
+		r.map(|_| root)
+	}
+}
+
+fn mark_source(w:&mut Wood, file:&Arc<str>) {
+	w.meta_mut().provenance = Some(Arc::new(Provenance::Source{ name:file.clone(), span:w.span() }));
+	if let Branchv(ref mut b) = *w {
+		for c in b.v.iter_mut() { mark_source(c, file); }
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn files()-> MemoryLoader {
+		MemoryLoader::new()
+			.with("main.term", "name app\ninclude \"db.term\"\nserver\n  include tls.term\n  port 80")
+			.with("db.term", "db\n  host h\ninclude tls.term")
+			.with("tls.term", "tls on")
+	}
+	
+	#[test]
+	fn including() {
+		let w = IncludeResolver::new(files()).load("main.term").unwrap();
+		assert_eq!(w, parse_multiline_termpose("name app\ndb\n  host h\ntls on\nserver\n  tls on\n  port 80").unwrap());
+		//positions are the ones in the included files
+		assert_eq!(w[1][1].line_and_col(), (1, 3));
+		assert_eq!(w[1][1].provenance().unwrap().to_string(), "db.term:2:3");
+		assert_eq!(w[3][1].provenance().unwrap().to_string(), "tls.term:1:1");
+		assert_eq!(w[0].provenance().unwrap().to_string(), "main.term:1:1");
+		
+		let parsed = parse_multiline_termpose("use tls.term\nx").unwrap();
+		let mut r = IncludeResolver::new(files()).directive("use");
+		assert_eq!(r.expand(parsed, None).unwrap(), wood!{(tls on) x});
+		assert_eq!(r.expand(wood!(use "db.term"), Some("main.term")).unwrap()[0], wood!(db (host h)));
+		
+		let mut calls = Vec::new();
+		{
+			let mut r = IncludeResolver::new(|n:&str| { calls.push(n.to_string()); Ok(format!("from {}", n)) });
+			assert_eq!(r.expand(wood!((include a) (include a)), None).unwrap(), wood!((from a) (from a)));
+		}
+		assert_eq!(calls, vec!("a", "a"));
+	}
+	
+	#[test]
+	fn include_errors() {
+		let cyclic = MemoryLoader::new().with("a", "x\ninclude b").with("b", "\n\n  include a").with("c", "include c");
+		let e = IncludeResolver::new(cyclic.clone()).load("a").unwrap_err();
+		assert_eq!(e.to_string(), "b:3:3: \"a\" includes itself, through a -> b -> a");
+		assert!(match e.kind { IncludeErrorKind::Cycle(ref c)=> c.len() == 3, _=> false });
+		assert_eq!(IncludeResolver::new(cyclic.clone()).load("c").unwrap_err().to_string(), "c:1:1: \"c\" includes itself, through c -> c");
+		assert!(IncludeResolver::new(cyclic).expand(wood!(include a), Some("a")).is_err());
+		
+		let e = IncludeResolver::new(files().with("bad.term", "ok\n  (fine)\n  oops)")).expand(wood!{(include "bad.term")}, None).unwrap_err();
+		assert_eq!(e.to_string(), "bad.term:3:7: unmatched paren");
+		assert!(IncludeResolver::new(files()).load("nothing.term").unwrap_err().to_string().starts_with("couldn't load \"nothing.term\""));
+		let e = IncludeResolver::new(files()).expand(parse_multiline_termpose("a\ninclude x y").unwrap(), None).unwrap_err();
+		assert_eq!((e.line, e.column), (1, 1));
+		assert!(match e.kind { IncludeErrorKind::Malformed=> true, _=> false });
+		
+		let mut n = 0;
+		let deep = IncludeResolver::new(move |_:&str| { n += 1; Ok(format!("include f{}", n)) }).max_depth(5).load("f0").unwrap_err();
+		assert!(match deep.kind { IncludeErrorKind::TooDeep(5)=> true, _=> false });
+		
+		//each of twelve levels includes the next twice, which is too much without ever being too deep
+		let doubling = |name:&str| Ok(if name.len() < 12 { format!("{0} (include {0}x) (include {0}x)", name) }else{ name.to_string() });
+		assert!(IncludeResolver::new(doubling).load("f").unwrap().iter_dfs().count() > 6000);
+		let e = IncludeResolver::new(doubling).max_nodes(1000).load("f").unwrap_err();
+		assert!(match e.kind { IncludeErrorKind::TooLarge(1000)=> true, _=> false });
+		assert_eq!(e.to_string(), "fxxxxxxxxx:1:34: expanding includes would make more than 1000 nodes");
+	}
+	
+	#[test]
+	fn fs_names() {
+		let l = FsLoader::new("conf");
+		assert_eq!(l.resolve(None, "main.term"), Path::new("conf").join("main.term").display().to_string());
+		let main = l.resolve(None, "sub/main.term");
+		assert_eq!(l.resolve(Some(&main), "../db.term"), Path::new("conf").join("db.term").display().to_string());
+		assert_eq!(l.resolve(Some(&main), "./x/../tls.term"), Path::new("conf").join("sub").join("tls.term").display().to_string());
+		assert_eq!(FsLoader::new("").resolve(None, "../up.term"), Path::new("..").join("up.term").display().to_string());
+	}
+}
@@ -0,0 +1,192 @@
+
+//impls for types from other crates, each behind a cargo feature of the same name
+
+use super::*;
+
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+#[cfg(feature = "uuid")]
+do_basic_stringifying_woodable_for!(Uuid); //hyphenated form
+#[cfg(feature = "uuid")]
+do_basic_destringifying_dewoodable_for!(Uuid);
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc, FixedOffset, NaiveDate};
+#[cfg(feature = "chrono")]
+impl Woodable for DateTime<Utc> {
+	fn woodify(&self) -> Wood { self.to_rfc3339().into() }
+}
+#[cfg(feature = "chrono")]
+impl Dewoodable for DateTime<Utc> {
+	fn dewoodify(v:&Wood) -> Result<DateTime<Utc>, DewoodifyError> {
+		DateTime::<FixedOffset>::dewoodify(v).map(|d| d.with_timezone(&Utc))
+	}
+}
+#[cfg(feature = "chrono")]
+impl Woodable for DateTime<FixedOffset> {
+	fn woodify(&self) -> Wood { self.to_rfc3339().into() }
+}
+#[cfg(feature = "chrono")]
+impl Dewoodable for DateTime<FixedOffset> {
+	fn dewoodify(v:&Wood) -> Result<DateTime<FixedOffset>, DewoodifyError> {
+		DateTime::parse_from_rfc3339(v.initial_str()).map_err(|er|{
+			DewoodifyError::new_with_cause(v, "couldn't parse an RFC 3339 datetime".into(), Some(Box::new(er)))
+		})
+	}
+}
+#[cfg(feature = "chrono")]
+do_basic_stringifying_woodable_for!(NaiveDate); //YYYY-MM-DD
+#[cfg(feature = "chrono")]
+do_basic_destringifying_dewoodable_for!(NaiveDate);
+
+#[cfg(feature = "time")]
+use time::OffsetDateTime;
+#[cfg(feature = "time")]
+use time::format_description::well_known::Rfc3339;
+#[cfg(feature = "time")]
+impl Woodable for OffsetDateTime {
+	fn woodify(&self) -> Wood {
+		//RFC 3339 can't represent years beyond 9999 or offsets with seconds in them, those just get time's default formatting
+		self.format(&Rfc3339).unwrap_or_else(|_| self.to_string()).into()
+	}
+}
+#[cfg(feature = "time")]
+impl Dewoodable for OffsetDateTime {
+	fn dewoodify(v:&Wood) -> Result<OffsetDateTime, DewoodifyError> {
+		OffsetDateTime::parse(v.initial_str(), &Rfc3339).map_err(|er|{
+			DewoodifyError::new_with_cause(v, "couldn't parse an RFC 3339 datetime".into(), Some(Box::new(er)))
+		})
+	}
+}
+
+#[cfg(feature = "url")]
+use url::Url;
+#[cfg(feature = "url")]
+impl Woodable for Url {
+	fn woodify(&self) -> Wood { self.as_str().into() }
+}
+#[cfg(feature = "url")]
+impl Dewoodable for Url {
+	fn dewoodify(v:&Wood) -> Result<Url, DewoodifyError> {
+		let s = v.initial_str();
+		Url::parse(s).map_err(|er|{
+			DewoodifyError::new_with_cause(v, format!("\"{}\" is not a valid url: {}", s, er), Some(Box::new(er)))
+		})
+	}
+}
+
+//these are kept as leaves in full, never passing through a float
+#[cfg(feature = "num-bigint")]
+use num_bigint::{BigInt, BigUint};
+#[cfg(feature = "num-bigint")]
+do_basic_stringifying_woodable_for!(BigInt);
+#[cfg(feature = "num-bigint")]
+do_basic_destringifying_dewoodable_for!(BigInt);
+#[cfg(feature = "num-bigint")]
+do_basic_stringifying_woodable_for!(BigUint);
+#[cfg(feature = "num-bigint")]
+do_basic_destringifying_dewoodable_for!(BigUint);
+
+#[cfg(feature = "rust_decimal")]
+use rust_decimal::Decimal;
+#[cfg(feature = "rust_decimal")]
+do_basic_stringifying_woodable_for!(Decimal);
+#[cfg(feature = "rust_decimal")]
+do_basic_destringifying_dewoodable_for!(Decimal);
+
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+#[cfg(feature = "indexmap")]
+use std::hash::Hash;
+#[cfg(feature = "indexmap")]
+impl<K, V> Woodable for IndexMap<K, V> where K:Woodable, V:Woodable {
+	fn woodify(&self) -> Wood {
+		let mut ret = Vec::with_capacity(self.len());
+		for (k, v) in self.iter() { ret.push(branch!(k.woodify(), v.woodify())); }
+		ret.into()
+	}
+}
+#[cfg(feature = "indexmap")]
+impl<K, V> Dewoodable for IndexMap<K, V> where K:Dewoodable + Eq + Hash, V:Dewoodable {
+	fn dewoodify(v:&Wood) -> Result<IndexMap<K, V>, DewoodifyError> {
+		let pairs:Vec<(K, V)> = wooder::OrderedMapBi(wooder::Iden, wooder::Iden).dewoodify(v)?;
+		Ok(pairs.into_iter().collect())
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn uuids() {
+		let w = parse_termpose("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+		let u:Uuid = dewoodify(&w).unwrap();
+		assert_eq!(u.woodify(), w);
+		let e = dewoodify::<Vec<Uuid>>(&parse_termpose("67e55044-10b1-426f-9247-bb680e5fe0c8 67e55044").unwrap()).unwrap_err();
+		assert_eq!(e.column, 38);
+		assert!(e.msg.contains("Uuid"));
+	}
+
+	
+	#[cfg(feature = "chrono")]
+	#[test]
+	fn chrono_datetimes() {
+		let w = parse_termpose("\"2021-03-04T05:06:07+00:00\"").unwrap();
+		let d:DateTime<Utc> = dewoodify(&w).unwrap();
+		assert_eq!(d.woodify(), w);
+		let shifted:DateTime<Utc> = dewoodify(&parse_termpose("\"2021-03-04T07:06:07+02:00\"").unwrap()).unwrap();
+		assert_eq!(d, shifted);
+		let n:NaiveDate = dewoodify(&parse_termpose("2021-03-04").unwrap()).unwrap();
+		assert_eq!(n.woodify().initial_str(), "2021-03-04");
+		assert!(dewoodify::<DateTime<Utc>>(&parse_termpose("yesterday").unwrap()).is_err());
+	}
+	
+	#[cfg(feature = "url")]
+	#[test]
+	fn urls() {
+		let u:Url = dewoodify(&parse_termpose("\"https://example.com:8080/a?b=c\"").unwrap()).unwrap();
+		assert_eq!(u.port(), Some(8080));
+		assert_eq!(u, dewoodify(&parse_termpose(&pretty_termpose(&u.woodify())).unwrap()).unwrap());
+		let e = dewoodify::<Url>(&parse_termpose("\"http://example.com:99999\"").unwrap()).unwrap_err();
+		assert!(e.msg.contains("port"));
+	}
+	
+	#[cfg(feature = "num-bigint")]
+	#[test]
+	fn big_integers() {
+		let w = parse_termpose("-123456789012345678901234567890 98765432109876543210987654321").unwrap();
+		let (a, b):(BigInt, BigUint) = dewoodify(&w).unwrap();
+		assert_eq!((a, b).woodify(), w);
+		assert!(dewoodify::<BigUint>(&parse_termpose("-1").unwrap()).is_err());
+	}
+	
+	#[cfg(feature = "rust_decimal")]
+	#[test]
+	fn decimals() {
+		let w = parse_termpose("1234567890.123456789000").unwrap();
+		let d:Decimal = dewoodify(&w).unwrap();
+		assert_eq!(d.woodify(), w); //trailing zeroes survive
+		assert!(dewoodify::<Decimal>(&parse_termpose("1.2.3").unwrap()).is_err());
+	}
+	
+	#[cfg(feature = "time")]
+	#[test]
+	fn time_datetimes() {
+		let w = parse_termpose("\"2021-03-04T05:06:07.5Z\"").unwrap();
+		let d:OffsetDateTime = dewoodify(&w).unwrap();
+		assert_eq!(d.woodify(), w);
+		assert!(dewoodify::<OffsetDateTime>(&parse_termpose("2021-03-04").unwrap()).is_err());
+	}
+	
+	#[cfg(feature = "indexmap")]
+	#[test]
+	fn index_maps() {
+		let w = parse_termpose("zeta:1 alpha:2 mid:3").unwrap();
+		let m:IndexMap<String, u32> = dewoodify(&w).unwrap();
+		assert_eq!(m.keys().map(|k| k.as_str()).collect::<Vec<_>>(), vec!("zeta", "alpha", "mid"));
+		assert_eq!(m.woodify(), w);
+	}
+}
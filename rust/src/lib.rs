@@ -5,15 +5,53 @@
 // extern crate string_cache;
 // use string_cache::DefaultLeaf as Strin;
 use std::str::FromStr;
+use std::num::{IntErrorKind, NonZeroU8, NonZeroI8, NonZeroU16, NonZeroI16, NonZeroU32, NonZeroI32, NonZeroU64, NonZeroI64, NonZeroU128, NonZeroI128, NonZeroUsize, NonZeroIsize};
 use std::mem::{forget, replace, uninitialized};
-use std::cmp::{PartialEq};
+use std::cmp::{PartialEq, Ordering};
+use std::hash::{Hash, Hasher};
 use std::result::Result;
 use std::error::Error;
 use std::fmt::{Formatter, Display, Debug};
-use std::borrow::{Borrow};
+use std::borrow::{Borrow, Cow};
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut, Index};
+use std::collections::{VecDeque, LinkedList, BinaryHeap};
+use std::rc::Rc;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use std::ptr::{null_mut};
 extern crate ref_slice;
 use ref_slice::ref_slice;
+#[cfg(feature = "uuid")]
+extern crate uuid;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "time")]
+extern crate time;
+#[cfg(feature = "url")]
+extern crate url;
+#[cfg(feature = "num-bigint")]
+extern crate num_bigint;
+#[cfg(feature = "rust_decimal")]
+extern crate rust_decimal;
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "transcode")]
+extern crate serde_transcode;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(feature = "tokio")]
+extern crate futures_core;
+#[cfg(all(test, feature = "serde"))]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(all(test, feature = "transcode"))]
+extern crate serde_json;
 
 
 // pub trait Wood where Self:Sized {
@@ -28,38 +66,169 @@ use ref_slice::ref_slice;
 // 	}
 // }
 
+/// equality is structural: line numbers are not considered important, so wood that was parsed will equal the same wood built in code. See Wood::identical for an equality that does consider them
 impl PartialEq for Wood {
-	fn eq(&self, other: &Self)-> bool { //line numbers are not considered important
+	fn eq(&self, other: &Self)-> bool { self.structurally_equals(other) }
+}
+impl PartialEq for Branch {
+	fn eq(&self, other: &Self)-> bool { self.v == other.v }
+}
+impl Eq for Branch {}
+impl PartialEq for Leaf {
+	fn eq(&self, other: &Self)-> bool { self.v == other.v }
+}
+impl Eq for Leaf {}
+
+//for canonical_hash
+struct FnvHasher(u64);
+impl Hasher for FnvHasher {
+	fn finish(&self)-> u64 { self.0 }
+	fn write(&mut self, bytes:&[u8]) {
+		for b in bytes {
+			self.0 ^= *b as u64;
+			self.0 = self.0.wrapping_mul(0x100000001b3);
+		}
+	}
+}
+
+/// consistent with ==, ignoring positions
+impl Hash for Wood {
+	fn hash<H:Hasher>(&self, state:&mut H) {
 		match *self {
-			Leafv(ref sa)=> {
-				match *other {
-					Leafv(ref so)=> sa.v == so.v,
-					_=> false,
-				}
-			}
-			Branchv(ref la)=> {
-				match *other {
-					Branchv(ref lo)=> la.v == lo.v,
-					_=> false,
-				}
-			}
+			Leafv(ref l)=> { 0u8.hash(state); l.v.hash(state); }
+			Branchv(ref b)=> { 1u8.hash(state); b.v.hash(state); }
 		}
 	}
 }
 
+/// leaves come before branches, leaves are ordered by their strings and branches lexicographically by their contents. Like equality, this ignores line numbers
+impl Ord for Wood {
+	fn cmp(&self, other:&Self)-> Ordering {
+		match (self, other) {
+			(&Leafv(ref a), &Leafv(ref b))=> a.v.cmp(&b.v),
+			(&Leafv(_), &Branchv(_))=> Ordering::Less,
+			(&Branchv(_), &Leafv(_))=> Ordering::Greater,
+			(&Branchv(ref a), &Branchv(ref b))=> a.v.cmp(&b.v),
+		}
+	}
+}
+impl PartialOrd for Wood {
+	fn partial_cmp(&self, other:&Self)-> Option<Ordering> { Some(self.cmp(other)) }
+}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// a Wood equals a str when it's a leaf with that content
+impl PartialEq<str> for Wood {
+	fn eq(&self, other:&str)-> bool {
+		match *self {
+			Leafv(ref l)=> l.v == other,
+			Branchv(_)=> false,
+		}
+	}
+}
+impl<'a> PartialEq<&'a str> for Wood {
+	fn eq(&self, other:&&'a str)-> bool { *self == **other }
+}
+impl PartialEq<String> for Wood {
+	fn eq(&self, other:&String)-> bool { *self == *other.as_str() }
+}
+impl PartialEq<Wood> for str {
+	fn eq(&self, other:&Wood)-> bool { *other == *self }
+}
+impl<'a> PartialEq<Wood> for &'a str {
+	fn eq(&self, other:&Wood)-> bool { *other == **self }
+}
+impl PartialEq<Wood> for String {
+	fn eq(&self, other:&Wood)-> bool { *other == *self.as_str() }
+}
+
+/// the rest of a node's Span, beyond its line and column. Synthetic nodes have the default, which has an end_line and end_column of -1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Extent {
+	pub start: usize,
+	pub end: usize,
+	pub end_line: isize,
+	pub end_column: isize,
+}
+impl Default for Extent {
+	fn default()-> Self { Extent{ start:0, end:0, end_line:-1, end_column:-1 } }
+}
+
+/// the text a node was parsed from. start..end is a byte range into the source, so `&src[s.start..s.end]` is the node's text, quotes and parens included. The lines and columns are those of its first and last characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+	pub start: usize,
+	pub end: usize,
+	pub line: isize,
+	pub column: isize,
+	pub end_line: isize,
+	pub end_column: isize,
+}
+
+/// where a node came from, when its line and column don't say. Nodes built in code have no position, so errors about them would otherwise be impossible to trace
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Provenance {
+	/// derived from a span of some named source, EG, a template file
+	Source{ name:Arc<str>, span:Span },
+	/// built in code, at this file and line. `provenance!()` makes one of these for wherever it's written
+	Synthetic{ file:&'static str, line:u32 },
+}
+impl Display for Provenance {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+		match *self {
+			Provenance::Source{ ref name, ref span }=> write_location(f, Some(name), span.line, span.column),
+			Provenance::Synthetic{ file, line }=> write!(f, "synthetic at {}:{}", file, line),
+		}
+	}
+}
+
+/// `Provenance::Synthetic` for the file and line it's written on
+#[macro_export]
+macro_rules! provenance {
+	()=> { $crate::Provenance::Synthetic{ file:file!(), line:line!() } };
+}
+
+/// what a node has besides its line, column and contents. Nodes built in code usually have none of it, so it's kept out of line, where it only costs a pointer when it isn't there, see Branch::meta. More may be added, so make one from NodeMeta::default()
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct NodeMeta {
+	pub extent: Extent,
+	/// see Wood::provenance
+	pub provenance: Option<Arc<Provenance>>,
+	/// see Wood::comments
+	pub comments: Vec<Comment>,
+	/// set on the placeholders parse_termpose_recovering leaves where there were syntax errors, to the error's message
+	pub unparsed: Option<String>,
+}
+//what meta() gives for nodes that don't have any
+static NO_META:NodeMeta = NodeMeta{ extent:Extent{ start:0, end:0, end_line:-1, end_column:-1 }, provenance:None, comments:Vec::new(), unparsed:None };
+//the meta of a node the parsers are making, which only know the extent to begin with
+fn meta_with_extent(extent:Extent)-> Option<Box<NodeMeta>> { Some(Box::new(NodeMeta{ extent, ..NodeMeta::default() })) }
+
+#[derive(Debug, Clone)]
 pub struct Branch {
 	pub line: isize,
 	pub column: isize,
+	pub meta: Option<Box<NodeMeta>>,
 	pub v: Vec<Wood>,
 }
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct Leaf {
 	pub line: isize,
 	pub column: isize,
+	pub meta: Option<Box<NodeMeta>>,
 	pub v: String,
 }
+macro_rules! do_meta_accessors_for {
+	($($Node:ident),*)=> ($(
+		impl $Node {
+			/// the NodeMeta, or an empty one if there isn't any
+			pub fn meta(&self)-> &NodeMeta { self.meta.as_ref().map_or(&NO_META, |m| &**m) }
+			/// the NodeMeta, which is made if there wasn't any
+			pub fn meta_mut(&mut self)-> &mut NodeMeta { self.meta.get_or_insert_with(Default::default) }
+		}
+	)*)
+}
+do_meta_accessors_for!(Branch, Leaf);
 #[derive(Debug, Clone, Eq)]
 pub enum Wood {
 	Branchv(Branch),
@@ -69,14 +238,22 @@ pub use Wood::*;
 
 
 impl Into<Wood> for String {
-	fn into(self) -> Wood { Leafv(Leaf{ line:-1, column:-1, v:self }) }
+	fn into(self) -> Wood { Leafv(Leaf{ line:-1, column:-1, meta:None, v:self }) }
 }
 impl<'a> Into<Wood> for &'a str {
 	fn into(self) -> Wood { self.to_string().into() }
 }
 impl<'a> Into<Wood> for Vec<Wood> {
-	fn into(self) -> Wood { Branchv(Branch{ line:-1, column:-1, v:self }) }
+	fn into(self) -> Wood { Branchv(Branch{ line:-1, column:-1, meta:None, v:self }) }
 }
+macro_rules! do_from_for_leaf {
+	($($Type:ident),*) => ($(
+		impl From<$Type> for Wood {
+			fn from(v:$Type) -> Wood { v.to_string().into() }
+		}
+	)*)
+}
+do_from_for_leaf!(bool, char, u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64);
 
 fn tail<I:Iterator>(mut v:I)-> I {
 	v.next();
@@ -93,28 +270,146 @@ fn tail<I:Iterator>(mut v:I)-> I {
 // 	}
 // }
 
-fn push_escaped(take:&mut String, give:&str){
+/// which chars the printers write as escapes. Quotes, backslashes, line breaks and tabs always are
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EscapePolicy {
+	/// only those
+	Minimal,
+	/// also control chars and the invisible formatting chars that can make text look like something it isn't, as \xNN or \u{N}
+	Printable,
+	/// everything other than printable ascii
+	Ascii,
+}
+impl Default for EscapePolicy {
+	fn default()-> Self { EscapePolicy::Minimal }
+}
+impl EscapePolicy {
+	/// whether c would be written as a \xNN or \u{N} code
+	pub fn escapes_as_code(self, c:char)-> bool {
+		if c == '\n' || c == '\t' || c == '\r' { return false; }
+		match self {
+			EscapePolicy::Minimal=> false,
+			EscapePolicy::Printable=> c.is_control() || ('\u{200b}'..='\u{200f}').contains(&c) || ('\u{202a}'..='\u{202e}').contains(&c) || ('\u{2066}'..='\u{2069}').contains(&c) || c == '\u{2028}' || c == '\u{2029}' || c == '\u{feff}',
+			EscapePolicy::Ascii=> c.is_control() || !c.is_ascii(),
+		}
+	}
+}
+
+fn push_escaped(take:&mut String, give:&str){ push_escaped_with(take, give, EscapePolicy::Minimal) }
+fn push_escaped_with(take:&mut String, give:&str, policy:EscapePolicy){
 	for c in give.chars() {
 		match c {
 			'\n'=> { take.push('\\'); take.push('n'); }
 			'\t'=> { take.push('\\'); take.push('t'); }
 			'"'=> { take.push('\\'); take.push('"'); }
+			'\r'=> { take.push('\\'); take.push('r'); }
+			'\\'=> { take.push('\\'); take.push('\\'); }
+			c if policy.escapes_as_code(c)=> {
+				if (c as u32) < 0x80 { take.push_str(&format!("\\x{:02X}", c as u32)); }
+				else{ take.push_str(&format!("\\u{{{:X}}}", c as u32)); }
+			}
 			_=> { take.push(c); }
 		}
 	}
 }
 
 impl Wood {
-	pub fn leaf(v:String)-> Wood    { Wood::Leafv(Leaf{line:-1, column:-1, v:v}) }
-	pub fn branch(v:Vec<Wood>)-> Wood { Wood::Branchv(Branch{line:-1, column:-1, v:v}) }
+	pub fn leaf(v:String)-> Wood    { Wood::Leafv(Leaf{line:-1, column:-1, meta:None, v:v}) }
+	pub fn branch(v:Vec<Wood>)-> Wood { Wood::Branchv(Branch{line:-1, column:-1, meta:None, v:v}) }
 	pub fn is_leaf(&self)-> bool { match self { &Leafv(_)=> true, _=> false } }
 	pub fn is_branch(&self)-> bool { match self { &Branchv(_)=> true, _=> false } }
+	/// the same contents in the same shape, regardless of where they came from. This is what == does
+	pub fn structurally_equals(&self, other:&Wood)-> bool {
+		match (self, other) {
+			(&Leafv(ref a), &Leafv(ref b))=> a.v == b.v,
+			(&Branchv(ref a), &Branchv(ref b))=> {
+				a.v.len() == b.v.len() && a.v.iter().zip(b.v.iter()).all(|(x, y)| x.structurally_equals(y))
+			}
+			_=> false,
+		}
+	}
+	/// a digest of the structure and the leaves' contents, which is all that == considers, so it doesn't change with formatting, quoting style or positions. It's 64 bit FNV-1a over an encoding where each leaf is a 0 byte, its length in bytes as a little endian u64 then its bytes, and each branch is a 1 byte, its length as a little endian u64 then its contents. That won't change between versions or platforms, so it's fine to store
+	pub fn canonical_hash(&self)-> u64 {
+		let mut h = FnvHasher(0xcbf29ce484222325);
+		self.canonical_feed(&mut h);
+		h.finish()
+	}
+	fn canonical_feed(&self, h:&mut FnvHasher) {
+		match *self {
+			Leafv(ref l)=> {
+				h.write(&[0]);
+				h.write(&(l.v.len() as u64).to_le_bytes());
+				h.write(l.v.as_bytes());
+			}
+			Branchv(ref b)=> {
+				h.write(&[1]);
+				h.write(&(b.v.len() as u64).to_le_bytes());
+				for c in b.v.iter() { c.canonical_feed(h); }
+			}
+		}
+	}
+	/// structurally equal, and with all of the same spans
+	pub fn identical(&self, other:&Wood)-> bool {
+		self.span() == other.span() && match (self, other) {
+			(&Leafv(ref a), &Leafv(ref b))=> a.v == b.v,
+			(&Branchv(ref a), &Branchv(ref b))=> {
+				a.v.len() == b.v.len() && a.v.iter().zip(b.v.iter()).all(|(x, y)| x.identical(y))
+			}
+			_=> false,
+		}
+	}
 	pub fn line_and_col(&self)-> (isize, isize) {
 		match *self {
 			Wood::Branchv(ref l)=> (l.line, l.column),
 			Wood::Leafv(ref a)=> (a.line, a.column),
 		}
 	}
+	pub fn span(&self)-> Span {
+		let (line, column, e) = match *self {
+			Branchv(ref b)=> (b.line, b.column, b.meta().extent),
+			Leafv(ref l)=> (l.line, l.column, l.meta().extent),
+		};
+		Span{ start:e.start, end:e.end, line, column, end_line:e.end_line, end_column:e.end_column }
+	}
+	/// the node's NodeMeta, or an empty one if there isn't any
+	pub fn meta(&self)-> &NodeMeta {
+		match *self {
+			Branchv(ref b)=> b.meta(),
+			Leafv(ref l)=> l.meta(),
+		}
+	}
+	/// the node's NodeMeta, which is made if there wasn't any
+	pub fn meta_mut(&mut self)-> &mut NodeMeta {
+		match *self {
+			Branchv(ref mut b)=> b.meta_mut(),
+			Leafv(ref mut l)=> l.meta_mut(),
+		}
+	}
+	pub fn provenance(&self)-> Option<&Provenance> {
+		self.provenance_arc().map(|p| &**p)
+	}
+	/// whether this stands in for text that couldn't be parsed, see parse_termpose_recovering
+	pub fn is_error_placeholder(&self)-> bool { self.meta().unparsed.is_some() }
+	/// provenance, shared rather than borrowed, for errors that have to outlive the wood, see DewoodifyError::with_provenance
+	pub fn provenance_arc(&self)-> Option<&Arc<Provenance>> {
+		self.meta().provenance.as_ref()
+	}
+	/// gives p to this node and every node within it that has neither a position nor a provenance of its own, so parsed wood spliced into built wood stays where it was
+	pub fn with_provenance(mut self, p:Provenance)-> Wood {
+		self.fill_provenance(&Arc::new(p));
+		self
+	}
+	fn fill_provenance(&mut self, p:&Arc<Provenance>) {
+		match *self {
+			Branchv(ref mut b)=> {
+				if b.meta().provenance.is_none() && b.line == -1 { b.meta_mut().provenance = Some(p.clone()); }
+				for c in b.v.iter_mut() { c.fill_provenance(p); }
+			}
+			Leafv(ref mut l)=> {
+				if l.meta().provenance.is_none() && l.line == -1 { l.meta_mut().provenance = Some(p.clone()); }
+			}
+		}
+	}
 	pub fn initial_str(&self)-> &str { //if it bottoms out at an empty branch, it returns the empty str
 		match *self {
 			Branchv(ref v)=> {
@@ -127,10 +422,6 @@ impl Wood {
 			Leafv(ref v)=> v.v.as_str(),
 		}
 	}
-	pub fn to_string(&self)-> String {
-		to_woodslist(self)
-	}
-	
 	pub fn contents(&self)-> std::slice::Iter<Self> { //if Leaf, returns a slice iter of a single element that is the leaf's str
 		match *self.borrow() {
 			Branchv(ref v)=> v.v.iter(),
@@ -146,51 +437,441 @@ impl Wood {
 	pub fn find(&self, key:&str)-> Option<&Wood> {
 		self.contents().find(|el| el.initial_str() == key)
 	}
+	/// the ith element of contents(), so a leaf has itself at 0
+	pub fn get(&self, i:usize)-> Option<&Wood> {
+		self.contents().as_slice().get(i)
+	}
+	/// the first element of contents() whose initial_str is key, same as find
+	pub fn get_key(&self, key:&str)-> Option<&Wood> {
+		self.find(key)
+	}
+	pub fn branch_builder()-> WoodBuilder { WoodBuilder::new() }
+	
+	/// follows a path of keys separated by dots or slashes, EG, `"server.tls.cert"`, down through nested branches. The first key is looked for among the contents, like find, and each later key among the tail of what the previous one found. The result is the `(key …)` branch of the last key, just as find would return it
+	pub fn get_path(&self, path:&str)-> Result<&Wood, PathNotFound> {
+		let mut at = self;
+		for (segment, key) in path.split(|c| c == '.' || c == '/').enumerate() {
+			let found = if segment == 0 {
+				at.find(key)
+			}else{
+				at.tail().find(|el| el.initial_str() == key)
+			};
+			match found {
+				Some(f)=> at = f,
+				None=> {
+					let (line, column) = at.line_and_col();
+					return Err(PathNotFound{ segment, key:key.to_string(), line, column });
+				}
+			}
+		}
+		Ok(at)
+	}
+	
+	/// the value of the `(key value)` pair among the contents
+	pub fn get_child(&self, key:&str)-> Result<&Wood, DewoodifyError> {
+		match self.find(key) {
+			Some(pair)=> {
+				pair.tail().next().ok_or_else(|| DewoodifyError::new(pair, format!("\"{}\" has no value", key)))
+			}
+			None=> Err(DewoodifyError::new(self, format!("there is no \"{}\" here", key))),
+		}
+	}
+	/// dewoodifies the value of the `(key value)` pair among the contents
+	pub fn get_as<T>(&self, key:&str)-> Result<T, DewoodifyError> where T: Dewoodable {
+		T::dewoodify(self.get_child(key)?)
+	}
+	pub fn get_str(&self, key:&str)-> Result<&str, DewoodifyError> {
+		let v = self.get_child(key)?;
+		match *v {
+			Leafv(ref l)=> Ok(&l.v),
+			Branchv(_)=> Err(DewoodifyError::new(v, format!("expected \"{}\" to be a leaf, but it's a branch", key))),
+		}
+	}
+	pub fn get_i64(&self, key:&str)-> Result<i64, DewoodifyError> { self.get_as(key) }
+	pub fn get_f64(&self, key:&str)-> Result<f64, DewoodifyError> { self.get_as(key) }
+	pub fn get_bool(&self, key:&str)-> Result<bool, DewoodifyError> { self.get_as(key) }
+	
+	pub fn find_mut(&mut self, key:&str)-> Option<&mut Wood> {
+		match *self {
+			Branchv(ref mut b)=> b.v.iter_mut().find(|el| el.initial_str() == key),
+			Leafv(ref l)=> if l.v == key { Some(self) } else { None },
+		}
+	}
+	pub fn tail_mut<'b>(&'b mut self)-> std::slice::IterMut<'b, Self> { //if Leaf, returns an empty slice iter
+		match *self {
+			Branchv(ref mut v)=> tail(v.v.iter_mut()),
+			Leafv(_)=> [].iter_mut(),
+		}
+	}
+	/// moves everything after the first element out of a branch. If Leaf, yields nothing
+	pub fn drain_tail<'b>(&'b mut self)-> DrainTail<'b> {
+		match *self {
+			Branchv(ref mut b) if b.v.len() > 1 => DrainTail{ inner:Some(b.v.drain(1..)) },
+			_=> DrainTail{ inner:None },
+		}
+	}
+	//a leaf is treated as a branch containing just itself, as in contents(). This makes that so, keeping the position
+	fn as_branch_mut(&mut self)-> &mut Vec<Wood> {
+		if let Leafv(ref l) = *self {
+			let (line, column) = (l.line, l.column);
+			let meta = Some(Box::new(NodeMeta{ extent:l.meta().extent, provenance:l.meta().provenance.clone(), ..NodeMeta::default() }));
+			let leaf = replace(self, Wood::branch(Vec::new()));
+			*self = Branchv(Branch{ line, column, meta, v:vec!(leaf) });
+		}
+		match *self {
+			Branchv(ref mut b)=> &mut b.v,
+			Leafv(_)=> unreachable!(),
+		}
+	}
+	/// appends a child. A leaf becomes a branch of itself followed by the child
+	pub fn push_child(&mut self, v:Wood) {
+		self.as_branch_mut().push(v);
+	}
+	/// inserts a child at position i, panicking if i > the number of children. A leaf is treated as a branch containing just itself
+	pub fn insert_child(&mut self, i:usize, v:Wood) {
+		self.as_branch_mut().insert(i, v);
+	}
+	/// removes and returns the child at position i, panicking if it's out of bounds. A leaf is treated as a branch containing just itself
+	pub fn remove_child(&mut self, i:usize)-> Wood {
+		self.as_branch_mut().remove(i)
+	}
+	/// replaces whatever is found by following the path of keys with find_mut, returning what was there, or None if the path leads nowhere. An empty path replaces self
+	pub fn replace(&mut self, path:&[&str], v:Wood)-> Option<Wood> {
+		let mut at = self;
+		for k in path {
+			at = at.find_mut(k)?;
+		}
+		Some(replace(at, v))
+	}
+	/// sets the str that initial_str would return. If it bottoms out at an empty branch, a leaf is added there
+	pub fn set_initial(&mut self, v:&str) {
+		match *self {
+			Branchv(ref mut b)=> {
+				if let Some(first) = b.v.first_mut() {
+					first.set_initial(v);
+				}else{
+					b.v.push(v.into());
+				}
+			}
+			Leafv(ref mut l)=> {
+				l.v.clear();
+				l.v.push_str(v);
+			}
+		}
+	}
+}
+
+/// the ith element of contents(), panicking if there isn't one. See Wood::get for a version that doesn't panic
+impl Index<usize> for Wood {
+	type Output = Wood;
+	fn index(&self, i:usize)-> &Wood {
+		match self.get(i) {
+			Some(w)=> w,
+			None=> panic!("there's no element {} in {}", i, self.to_string()),
+		}
+	}
+}
+/// the first element of contents() whose initial_str is key, panicking if there isn't one. See Wood::get_key for a version that doesn't panic
+impl<'a> Index<&'a str> for Wood {
+	type Output = Wood;
+	fn index(&self, key:&str)-> &Wood {
+		match self.get_key(key) {
+			Some(w)=> w,
+			None=> panic!("there's nothing called \"{}\" in {}", key, self.to_string()),
+		}
+	}
+}
+
+/// see Wood::drain_tail
+pub struct DrainTail<'a> {
+	inner: Option<std::vec::Drain<'a, Wood>>,
+}
+impl<'a> Iterator for DrainTail<'a> {
+	type Item = Wood;
+	fn next(&mut self)-> Option<Wood> { self.inner.as_mut().and_then(|d| d.next()) }
+	fn size_hint(&self)-> (usize, Option<usize>) { self.inner.as_ref().map_or((0, Some(0)), |d| d.size_hint()) }
+}
+
+/// moves the contents out. As with contents(), a leaf yields just itself
+impl IntoIterator for Wood {
+	type Item = Wood;
+	type IntoIter = std::vec::IntoIter<Wood>;
+	fn into_iter(self)-> Self::IntoIter {
+		match self {
+			Branchv(b)=> b.v.into_iter(),
+			l@Leafv(_)=> vec!(l).into_iter(),
+		}
+	}
+}
+impl<'a> IntoIterator for &'a Wood {
+	type Item = &'a Wood;
+	type IntoIter = std::slice::Iter<'a, Wood>;
+	fn into_iter(self)-> Self::IntoIter { self.contents() }
+}
+
+/// parses termpose, see parse_termpose
+impl FromStr for Wood {
+	type Err = PositionedError;
+	fn from_str(s:&str)-> Result<Wood, PositionedError> { parse_termpose(s) }
+}
+
+/// collects into a branch
+impl FromIterator<Wood> for Wood {
+	fn from_iter<I>(v:I)-> Wood where I: IntoIterator<Item=Wood> { Wood::branch(v.into_iter().collect()) }
+}
+/// collects into a branch of leaves
+impl<'a> FromIterator<&'a str> for Wood {
+	fn from_iter<I>(v:I)-> Wood where I: IntoIterator<Item=&'a str> { Wood::branch(v.into_iter().map(|s| s.into()).collect()) }
+}
+/// appends children. As with push_child, a leaf becomes a branch of itself followed by them
+impl Extend<Wood> for Wood {
+	fn extend<I>(&mut self, v:I) where I: IntoIterator<Item=Wood> { self.as_branch_mut().extend(v) }
+}
+
+/// assembles a branch one element at a time, EG, `Wood::branch_builder().leaf("server").pair("port", 8080).build()` is `server port:8080`
+#[derive(Debug, Clone, Default)]
+pub struct WoodBuilder {
+	v: Vec<Wood>,
+	provenance: Option<Provenance>,
+}
+impl WoodBuilder {
+	pub fn new()-> Self { WoodBuilder{ v:Vec::new(), provenance:None } }
+	/// what build will give to the branch and to everything in it that doesn't have its own, EG, `.provenance(provenance!())`
+	pub fn provenance(mut self, p:Provenance)-> Self {
+		self.provenance = Some(p);
+		self
+	}
+	pub fn leaf<S>(mut self, v:S)-> Self where S: Into<String> {
+		self.v.push(Wood::leaf(v.into()));
+		self
+	}
+	/// appends the woodification of v
+	pub fn value<T>(mut self, v:T)-> Self where T: Woodable {
+		self.v.push(v.woodify());
+		self
+	}
+	/// appends `(key v)`
+	pub fn pair<T>(mut self, key:&str, v:T)-> Self where T: Woodable {
+		self.v.push(branch!(key, v.woodify()));
+		self
+	}
+	/// appends a Wood, or another builder's branch
+	pub fn child<W>(mut self, v:W)-> Self where W: Into<Wood> {
+		self.v.push(v.into());
+		self
+	}
+	/// appends the woodification of every element
+	pub fn values<I>(mut self, v:I)-> Self where I: IntoIterator, I::Item: Woodable {
+		self.v.extend(v.into_iter().map(|e| e.woodify()));
+		self
+	}
+	pub fn build(self)-> Wood {
+		let w:Wood = self.v.into();
+		match self.provenance {
+			Some(p)=> w.with_provenance(p),
+			None=> w,
+		}
+	}
+}
+impl Into<Wood> for WoodBuilder {
+	fn into(self) -> Wood { self.build() }
 }
 
 #[macro_export]
 macro_rules! branch {
 	($($inner:expr),* $(,)*)=> {{
-		$crate::Branchv($crate::Branch{line:-1, column:-1, v:vec!($($inner.into()),*)})
+		$crate::Branchv($crate::Branch{line:-1, column:-1, meta:None, v:vec!($($inner.into()),*)})
+	}};
+}
+
+/// builds wood the way it would be written in termpose, EG, `wood!{ server (host "example.com") (port {port}) (tags ..{tags}) }`.
+/// Bare words and literals become leaves, parens become branches, `{expr}` woodifies the value of an expression in place and `..{expr}` woodifies every element of an iterable, splicing them in. When there's more than one thing at the top level, they're wrapped in a branch, as they would be on a line of termpose
+#[macro_export]
+macro_rules! wood {
+	(@push $v:ident)=> {};
+	(@push $v:ident .. {$e:expr} $($rest:tt)*)=> {
+		for el in $e { $v.push($crate::Woodable::woodify(&el)); }
+		$crate::wood!(@push $v $($rest)*);
+	};
+	(@push $v:ident {$e:expr} $($rest:tt)*)=> {
+		$v.push($crate::Woodable::woodify(&$e));
+		$crate::wood!(@push $v $($rest)*);
+	};
+	(@push $v:ident ($($inner:tt)*) $($rest:tt)*)=> {
+		$v.push($crate::wood!(@branch $($inner)*));
+		$crate::wood!(@push $v $($rest)*);
+	};
+	(@push $v:ident $l:literal $($rest:tt)*)=> {
+		$v.push($crate::Woodable::woodify(&$l));
+		$crate::wood!(@push $v $($rest)*);
+	};
+	(@push $v:ident $w:ident $($rest:tt)*)=> {
+		$v.push($crate::Wood::leaf(stringify!($w).to_string()));
+		$crate::wood!(@push $v $($rest)*);
+	};
+	(@push $v:ident $bad:tt $($rest:tt)*)=> {
+		compile_error!(concat!("wood! can't make a leaf out of `", stringify!($bad), "`, try putting it in quotes"))
+	};
+	(@branch $($inner:tt)*)=> {{
+		#[allow(unused_mut)]
+		let mut v:Vec<$crate::Wood> = Vec::new();
+		$crate::wood!(@push v $($inner)*);
+		$crate::Wood::branch(v)
 	}};
+	//a single item stands alone
+	(@top {$e:expr})=> { $crate::Woodable::woodify(&$e) };
+	(@top ($($inner:tt)*))=> { $crate::wood!(@branch $($inner)*) };
+	(@top $l:literal)=> { $crate::Woodable::woodify(&$l) };
+	(@top $w:ident)=> { $crate::Wood::leaf(stringify!($w).to_string()) };
+	(@top $($all:tt)*)=> { $crate::wood!(@branch $($all)*) };
+	($($all:tt)*)=> { $crate::Wood::with_provenance($crate::wood!(@top $($all)*), $crate::provenance!()) };
+}
+
+/// matches wood against a shape and evaluates an expression with what it bound, EG, `wood_match!(w, ("point" x y) => Point{ x, y })`, returning a DewoodifyError if the wood doesn't have that shape.
+/// In a shape, parens match a branch with exactly that many elements, literals match leaves with the same text, `_` matches anything, a name binds a leaf parsed with FromStr (the type is inferred from how it's used), `&name` binds the subwood itself, and `..` at the end of a branch allows more elements, which `..name` binds as a slice
+#[macro_export]
+macro_rules! wood_match {
+	($w:expr, $pat:tt => $body:expr)=> {
+		(|| -> ::std::result::Result<_, $crate::DewoodifyError> {
+			let matching:&$crate::Wood = &$w;
+			$crate::wood_match!(@one matching, $pat);
+			Ok($body)
+		})()
+	};
+	(@one $w:expr, _)=> {};
+	(@one $w:expr, ($($items:tt)*))=> {
+		let node:&$crate::Wood = $w;
+		let items:&[$crate::Wood] = match *node {
+			$crate::Branchv(ref b)=> &b.v,
+			$crate::Leafv(_)=> return Err($crate::DewoodifyError::new(node, concat!("expected a branch like ", stringify!(($($items)*))).to_string())),
+		};
+		$crate::wood_match!(@items node, items, 0usize; $($items)*);
+	};
+	(@one $w:expr, $l:literal)=> {
+		let node:&$crate::Wood = $w;
+		if *node != $crate::Woodable::woodify(&$l) {
+			return Err($crate::DewoodifyError::new(node, concat!("expected ", stringify!($l), " here").to_string()));
+		}
+	};
+	(@one $w:expr, $x:ident)=> {
+		let $x = $crate::parse_leaf($w)?;
+	};
+	(@items $w:expr, $s:ident, $i:expr;)=> {
+		if $s.len() != $i {
+			return Err($crate::DewoodifyError::new($w, format!("expected {} elements, but there were {}", $i, $s.len())));
+		}
+	};
+	(@items $w:expr, $s:ident, $i:expr; ..)=> {};
+	(@items $w:expr, $s:ident, $i:expr; .. $rest:ident)=> {
+		let $rest:&[$crate::Wood] = &$s[$i..];
+	};
+	(@items $w:expr, $s:ident, $i:expr; & $x:ident $($more:tt)*)=> {
+		let $x:&$crate::Wood = $crate::wood_match!(@get $w, $s, $i);
+		$crate::wood_match!(@items $w, $s, $i + 1; $($more)*);
+	};
+	(@items $w:expr, $s:ident, $i:expr; $p:tt $($more:tt)*)=> {
+		$crate::wood_match!(@one $crate::wood_match!(@get $w, $s, $i), $p);
+		$crate::wood_match!(@items $w, $s, $i + 1; $($more)*);
+	};
+	(@get $w:expr, $s:ident, $i:expr)=> {
+		match $s.get($i) {
+			Some(e)=> e,
+			None=> return Err($crate::DewoodifyError::new($w, format!("expected at least {} elements, but there were {}", $i + 1, $s.len()))),
+		}
+	};
 }
 
 
 
-pub trait Wooder<T> {
+pub trait Wooder<T:?Sized> {
 	fn woodify(&self, v:&T) -> Wood;
 }
 pub trait Dewooder<T> {
 	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError>;
 }
 
+/// made with DewoodifyError::new, new_with_cause or located, as there may be more to it later
 #[derive(Debug)]
+#[non_exhaustive]
 pub struct DewoodifyError{
 	pub line:isize,
 	pub column:isize,
 	pub msg:String,
 	pub cause:Option<Box<Error>>,
+	/// that of the wood the error is about, if it had one
+	pub provenance:Option<Arc<Provenance>>,
+	/// the file the wood was parsed from, if it was read by deserialize_file
+	pub file:Option<Arc<str>>,
 }
 impl Display for DewoodifyError {
 	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-		Debug::fmt(self, f)
+		match self.file {
+			Some(ref file)=> write_located(f, Some(file), self.line, self.column, &self.msg),
+			None=> Debug::fmt(self, f),
+		}
+	}
+}
+//file:line:column, with the line counted from 1 the way editors count them. Whichever of the file and the position (line -1) isn't known is left out
+fn write_location(f:&mut Formatter, file:Option<&str>, line:isize, column:isize)-> Result<(), std::fmt::Error> {
+	match (file, line >= 0) {
+		(Some(file), true)=> write!(f, "{}:{}:{}", file, line + 1, column),
+		(Some(file), false)=> f.write_str(file),
+		(None, true)=> write!(f, "{}:{}", line + 1, column),
+		(None, false)=> Ok(()),
 	}
 }
+//file:line:column: msg, or as much of the location as is known
+fn write_located(f:&mut Formatter, file:Option<&str>, line:isize, column:isize, msg:&str)-> Result<(), std::fmt::Error> {
+	write_location(f, file, line, column)?;
+	if file.is_some() || line >= 0 { f.write_str(": ")?; }
+	f.write_str(msg)
+}
 impl DewoodifyError {
+	/// the error, as being about the named file, which it will be displayed with
+	pub fn in_file(self, file:Arc<str>)-> Self { DewoodifyError{ file:Some(file), ..self } }
 	pub fn new(source: &Wood, msg:String) -> Self {
 		let (line, column) = source.line_and_col();
-		Self{ line, column, msg, cause:None }
+		Self{ line, column, msg, cause:None, provenance:source.provenance_arc().cloned(), file:None }
 	}
 	pub fn new_with_cause(source:&Wood, msg:String, cause:Option<Box<Error>>) -> Self {
 		let (line, column) = source.line_and_col();
-		DewoodifyError{ line, column, msg, cause }
+		DewoodifyError{ line, column, msg, cause, provenance:source.provenance_arc().cloned(), file:None }
+	}
+	/// an error at the given position, with no wood to take it from. -1s if it isn't about anywhere in particular
+	pub fn located(line:isize, column:isize, msg:String)-> Self {
+		DewoodifyError{ line, column, msg, cause:None, provenance:None, file:None }
 	}
+	pub fn with_cause(self, cause:Box<Error>)-> Self { DewoodifyError{ cause:Some(cause), ..self } }
+	pub fn with_provenance(self, provenance:Option<Arc<Provenance>>)-> Self { DewoodifyError{ provenance, ..self } }
 }
 
 impl Error for DewoodifyError {
 	fn description(&self) -> &str { self.msg.as_str() }
 	fn cause(&self) -> Option<&Error> { self.cause.as_ref().map(|e| e.as_ref()) }
 }
+
+/// from get_path, when one of the path's segments couldn't be found. segment counts from 0, and line and column are those of the wood that was being searched when it wasn't found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathNotFound{
+	pub segment:usize,
+	pub key:String,
+	pub line:isize,
+	pub column:isize,
+}
+impl Display for PathNotFound {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+		write_located(f, None, self.line, self.column, &format!("nothing named \"{}\" was found at segment {} of the path", self.key, self.segment))
+	}
+}
+impl Error for PathNotFound {
+	fn description(&self) -> &str { "path not found" }
+}
+impl From<PathNotFound> for DewoodifyError {
+	fn from(e:PathNotFound)-> Self {
+		let msg = format!("nothing named \"{}\" was found at segment {} of the path", e.key, e.segment);
+		DewoodifyError::located(e.line, e.column, msg)
+	}
+}
 pub trait Woodable {
 	fn woodify(&self) -> Wood;
 }
@@ -204,11 +885,40 @@ pub fn woodify<T>(v:&T) -> Wood where T: Woodable {
 pub fn dewoodify<T>(v:&Wood) -> Result<T, DewoodifyError> where T: Dewoodable {
 	T::dewoodify(v)
 }
+/// parses a leaf with FromStr, for types that don't implement Dewoodable
+pub fn parse_leaf<T>(v:&Wood) -> Result<T, DewoodifyError> where T: FromStr, T::Err: Error + 'static {
+	match *v {
+		Leafv(ref l)=> l.v.parse().map_err(|er| {
+			DewoodifyError::new_with_cause(v, format!("couldn't parse \"{}\"", l.v), Some(Box::new(er)))
+		}),
+		Branchv(_)=> Err(DewoodifyError::new(v, "expected a leaf, but found a branch".into())),
+	}
+}
 
 #[derive(Debug)]
 pub enum WoodError{
 	ParserError(PositionedError),
 	DewoodifyError(DewoodifyError),
+	IoError(std::io::Error),
+}
+impl Display for WoodError {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+		match *self {
+			WoodError::ParserError(ref e)=> Display::fmt(e, f),
+			WoodError::DewoodifyError(ref e)=> Display::fmt(e, f),
+			WoodError::IoError(ref e)=> Display::fmt(e, f),
+		}
+	}
+}
+impl Error for WoodError {
+	fn description(&self) -> &str { "couldn't read the wood" }
+	fn cause(&self) -> Option<&Error> {
+		match *self {
+			WoodError::ParserError(ref e)=> Some(e),
+			WoodError::DewoodifyError(ref e)=> Some(e),
+			WoodError::IoError(ref e)=> Some(e),
+		}
+	}
 }
 
 pub fn deserialize<T>(v:&str) -> Result<T, WoodError> where T : Dewoodable {
@@ -217,9 +927,26 @@ pub fn deserialize<T>(v:&str) -> Result<T, WoodError> where T : Dewoodable {
 		Err(e)=> Err(WoodError::ParserError(e)),
 	}
 }
+/// reads and parses a termpose file, as parse_termpose would. Errors carry the path, so they display as `path:line:column: message`
+pub fn parse_file<P:AsRef<Path>>(path:P) -> Result<Wood, WoodError> {
+	let path = path.as_ref();
+	let name:Arc<str> = path.display().to_string().into();
+	let text = std::fs::read_to_string(path).map_err(|e| WoodError::IoError(std::io::Error::new(e.kind(), format!("{}: {}", name, e))))?;
+	parse_termpose(&text).map_err(|e| WoodError::ParserError(e.in_file(name)))
+}
+/// deserialize, for a file. Errors carry the path, as in parse_file
+pub fn deserialize_file<T, P>(path:P) -> Result<T, WoodError> where T: Dewoodable, P: AsRef<Path> {
+	let path = path.as_ref();
+	let w = parse_file(path)?;
+	dewoodify(&w).map_err(|e| WoodError::DewoodifyError(e.in_file(path.display().to_string().into())))
+}
 pub fn serialize<T>(v:&T) -> String where T: Woodable {
 	woodify(v).to_string()
 }
+/// serialize, written to out as it goes rather than built up into a String first, see Wood::write_to
+pub fn serialize_to_writer<T, W>(v:&T, out:&mut W) -> std::io::Result<()> where T: Woodable, W: std::io::Write + ?Sized {
+	woodify(v).write_to(out)
+}
 
 macro_rules! do_basic_stringifying_woodable_for {
 	($Type:ident) => (
@@ -233,7 +960,23 @@ macro_rules! do_basic_destringifying_dewoodable_for {
 		impl Dewoodable for $Type {
 			fn dewoodify(v:&Wood) -> Result<$Type, DewoodifyError> {
 				$Type::from_str(v.initial_str()).map_err(|er|{
-					DewoodifyError::new_with_cause(v, format!("couldn't parse {}", stringify!($name)), Some(Box::new(er)))
+					DewoodifyError::new_with_cause(v, format!("couldn't parse {}", stringify!($Type)), Some(Box::new(er)))
+				})
+			}
+		}
+	)
+}
+macro_rules! do_integer_dewoodable_for {
+	($Type:ident) => (
+		impl Dewoodable for $Type {
+			fn dewoodify(v:&Wood) -> Result<$Type, DewoodifyError> {
+				let s = v.initial_str();
+				$Type::from_str(s).map_err(|er|{
+					let msg = match *er.kind() {
+						IntErrorKind::PosOverflow | IntErrorKind::NegOverflow=> format!("{} is out of range for {} ({} to {})", s, stringify!($Type), $Type::MIN, $Type::MAX),
+						_=> format!("couldn't parse {}", stringify!($Type)),
+					};
+					DewoodifyError::new_with_cause(v, msg, Some(Box::new(er)))
 				})
 			}
 		}
@@ -242,22 +985,75 @@ macro_rules! do_basic_destringifying_dewoodable_for {
 
 do_basic_stringifying_woodable_for!(char);
 do_basic_destringifying_dewoodable_for!(char);
+do_basic_stringifying_woodable_for!(u8);
+do_integer_dewoodable_for!(u8);
+do_basic_stringifying_woodable_for!(i8);
+do_integer_dewoodable_for!(i8);
+do_basic_stringifying_woodable_for!(u16);
+do_integer_dewoodable_for!(u16);
+do_basic_stringifying_woodable_for!(i16);
+do_integer_dewoodable_for!(i16);
 do_basic_stringifying_woodable_for!(u32);
-do_basic_destringifying_dewoodable_for!(u32);
+do_integer_dewoodable_for!(u32);
 do_basic_stringifying_woodable_for!(u64);
-do_basic_destringifying_dewoodable_for!(u64);
+do_integer_dewoodable_for!(u64);
 do_basic_stringifying_woodable_for!(i32);
-do_basic_destringifying_dewoodable_for!(i32);
+do_integer_dewoodable_for!(i32);
 do_basic_stringifying_woodable_for!(i64);
-do_basic_destringifying_dewoodable_for!(i64);
+do_integer_dewoodable_for!(i64);
+do_basic_stringifying_woodable_for!(u128);
+do_integer_dewoodable_for!(u128);
+do_basic_stringifying_woodable_for!(i128);
+do_integer_dewoodable_for!(i128);
 do_basic_stringifying_woodable_for!(f32);
 do_basic_destringifying_dewoodable_for!(f32);
 do_basic_stringifying_woodable_for!(f64);
 do_basic_destringifying_dewoodable_for!(f64);
 do_basic_stringifying_woodable_for!(isize);
-do_basic_destringifying_dewoodable_for!(isize);
+do_integer_dewoodable_for!(isize);
 do_basic_stringifying_woodable_for!(usize);
-do_basic_destringifying_dewoodable_for!(usize);
+do_integer_dewoodable_for!(usize);
+
+macro_rules! do_nonzero_woodable_and_dewoodable_for {
+	($Type:ident, $Inner:ident) => (
+		impl Woodable for $Type {
+			fn woodify(&self) -> Wood { self.get().woodify() }
+		}
+		impl Dewoodable for $Type {
+			fn dewoodify(v:&Wood) -> Result<$Type, DewoodifyError> {
+				$Type::new($Inner::dewoodify(v)?).ok_or_else(||{
+					DewoodifyError::new(v, format!("{} must not be zero", stringify!($Type)))
+				})
+			}
+		}
+	)
+}
+
+do_nonzero_woodable_and_dewoodable_for!(NonZeroU8, u8);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroI8, i8);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroU16, u16);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroI16, i16);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroU32, u32);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroI32, i32);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroU64, u64);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroI64, i64);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroU128, u128);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroI128, i128);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroUsize, usize);
+do_nonzero_woodable_and_dewoodable_for!(NonZeroIsize, isize);
+
+do_basic_stringifying_woodable_for!(IpAddr);
+do_basic_destringifying_dewoodable_for!(IpAddr);
+do_basic_stringifying_woodable_for!(Ipv4Addr);
+do_basic_destringifying_dewoodable_for!(Ipv4Addr);
+do_basic_stringifying_woodable_for!(Ipv6Addr);
+do_basic_destringifying_dewoodable_for!(Ipv6Addr);
+do_basic_stringifying_woodable_for!(SocketAddr);
+do_basic_destringifying_dewoodable_for!(SocketAddr);
+do_basic_stringifying_woodable_for!(SocketAddrV4);
+do_basic_destringifying_dewoodable_for!(SocketAddrV4);
+do_basic_stringifying_woodable_for!(SocketAddrV6);
+do_basic_destringifying_dewoodable_for!(SocketAddrV6);
 
 do_basic_stringifying_woodable_for!(bool);
 impl Dewoodable for bool {
@@ -274,10 +1070,81 @@ impl Dewoodable for bool {
 	}
 }
 
+/// the unit type and PhantomData carry no information, so they woodify as an empty branch and will dewoodify from anything
+impl Woodable for () {
+	fn woodify(&self) -> Wood { branch!() }
+}
+impl Dewoodable for () {
+	fn dewoodify(_v:&Wood) -> Result<(), DewoodifyError> { Ok(()) }
+}
+impl<T> Woodable for PhantomData<T> where T:?Sized {
+	fn woodify(&self) -> Wood { branch!() }
+}
+impl<T> Dewoodable for PhantomData<T> where T:?Sized {
+	fn dewoodify(_v:&Wood) -> Result<PhantomData<T>, DewoodifyError> { Ok(PhantomData) }
+}
+
+macro_rules! do_tuple_woodable_and_dewoodable_for {
+	($arity:expr; $($T:ident $i:tt),*) => (
+		impl<$($T),*> Woodable for ($($T,)*) where $($T:Woodable),* {
+			fn woodify(&self) -> Wood {
+				branch!($(self.$i.woodify()),*)
+			}
+		}
+		impl<$($T),*> Dewoodable for ($($T,)*) where $($T:Dewoodable),* {
+			fn dewoodify(v:&Wood) -> Result<Self, DewoodifyError> {
+				let li = v.contents().as_slice();
+				if li.len() != $arity {
+					return Err(DewoodifyError::new(v, format!("expected a tuple of {} elements, but there are {}", $arity, li.len())));
+				}
+				Ok(($(
+					$T::dewoodify(&li[$i]).map_err(|e|{
+						DewoodifyError::new_with_cause(&li[$i], format!("element {} of the {}-tuple couldn't be dewoodified: {}", $i, $arity, e.msg), Some(Box::new(e)))
+					})?,
+				)*))
+			}
+		}
+		impl<$($T),*> DewoodableOwned for ($($T,)*) where $($T:DewoodableOwned),* {
+			fn dewoodify_owned(v:Wood) -> Result<Self, DewoodifyError> {
+				let len = v.contents().len();
+				if len != $arity {
+					return Err(DewoodifyError::new(&v, format!("expected a tuple of {} elements, but there are {}", $arity, len)));
+				}
+				let mut li = v.into_iter();
+				Ok(($({
+					let w = li.next().unwrap();
+					let ((line, column), provenance) = (w.line_and_col(), w.provenance_arc().cloned());
+					$T::dewoodify_owned(w).map_err(|e|{
+						DewoodifyError::located(line, column, format!("element {} of the {}-tuple couldn't be dewoodified: {}", $i, $arity, e.msg)).with_provenance(provenance).with_cause(Box::new(e))
+					})?
+				},)*))
+			}
+		}
+	)
+}
+
+do_tuple_woodable_and_dewoodable_for!(1; A 0);
+do_tuple_woodable_and_dewoodable_for!(2; A 0, B 1);
+do_tuple_woodable_and_dewoodable_for!(3; A 0, B 1, C 2);
+do_tuple_woodable_and_dewoodable_for!(4; A 0, B 1, C 2, D 3);
+do_tuple_woodable_and_dewoodable_for!(5; A 0, B 1, C 2, D 3, E 4);
+do_tuple_woodable_and_dewoodable_for!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+do_tuple_woodable_and_dewoodable_for!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+do_tuple_woodable_and_dewoodable_for!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+do_tuple_woodable_and_dewoodable_for!(9; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+do_tuple_woodable_and_dewoodable_for!(10; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+do_tuple_woodable_and_dewoodable_for!(11; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+do_tuple_woodable_and_dewoodable_for!(12; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
 // impl<I, T> Woodable for I where I: Iterator<Item=T>, T:Woodable {
 	
 // }
 
+impl Woodable for str {
+	fn woodify(&self) -> Wood {
+		self.into()
+	}
+}
 impl Woodable for String {
 	fn woodify(&self) -> Wood {
 		self.as_str().into()
@@ -292,6 +1159,117 @@ impl Dewoodable for String {
 	}
 }
 
+impl<'a, T> Woodable for &'a T where T:Woodable + ?Sized {
+	fn woodify(&self) -> Wood {
+		(**self).woodify()
+	}
+}
+
+/// Paths are written as OsStrs are: an ordinary leaf if they're valid unicode, otherwise the platform's own encoding of them, see wooder::OsStrTran, so they survive intact.
+impl Woodable for Path {
+	fn woodify(&self) -> Wood {
+		wooder::OS_STR_STRICT.woodify_os_str(self.as_os_str())
+	}
+}
+impl Woodable for PathBuf {
+	fn woodify(&self) -> Wood {
+		self.as_path().woodify()
+	}
+}
+impl Dewoodable for PathBuf {
+	fn dewoodify(v:&Wood) -> Result<PathBuf, DewoodifyError> {
+		wooder::OS_STR_STRICT.dewoodify(v).map(PathBuf::from)
+	}
+}
+
+impl<'a> Woodable for Cow<'a, str> {
+	fn woodify(&self) -> Wood {
+		self.as_ref().into()
+	}
+}
+impl<'a> Dewoodable for Cow<'a, str> {
+	fn dewoodify(v:&Wood) -> Result<Self, DewoodifyError> {
+		String::dewoodify(v).map(Cow::Owned)
+	}
+}
+
+/// For types that can be read out of a Wood by borrowing from it, rather than copying
+pub trait DewoodableRef<'w> where Self:Sized {
+	fn dewoodify_ref(v:&'w Wood) -> Result<Self, DewoodifyError>;
+}
+impl<'w> DewoodableRef<'w> for &'w str {
+	fn dewoodify_ref(v:&'w Wood) -> Result<&'w str, DewoodifyError> {
+		match *v {
+			Leafv(ref a)=> Ok(a.v.as_str()),
+			Branchv(_)=> Err(DewoodifyError::new_with_cause(v, "sought string, found branch".into(), None)),
+		}
+	}
+}
+impl<'w> DewoodableRef<'w> for Cow<'w, str> {
+	fn dewoodify_ref(v:&'w Wood) -> Result<Cow<'w, str>, DewoodifyError> {
+		<&'w str>::dewoodify_ref(v).map(Cow::Borrowed)
+	}
+}
+impl<'w, T> DewoodableRef<'w> for Vec<T> where T:DewoodableRef<'w> {
+	fn dewoodify_ref(v:&'w Wood) -> Result<Vec<T>, DewoodifyError> {
+		v.contents().map(T::dewoodify_ref).collect()
+	}
+}
+
+/// zero-copy dewoodification, eg, `let s:&str = dewoodify_ref(&w)?;`
+pub fn dewoodify_ref<'w, T>(v:&'w Wood) -> Result<T, DewoodifyError> where T: DewoodableRef<'w> {
+	T::dewoodify_ref(v)
+}
+
+/// For types that can be read out of a Wood that's given up to them, moving the leaf strings out rather than copying them
+pub trait DewoodableOwned where Self:Sized {
+	fn dewoodify_owned(v:Wood) -> Result<Self, DewoodifyError>;
+}
+impl DewoodableOwned for String {
+	fn dewoodify_owned(v:Wood) -> Result<String, DewoodifyError> {
+		match v {
+			Leafv(a)=> Ok(a.v),
+			Branchv(_)=> Err(DewoodifyError::new_with_cause(&v, "sought string, found branch".into(), None)),
+		}
+	}
+}
+impl DewoodableOwned for PathBuf {
+	fn dewoodify_owned(v:Wood) -> Result<PathBuf, DewoodifyError> {
+		String::dewoodify_owned(v).map(PathBuf::from)
+	}
+}
+impl DewoodableOwned for Wood {
+	fn dewoodify_owned(v:Wood) -> Result<Wood, DewoodifyError> { Ok(v) }
+}
+impl<T> DewoodableOwned for Vec<T> where T:DewoodableOwned {
+	fn dewoodify_owned(v:Wood) -> Result<Vec<T>, DewoodifyError> {
+		v.into_iter().map(T::dewoodify_owned).collect()
+	}
+}
+impl<T> DewoodableOwned for Box<T> where T:DewoodableOwned {
+	fn dewoodify_owned(v:Wood) -> Result<Box<T>, DewoodifyError> { T::dewoodify_owned(v).map(Box::new) }
+}
+impl<T> DewoodableOwned for Spanned<T> where T:DewoodableOwned {
+	fn dewoodify_owned(v:Wood) -> Result<Spanned<T>, DewoodifyError> {
+		let s = v.span();
+		Ok(Spanned{ line:s.line, column:s.column, extent:Extent{ start:s.start, end:s.end, end_line:s.end_line, end_column:s.end_column }, v:T::dewoodify_owned(v)? })
+	}
+}
+//types that have nothing to gain from ownership just borrow
+macro_rules! do_dewoodable_owned_by_ref_for {
+	($($Type:ty),*) => ($(
+		impl DewoodableOwned for $Type {
+			fn dewoodify_owned(v:Wood) -> Result<$Type, DewoodifyError> { <$Type>::dewoodify(&v) }
+		}
+	)*)
+}
+do_dewoodable_owned_by_ref_for!(bool, char, u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64);
+
+/// consuming dewoodification, which avoids copying leaves, eg, `let names:Vec<String> = dewoodify_owned(w)?;`
+pub fn dewoodify_owned<T>(v:Wood) -> Result<T, DewoodifyError> where T: DewoodableOwned {
+	T::dewoodify_owned(v)
+}
+
 pub fn woodify_seq_into<'a, InnerTran, T, I>(inner:&InnerTran, v:I, output:&mut Vec<Wood>)
 	where InnerTran: Wooder<T>, I:Iterator<Item=&'a T>, T:'a
 {
@@ -300,7 +1278,6 @@ pub fn woodify_seq_into<'a, InnerTran, T, I>(inner:&InnerTran, v:I, output:&mut
 pub fn dewoodify_seq_into<'a, InnerTran, T, I>(inner:&InnerTran, v:I, output:&mut Vec<T>) -> Result<(), DewoodifyError>
 	where InnerTran: Dewooder<T>, I:Iterator<Item=&'a Wood>
 {
-	// let errors = Vec::new();
 	for vi in v {
 		match inner.dewoodify(vi) {
 			Ok(vii)=> output.push(vii),
@@ -308,12 +1285,25 @@ pub fn dewoodify_seq_into<'a, InnerTran, T, I>(inner:&InnerTran, v:I, output:&mu
 		}
 	}
 	Ok(())
-	// if errors.len() > 0 {
-	// 	let msgs = String::new();
-	// 	for e in errors {
-	// 		msgs.push(format!("{}\n"))
-	// 	}
-	// }
+}
+/// like dewoodify_seq_into, but carries on past elements that fail, so that every error can be reported at once
+pub fn dewoodify_seq_all_into<'a, InnerTran, T, I>(inner:&InnerTran, v:I, output:&mut Vec<T>) -> Result<(), Vec<DewoodifyError>>
+	where InnerTran: Dewooder<T>, I:Iterator<Item=&'a Wood>
+{
+	let mut errors = Vec::new();
+	for vi in v {
+		match inner.dewoodify(vi) {
+			Ok(vii)=> output.push(vii),
+			Err(e)=> errors.push(e),
+		}
+	}
+	if errors.len() == 0 { Ok(()) } else { Err(errors) }
+}
+/// dewoodifies every element of v, returning either all of them or every error that was encountered
+pub fn dewoodify_all<T>(v:&Wood) -> Result<Vec<T>, Vec<DewoodifyError>> where T: Dewoodable {
+	let mut ret = Vec::new();
+	dewoodify_seq_all_into(&wooder::Iden, v.contents(), &mut ret)?;
+	Ok(ret)
 }
 
 
@@ -332,6 +1322,106 @@ impl<T> Dewoodable for Vec<T> where T:Dewoodable {
 	}
 }
 
+macro_rules! do_pointer_woodable_and_dewoodable_for {
+	($Ptr:ident) => (
+		impl<T> Woodable for $Ptr<T> where T:Woodable + ?Sized {
+			fn woodify(&self) -> Wood { (**self).woodify() }
+		}
+		impl<T> Dewoodable for $Ptr<T> where T:Dewoodable {
+			fn dewoodify(v:&Wood) -> Result<$Ptr<T>, DewoodifyError> { T::dewoodify(v).map($Ptr::new) }
+		}
+	)
+}
+
+do_pointer_woodable_and_dewoodable_for!(Box);
+do_pointer_woodable_and_dewoodable_for!(Rc);
+do_pointer_woodable_and_dewoodable_for!(Arc);
+
+macro_rules! do_sequence_woodable_and_dewoodable_for {
+	($Seq:ident $(, $Bound:ident)*) => (
+		impl<T> Woodable for $Seq<T> where T:Woodable $(+ $Bound)* {
+			fn woodify(&self) -> Wood {
+				let mut ret = Vec::new();
+				woodify_seq_into(&wooder::Iden, self.iter(), &mut ret);
+				ret.into()
+			}
+		}
+		impl<T> Dewoodable for $Seq<T> where T:Dewoodable $(+ $Bound)* {
+			fn dewoodify(v:&Wood) -> Result<$Seq<T>, DewoodifyError> {
+				let mut ret = Vec::new();
+				dewoodify_seq_into(&wooder::Iden, v.contents(), &mut ret)?;
+				Ok(ret.into_iter().collect())
+			}
+		}
+	)
+}
+
+do_sequence_woodable_and_dewoodable_for!(VecDeque);
+do_sequence_woodable_and_dewoodable_for!(LinkedList);
+do_sequence_woodable_and_dewoodable_for!(BinaryHeap, Ord); //note, woodifies in the heap's internal order, which is arbitrary
+
+impl<T, const N:usize> Woodable for [T; N] where T:Woodable {
+	fn woodify(&self) -> Wood {
+		let mut ret = Vec::new();
+		woodify_seq_into(&wooder::Iden, self.iter(), &mut ret);
+		ret.into()
+	}
+}
+impl<T, const N:usize> Dewoodable for [T; N] where T:Dewoodable {
+	fn dewoodify(v:&Wood) -> Result<[T; N], DewoodifyError> {
+		let contents = v.contents();
+		if contents.len() != N {
+			return Err(DewoodifyError::new(v, format!("expected an array of exactly {} elements, but there are {}", N, contents.len())));
+		}
+		let mut ret = Vec::with_capacity(N);
+		dewoodify_seq_into(&wooder::Iden, contents, &mut ret)?;
+		match <[T; N]>::try_from(ret) {
+			Ok(a)=> Ok(a),
+			Err(_)=> unreachable!(), //we checked the length already
+		}
+	}
+}
+
+/// a value along with where it was dewoodified from, so that later validation can point back into the source. The line, column and extent are those of the node it was read from, see Wood::span. Values that weren't parsed from anything have -1s for lines and columns, and the default extent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Spanned<T> {
+	pub line: isize,
+	pub column: isize,
+	pub extent: Extent,
+	pub v: T,
+}
+impl<T> Default for Spanned<T> where T:Default {
+	fn default()-> Self { Spanned::new(T::default()) }
+}
+impl<T> Spanned<T> {
+	pub fn new(v:T)-> Self { Spanned{ line:-1, column:-1, extent:Extent::default(), v } }
+	/// the text it was read from, `&src[s.span().start..s.span().end]`
+	pub fn span(&self)-> Span {
+		let e = self.extent;
+		Span{ start:e.start, end:e.end, line:self.line, column:self.column, end_line:e.end_line, end_column:e.end_column }
+	}
+	pub fn into_inner(self)-> T { self.v }
+	/// an error located where this value came from
+	pub fn error(&self, msg:String)-> DewoodifyError {
+		DewoodifyError::located(self.line, self.column, msg)
+	}
+}
+impl<T> Deref for Spanned<T> {
+	type Target = T;
+	fn deref(&self)-> &T { &self.v }
+}
+impl<T> DerefMut for Spanned<T> {
+	fn deref_mut(&mut self)-> &mut T { &mut self.v }
+}
+impl<T> Woodable for Spanned<T> where T:Woodable {
+	fn woodify(&self) -> Wood { self.v.woodify() }
+}
+impl<T> Dewoodable for Spanned<T> where T:Dewoodable {
+	fn dewoodify(v:&Wood) -> Result<Spanned<T>, DewoodifyError> {
+		let s = v.span();
+		Ok(Spanned{ line:s.line, column:s.column, extent:Extent{ start:s.start, end:s.end, end_line:s.end_line, end_column:s.end_column }, v:T::dewoodify(v)? })
+	}
+}
 
 
 
@@ -340,3 +1430,56 @@ mod parsers; pub use parsers::*;
 
 pub mod wooder;
 
+mod select; pub use select::{Query, QueryError};
+
+mod cursor; pub use cursor::WoodCursor;
+
+mod merge; pub use merge::MergeStrategy;
+
+mod patch; pub use patch::{diff, WoodPatch, PatchOp, PatchConflict};
+
+mod traverse; pub use traverse::{Dfs, Bfs, WoodPath, WoodVisitor, WoodVisitorMut, walk, walk_mut};
+
+mod include; pub use include::{IncludeResolver, IncludeLoader, FsLoader, MemoryLoader, IncludeError, IncludeErrorKind};
+
+mod anchor; pub use anchor::{resolve_anchors, resolve_anchors_limited, AnchorError, AnchorErrorKind};
+
+#[cfg(any(feature = "uuid", feature = "chrono", feature = "time", feature = "url", feature = "num-bigint", feature = "rust_decimal", feature = "indexmap"))]
+mod integrations;
+
+#[cfg(feature = "serde")]
+pub mod ser;
+#[cfg(feature = "serde")]
+pub use ser::{to_wood, to_string};
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "serde")]
+pub use de::{from_wood, from_str};
+#[cfg(feature = "transcode")]
+pub mod transcode;
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn spanned() {
+		let src = "ports 80 81 lots";
+		let w = parse_termpose(src).unwrap();
+		let ports:Vec<Spanned<String>> = dewoodify(&w).unwrap();
+		assert_eq!(ports[0].v, "ports");
+		assert_eq!((ports[2].line, ports[2].column), (0, 10));
+		assert_eq!(&src[ports[3].span().start..ports[3].span().end], "lots");
+		assert_eq!(Spanned::new(1u8).span(), Wood::leaf("1".into()).span());
+		let e = ports[3].error(format!("{} isn't a port", *ports[3]));
+		assert_eq!((e.line, e.column), (0, 13));
+		assert_eq!(Spanned::new(3u8).woodify().to_string(), "3");
+	}
+	
+	#[test]
+	fn node_size() {
+		//a leaf is its position, a pointer to its NodeMeta, and its string
+		assert_eq!(std::mem::size_of::<Leaf>(), 2*std::mem::size_of::<isize>() + std::mem::size_of::<usize>() + std::mem::size_of::<String>());
+	}
+}
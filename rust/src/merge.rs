@@ -0,0 +1,116 @@
+//overlaying one wood onto another, for layered configuration
+
+use super::*;
+
+/// what Wood::merge does when an entry in the overlay has the same key as one that's already there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+	/// the overlay's entry replaces the existing one
+	Override,
+	/// everything after the key in the overlay's entry is added onto the end of the existing one, EG, `tags a b` and `tags c` make `tags a b c`
+	Append,
+	/// if both entries contain only `(key …)` branches after their keys, they're merged entry by entry in the same way, otherwise this is the same as Override
+	Deep,
+}
+
+//whether everything after the key is a branch, which is taken to mean that it's a section of more entries rather than a value
+fn is_section(v:&Wood)-> bool {
+	match *v {
+		Branchv(ref b)=> b.v.len() > 1 && b.v[1..].iter().all(|c| c.is_branch()),
+		Leafv(_)=> false,
+	}
+}
+
+impl Wood {
+	/// overlays the entries of overlay onto self's. Entries are the elements of the contents, which are matched up by their initial_str, as with find. Entries that aren't there yet are added at the end. This is meant for the roots of multiline termpose documents, where each line is an entry
+	pub fn merge(&mut self, overlay:&Wood, strategy:MergeStrategy) {
+		self.merge_with(overlay, |_| strategy)
+	}
+	/// like merge, but the strategy is chosen for each entry that's in both, by a function that's given the keys leading to it, EG, `["server", "tls"]`
+	pub fn merge_with<F>(&mut self, overlay:&Wood, mut choose:F) where F: FnMut(&[String])-> MergeStrategy {
+		self.merge_entries(overlay, 0, &mut Vec::new(), &mut choose)
+	}
+	//entries start from start, 0 at the root, 1 inside a (key …) section
+	fn merge_entries<F>(&mut self, overlay:&Wood, start:usize, path:&mut Vec<String>, choose:&mut F) where F: FnMut(&[String])-> MergeStrategy {
+		let entries = self.as_branch_mut();
+		for o in overlay.contents().skip(start) {
+			let key = o.initial_str();
+			match entries.iter().skip(start).position(|e| e.initial_str() == key) {
+				Some(i)=> {
+					let existing = &mut entries[start + i];
+					path.push(key.to_string());
+					match choose(path) {
+						MergeStrategy::Override=> *existing = o.clone(),
+						MergeStrategy::Append=> existing.extend(o.tail().cloned()),
+						MergeStrategy::Deep=> {
+							if is_section(existing) && is_section(o) {
+								existing.merge_entries(o, 1, path, choose);
+							}else{
+								*existing = o.clone();
+							}
+						}
+					}
+					path.pop();
+				}
+				None=> entries.push(o.clone()),
+			}
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn defaults()-> Wood {
+		parse_multiline_termpose("
+server
+  host localhost
+  port 80
+  tls
+    cert a.pem
+tags a b
+debug
+").unwrap()
+	}
+	
+	#[test]
+	fn merging() {
+		let user = parse_multiline_termpose("
+server
+  port 81
+  tls
+    key k.pem
+tags c
+verbose
+").unwrap();
+		let mut over = defaults();
+		over.merge(&user, MergeStrategy::Override);
+		assert_eq!(over, parse_multiline_termpose("
+server
+  port 81
+  tls
+    key k.pem
+tags c
+debug
+verbose
+").unwrap());
+		let mut deep = defaults();
+		deep.merge_with(&user, |path| if path[0] == "tags" { MergeStrategy::Append } else { MergeStrategy::Deep });
+		assert_eq!(deep, parse_multiline_termpose("
+server
+  host localhost
+  port 81
+  tls
+    cert a.pem
+    key k.pem
+tags a b c
+debug
+verbose
+").unwrap());
+		let mut seen = Vec::new();
+		defaults().merge_with(&user, |path| { seen.push(path.join(".")); MergeStrategy::Deep });
+		assert_eq!(seen, vec!("server", "server.port", "server.tls", "tags"));
+	}
+}
@@ -0,0 +1,167 @@
+//parsing termpose out of a tokio AsyncRead, so that a service reading a request body doesn't block its executor while the rest of it arrives. The crate is too old an edition for async fn, so the futures and streams are written out by hand
+
+use super::*;
+use std::io;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+use futures_core::Stream;
+
+/// reads r to the end and parses it as parse_termpose would
+pub fn parse_from_async_reader<R:AsyncRead + Unpin>(r:R)-> ReadAndParse<R> {
+	ReadAndParse{ reader:r, buf:Vec::new() }
+}
+
+/// the future returned by parse_from_async_reader
+pub struct ReadAndParse<R> {
+	reader: R,
+	buf: Vec<u8>,
+}
+
+impl<R:AsyncRead + Unpin> Future for ReadAndParse<R> {
+	type Output = Result<Wood, WoodError>;
+	fn poll(mut self:Pin<&mut Self>, cx:&mut Context)-> Poll<Self::Output> {
+		let this = &mut *self;
+		loop {
+			match read_some(&mut this.reader, cx, &mut this.buf) {
+				Poll::Pending=> return Poll::Pending,
+				Poll::Ready(Err(e))=> return Poll::Ready(Err(WoodError::IoError(e))),
+				Poll::Ready(Ok(0))=> break,
+				Poll::Ready(Ok(_))=> {}
+			}
+		}
+		Poll::Ready(
+			utf8(replace(&mut this.buf, Vec::new()))
+			.and_then(|s| parse_termpose(&s).map_err(WoodError::ParserError))
+		)
+	}
+}
+
+/// the async counterpart of Parser, a Stream of the roots of a termpose document as they arrive. Iteration ends after the first error
+pub struct AsyncParser<R> {
+	reader: R,
+	chunker: Chunker,
+	buf: Vec<u8>, //read, but not yet a whole line
+	done: bool,
+}
+
+impl<R:AsyncRead + Unpin> AsyncParser<R> {
+	pub fn from_async_reader(r:R)-> Self {
+		AsyncParser{ reader:r, chunker:Chunker::new(), buf:Vec::new(), done:false }
+	}
+	pub fn with_style(mut self, style:TermposeStyle)-> Self {
+		self.chunker.style = style;
+		self
+	}
+	fn take_line(&mut self, end:usize)-> Result<(), WoodError> {
+		let line:Vec<u8> = self.buf.drain(..end).collect();
+		let line = utf8(line)?;
+		self.chunker.push_line(&line).map_err(WoodError::ParserError)
+	}
+}
+
+impl<R:AsyncRead + Unpin> Stream for AsyncParser<R> {
+	type Item = Result<Wood, WoodError>;
+	fn poll_next(mut self:Pin<&mut Self>, cx:&mut Context)-> Poll<Option<Self::Item>> {
+		let this = &mut *self;
+		loop {
+			if let Some(w) = this.chunker.pending.pop_front() { return Poll::Ready(Some(Ok(w))); }
+			if this.done { return Poll::Ready(None); }
+			let r = if let Some(nl) = this.buf.iter().position(|&b| b == b'\n') {
+				this.take_line(nl + 1)
+			}else{
+				match read_some(&mut this.reader, cx, &mut this.buf) {
+					Poll::Pending=> return Poll::Pending,
+					Poll::Ready(Err(e))=> Err(WoodError::IoError(e)),
+					Poll::Ready(Ok(0))=> {
+						this.done = true;
+						let end = this.buf.len();
+						this.take_line(end).and_then(|_| this.chunker.finish().map_err(WoodError::ParserError))
+					}
+					Poll::Ready(Ok(_))=> Ok(()),
+				}
+			};
+			if let Err(e) = r {
+				this.done = true;
+				return Poll::Ready(Some(Err(e)));
+			}
+		}
+	}
+}
+
+//reads whatever's ready onto the end of buf, returning how much that was, so 0 at the end
+fn read_some<R:AsyncRead + Unpin>(r:&mut R, cx:&mut Context, buf:&mut Vec<u8>)-> Poll<io::Result<usize>> {
+	let mut space = [0u8; 8192];
+	let mut rb = ReadBuf::new(&mut space);
+	match Pin::new(r).poll_read(cx, &mut rb) {
+		Poll::Ready(Ok(()))=> {
+			buf.extend_from_slice(rb.filled());
+			Poll::Ready(Ok(rb.filled().len()))
+		}
+		Poll::Ready(Err(e))=> Poll::Ready(Err(e)),
+		Poll::Pending=> Poll::Pending,
+	}
+}
+
+fn utf8(v:Vec<u8>)-> Result<String, WoodError> {
+	String::from_utf8(v).map_err(|e| WoodError::IoError(io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::task::Waker;
+	
+	//hands out its bytes a few at a time, and is only ready every other poll, to exercise the Pending paths
+	struct Trickle<'a> { data:&'a [u8], ready:bool }
+	impl<'a> AsyncRead for Trickle<'a> {
+		fn poll_read(mut self:Pin<&mut Self>, cx:&mut Context, buf:&mut ReadBuf)-> Poll<io::Result<()>> {
+			self.ready = !self.ready;
+			if !self.ready {
+				cx.waker().wake_by_ref();
+				return Poll::Pending;
+			}
+			let n = self.data.len().min(3).min(buf.remaining());
+			buf.put_slice(&self.data[..n]);
+			self.data = &self.data[n..];
+			Poll::Ready(Ok(()))
+		}
+	}
+	
+	fn block_on<F:Future>(mut f:F)-> F::Output {
+		let mut f = unsafe{ Pin::new_unchecked(&mut f) };
+		let mut cx = Context::from_waker(Waker::noop());
+		loop {
+			if let Poll::Ready(v) = f.as_mut().poll(&mut cx) { return v; }
+		}
+	}
+	
+	fn collect<R:AsyncRead + Unpin>(mut p:AsyncParser<R>)-> Vec<Result<Wood, WoodError>> {
+		let mut cx = Context::from_waker(Waker::noop());
+		let mut out = Vec::new();
+		loop {
+			match Pin::new(&mut p).poll_next(&mut cx) {
+				Poll::Ready(Some(r))=> out.push(r),
+				Poll::Ready(None)=> return out,
+				Poll::Pending=> {}
+			}
+		}
+	}
+	
+	#[test]
+	fn async_reading() {
+		let src = "a b\n  c\nd \"\"\"\nraw\n\"\"\"\n\ne (f g)";
+		assert_eq!(block_on(parse_from_async_reader(Trickle{ data:src.as_bytes(), ready:false })).unwrap(), parse_termpose(src).unwrap());
+		let roots:Vec<Wood> = collect(AsyncParser::from_async_reader(Trickle{ data:src.as_bytes(), ready:false })).into_iter().map(|r| r.unwrap()).collect();
+		let whole = parse_multiline_termpose(src).unwrap();
+		assert_eq!(roots, whole.contents().cloned().collect::<Vec<Wood>>());
+		assert_eq!(roots[2].span(), whole[2].span());
+		
+		let bad = collect(AsyncParser::from_async_reader(&b"a\nb)\nc"[..]));
+		assert_eq!(bad.len(), 2);
+		assert!(match bad[1] { Err(WoodError::ParserError(ref e))=> e.line == 1, _=> false });
+		assert!(match block_on(parse_from_async_reader(&b"a \xff"[..])) { Err(WoodError::IoError(ref e))=> e.kind() == io::ErrorKind::InvalidData, _=> false });
+	}
+}
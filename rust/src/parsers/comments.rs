@@ -0,0 +1,211 @@
+//opt-in comments for termpose. A comment starts with a # at the start of a term and runs to the end of the line. They're found and blanked out before the text is parsed, so the parser never sees them and no positions move, then each is attached to the node it's nearest to
+
+use super::*;
+
+/// a comment, as attached to a node by parse_termpose_with_comments. text is what came after the #, trimmed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Comment {
+	pub text: String,
+	/// whether it came after the node, on the same line, rather than on a line of its own before it
+	pub trailing: bool,
+	pub line: isize,
+	pub column: isize,
+}
+
+struct Found {
+	comment: Comment,
+	offset: usize,
+}
+
+//the text with every comment replaced with spaces, and the comments. Quoted, multiline and raw strings are skipped over, as a # in them isn't a comment
+fn find_comments(s:&str)-> (String, Vec<Found>) {
+	let mut out = s.as_bytes().to_vec();
+	let mut found = Vec::new();
+	let mut it = s.char_indices().skip(if bom_len(s) > 0 { 1 }else{ 0 }).peekable();
+	let (mut line, mut column) = (0isize, 0isize);
+	let mut indent = 0; //of the current line, in bytes
+	let mut line_blank = true; //whether there's been nothing but whitespace on the line so far
+	let mut term_start = true; //whether the next char would start a term
+	let mut quote:Option<bool> = None; //if in a quoted string, whether there's been nothing but whitespace in it
+	let mut multiline:Option<usize> = None; //if in a multiline string, the indent of the line that opened it
+	let mut content_line = false; //whether this line is part of a multiline string
+	let mut raw = false; //whether in a """raw string"""
+	while let Some((i, c)) = it.next() {
+		if c == '\n' || c == '\r' {
+			if line_break_continues(c, it.peek().map(|p| p.1)) { it.next(); }
+			line += 1;
+			column = 0;
+			//a quote that's still open at the end of the line, with nothing in it, opens a multiline string
+			if quote == Some(true) { multiline = Some(indent); }
+			quote = None;
+			indent = 0;
+			line_blank = true;
+			term_start = true;
+			content_line = false;
+			continue;
+		}
+		column += 1;
+		if raw {
+			if c == '"' && s[i + 1..].starts_with("\"\"") {
+				it.next();
+				it.next();
+				column += 2;
+				raw = false;
+				line_blank = false;
+				term_start = false;
+			}
+			continue;
+		}
+		if content_line { continue; }
+		let first_on_line = line_blank;
+		if line_blank {
+			if c == ' ' || c == '\t' {
+				indent += 1;
+				continue;
+			}
+			line_blank = false;
+			if let Some(mi) = multiline {
+				if indent > mi {
+					content_line = true;
+					continue;
+				}
+				multiline = None;
+			}
+		}
+		let escaped_char_follows = c == '\\' && it.peek().map_or(false, |p| p.1 != '\n' && p.1 != '\r');
+		if quote.is_some() {
+			match c {
+				'"'=> {
+					quote = None;
+					term_start = false;
+				}
+				' ' | '\t'=> {}
+				_=> quote = Some(false),
+			}
+			if escaped_char_follows {
+				it.next();
+				column += 1;
+			}
+			continue;
+		}
+		match c {
+			'#' if term_start => {
+				let end = s[i..].find(|c| c == '\n' || c == '\r').map_or(s.len(), |e| i + e);
+				found.push(Found{
+					comment:Comment{ text:s[i + 1..end].trim().to_string(), trailing:!first_on_line, line, column },
+					offset:i,
+				});
+				for b in out[i..end].iter_mut() { *b = b' '; }
+				while it.peek().map_or(false, |p| p.0 < end) { it.next(); }
+			}
+			'"' if s[i + 1..].starts_with("\"\"")=> {
+				it.next();
+				it.next();
+				column += 2;
+				raw = true;
+			}
+			'"'=> quote = Some(true),
+			' ' | '\t'=> term_start = true,
+			_=> term_start = false,
+		}
+		if escaped_char_follows {
+			it.next();
+			column += 1;
+		}
+	}
+	(String::from_utf8(out).unwrap(), found) //only whole chars were blanked, so it's still utf8
+}
+
+pub(super) fn blank_comments(s:&str)-> String { find_comments(s).0 }
+
+fn node_at<'a>(w:&'a mut Wood, path:&[usize])-> &'a mut Wood {
+	path.iter().fold(w, |w, &i| match *w {
+		Branchv(ref mut b)=> &mut b.v[i],
+		Leafv(_)=> unreachable!(),
+	})
+}
+
+//a comment on a line of its own goes to the outermost node that starts after it. A trailing comment goes to the outermost node that starts on its line and ends before it. Anything left over goes to the root
+fn attach(root:&mut Wood, found:Vec<Found>) {
+	//in pre-order, the nodes are also in order of where they start, with outer nodes before the inner ones that start at the same place
+	let nodes:Vec<(Span, WoodPath)> = root.iter_dfs().skip(1).map(|(w, _, p)| (w.span(), p)).collect();
+	for Found{ comment, offset } in found {
+		let target = if comment.trailing {
+			let first_on_line = nodes.partition_point(|n| n.0.line < comment.line);
+			nodes[first_on_line..].iter().take_while(|n| n.0.start < offset).find(|n| n.0.end <= offset)
+		}else{
+			nodes.get(nodes.partition_point(|n| n.0.start < offset))
+		};
+		match target {
+			Some(&(_, ref path))=> node_at(root, &path.to_vec()).comments_mut().push(comment),
+			None=> root.comments_mut().push(comment),
+		}
+	}
+}
+
+/// like parse_multiline_termpose, but `#`s at the start of a term begin comments, which run to the end of the line and are attached to the nearest node, see Wood::comments. A comment on a line of its own belongs to whatever comes after it, a comment after something on the same line belongs to that. Comments after everything else belong to the root
+pub fn parse_multiline_termpose_with_comments(s:&str)-> Result<Wood, PositionedError> {
+	let (blanked, found) = find_comments(s);
+	let mut root = parse_multiline_termpose(&blanked)?;
+	attach(&mut root, found);
+	Ok(root)
+}
+
+/// parse_multiline_termpose_with_comments, returning the only root when there's just one, as parse_termpose does. Comments that would have gone to the root go to that one
+pub fn parse_termpose_with_comments(s:&str)-> Result<Wood, PositionedError> {
+	let root = parse_multiline_termpose_with_comments(s)?;
+	let l = assume_branch(root);
+	if l.v.len() == 1 {
+		let mut only = yank_first(l.v);
+		only.comments_mut().extend(l.meta.map_or(Vec::new(), |m| m.comments));
+		Ok(only)
+	}else{
+		Ok(Branchv(l))
+	}
+}
+
+impl Wood {
+	pub fn comments(&self)-> &[Comment] {
+		&self.meta().comments
+	}
+	pub fn comments_mut(&mut self)-> &mut Vec<Comment> {
+		&mut self.meta_mut().comments
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn texts(w:&Wood)-> Vec<&str> { w.comments().iter().map(|c| c.text.as_str()).collect() }
+	
+	#[test]
+	fn comments() {
+		let src = "# the server
+server # main one
+  # where it listens
+  port 80
+  motd \"
+    # not a comment
+  name \"# nor this\" color:#fff a#b
+# at the end";
+		let w = parse_multiline_termpose_with_comments(src).unwrap();
+		assert_eq!(w, parse_multiline_termpose(&src.replace("# the server", "").replace("# main one", "").replace("# where it listens", "").replace("# at the end", "")).unwrap());
+		let server = &w[0];
+		assert_eq!(texts(server), vec!("the server"));
+		assert_eq!(texts(&server[0]), vec!("main one"));
+		assert!(server[0].comments()[0].trailing && !server.comments()[0].trailing);
+		assert_eq!(texts(&server[1]), vec!("where it listens"));
+		assert_eq!((server[1].comments()[0].line, server[1].comments()[0].column), (2, 3));
+		assert_eq!(server[2][1], "# not a comment");
+		assert_eq!(server[3][1], "# nor this");
+		assert_eq!(server[3][3], "a#b");
+		assert_eq!(texts(&w), vec!("at the end"));
+		assert_eq!(server[1].span().start, src.find("port").unwrap());
+		
+		let lone = parse_termpose_with_comments("a b # c\n# d").unwrap();
+		assert_eq!(texts(&lone), vec!("c", "d"));
+		assert!(parse_termpose("a # b").unwrap().comments().is_empty());
+	}
+}
@@ -0,0 +1,225 @@
+//a concrete syntax tree for termpose, keeping everything the wood drops: whitespace, indentation, comments, parens and colons, how each leaf was quoted. Printing it gives back exactly the text it was parsed from, so a tool can change a few nodes in a file without reformatting the rest. It's cut out of the source along the spans of an ordinary parse, so it can't disagree with parse_multiline_termpose about what the text means
+
+use super::*;
+
+/// how a leaf was written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quoting {
+	Bare,
+	/// "in quotes"
+	Quoted,
+	/// a " at the end of a line, with the string on the indented lines after it
+	Multiline,
+	/// """raw""", everything up to the closing quotes taken as it is
+	Raw,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CstLeaf {
+	/// exactly as it was written, quotes, escapes and all
+	pub text: String,
+	/// what it means, the v of the Leaf it corresponds to
+	pub v: String,
+	pub quoting: Quoting,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CstBranch {
+	/// the contents in the order they were written, nodes interleaved with whatever came between them
+	pub pieces: Vec<CstPiece>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CstPiece {
+	/// text between nodes that isn't a node itself: whitespace, line breaks and indentation, comments, parens, colons
+	Trivia(String),
+	Node(CstNode),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CstNode {
+	Leaf(CstLeaf),
+	Branch(CstBranch),
+}
+
+fn quoting_of(text:&str)-> Quoting {
+	if text.starts_with("\"\"\"") {
+		Quoting::Raw
+	}else if text.starts_with('"') {
+		let rest = &text[1..];
+		let line_end = rest.find(|c| c == '\n' || c == '\r').unwrap_or(rest.len());
+		if line_end < rest.len() && rest[..line_end].chars().all(is_whitespace) { Quoting::Multiline }else{ Quoting::Quoted }
+	}else{
+		Quoting::Bare
+	}
+}
+
+fn cut(w:&Wood, src:&str, start:usize, end:usize)-> Result<CstNode, PositionedError> {
+	match *w {
+		Leafv(ref l)=> {
+			let text = &src[start..end];
+			Ok(CstNode::Leaf(CstLeaf{ text:text.to_string(), v:l.v.clone(), quoting:quoting_of(text) }))
+		}
+		Branchv(ref b)=> {
+			let mut pieces = Vec::new();
+			let mut at = start;
+			for c in b.v.iter() {
+				let s = c.span();
+				if s.start < at || s.end > end || s.end < s.start {
+					return Err(PositionedError::new(s.line, s.column, "this node's span doesn't fit in its parent's, so the concrete tree can't be cut out".into()));
+				}
+				if s.start > at { pieces.push(CstPiece::Trivia(src[at..s.start].to_string())); }
+				pieces.push(CstPiece::Node(cut(c, src, s.start, s.end)?));
+				at = s.end;
+			}
+			if end > at { pieces.push(CstPiece::Trivia(src[at..end].to_string())); }
+			Ok(CstNode::Branch(CstBranch{ pieces }))
+		}
+	}
+}
+
+/// the concrete tree of s. Like parse_multiline_termpose, the root is a branch with every root term in it, its trivia is whatever's before, between and after them. `parse_cst(s)?.to_string() == s`
+pub fn parse_cst(s:&str)-> Result<CstNode, PositionedError> {
+	let w = parse_multiline_termpose(s)?;
+	cut(&w, s, 0, s.len())
+}
+
+/// parse_cst, with #s starting comments as in parse_multiline_termpose_with_comments. The comments end up in the trivia
+pub fn parse_cst_with_comments(s:&str)-> Result<CstNode, PositionedError> {
+	let w = parse_multiline_termpose(&blank_comments(s))?; //blanking doesn't move anything, so the spans are good for s
+	cut(&w, s, 0, s.len())
+}
+
+impl CstLeaf {
+	/// a leaf for v, written the way stringify_leaf_termpose would
+	pub fn new(v:String)-> CstLeaf {
+		let mut text = String::new();
+		if v.is_empty() {
+			text.push_str("\"\"");
+		}else{
+			stringify_leaf_termpose(&Leaf{ line:-1, column:-1, meta:None, v:v.clone() }, &mut text, &DEFAULT_STYLE);
+		}
+		let quoting = quoting_of(&text);
+		CstLeaf{ text, v, quoting }
+	}
+	/// changes what the leaf says. A quoted leaf stays quoted. A multiline one becomes quoted, as there's no telling here what it would have to be indented to. A raw one stays raw if it still has line breaks and nothing in it that can't be raw
+	pub fn set_v(&mut self, v:String) {
+		if self.quoting == Quoting::Bare {
+			*self = CstLeaf::new(v);
+		}else if self.quoting == Quoting::Raw && fits_raw_string(&v, EscapePolicy::Minimal) {
+			let mut text = String::new();
+			stringify_leaf_termpose(&Leaf{ line:-1, column:-1, meta:None, v:v.clone() }, &mut text, &TermposeStyle{ raw_strings:true, ..DEFAULT_STYLE.clone() });
+			*self = CstLeaf{ text, v, quoting:Quoting::Raw };
+		}else{
+			let mut text = String::from("\"");
+			push_escaped(&mut text, &v);
+			text.push('"');
+			*self = CstLeaf{ text, v, quoting:Quoting::Quoted };
+		}
+	}
+}
+
+impl CstNode {
+	/// the node written inline, with parens around branches, so it can go anywhere a term can
+	pub fn from_wood(w:&Wood)-> CstNode {
+		match *w {
+			Leafv(ref l)=> CstNode::Leaf(CstLeaf::new(l.v.clone())),
+			Branchv(ref b)=> {
+				let mut pieces = vec!(CstPiece::Trivia("(".into()));
+				for (i, c) in b.v.iter().enumerate() {
+					if i != 0 { pieces.push(CstPiece::Trivia(" ".into())); }
+					pieces.push(CstPiece::Node(CstNode::from_wood(c)));
+				}
+				pieces.push(CstPiece::Trivia(")".into()));
+				CstNode::Branch(CstBranch{ pieces })
+			}
+		}
+	}
+	/// the concrete tree of src cut along w, which has to be what parse_multiline_termpose(src) returned, or what IncrementalTermpose::wood gives. Saves parsing src again when the wood's already up to date
+	pub fn from_parsed(w:&Wood, src:&str)-> Result<CstNode, PositionedError> {
+		cut(w, src, 0, src.len())
+	}
+	/// the plain wood, without positions. Parse the text again if those are needed
+	pub fn to_wood(&self)-> Wood {
+		match *self {
+			CstNode::Leaf(ref l)=> Wood::leaf(l.v.clone()),
+			CstNode::Branch(ref b)=> Wood::branch(b.children().map(|c| c.to_wood()).collect()),
+		}
+	}
+	pub fn is_leaf(&self)-> bool { match *self { CstNode::Leaf(_)=> true, CstNode::Branch(_)=> false } }
+	pub fn is_branch(&self)-> bool { !self.is_leaf() }
+	/// the nodes in a branch, skipping the trivia. Nothing for a leaf
+	pub fn children<'a>(&'a self)-> Box<Iterator<Item=&'a CstNode> + 'a> {
+		match *self {
+			CstNode::Branch(ref b)=> Box::new(b.children()),
+			CstNode::Leaf(_)=> Box::new(None.into_iter()),
+		}
+	}
+	fn write_into(&self, out:&mut String) {
+		match *self {
+			CstNode::Leaf(ref l)=> out.push_str(&l.text),
+			CstNode::Branch(ref b)=> for p in b.pieces.iter() {
+				match *p {
+					CstPiece::Trivia(ref t)=> out.push_str(t),
+					CstPiece::Node(ref n)=> n.write_into(out),
+				}
+			},
+		}
+	}
+}
+
+impl CstBranch {
+	pub fn children<'a>(&'a self)-> impl Iterator<Item=&'a CstNode> + 'a {
+		self.pieces.iter().filter_map(|p| match *p { CstPiece::Node(ref n)=> Some(n), _=> None })
+	}
+	pub fn children_mut<'a>(&'a mut self)-> impl Iterator<Item=&'a mut CstNode> + 'a {
+		self.pieces.iter_mut().filter_map(|p| match *p { CstPiece::Node(ref mut n)=> Some(n), _=> None })
+	}
+}
+
+impl Display for CstNode {
+	fn fmt(&self, f:&mut Formatter)-> Result<(), std::fmt::Error> {
+		let mut out = String::new();
+		self.write_into(&mut out);
+		f.write_str(&out)
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn lossless() {
+		let src = "server # main one\n\tport 80\n\tmotd:\"hi there\"\n\tlong \"\n\t\tfirst\n\t\tsecond\n\tescaped \"a\\tb\"\n\nother(x y) z\n";
+		let cst = parse_cst_with_comments(src).unwrap();
+		assert_eq!(cst.to_string(), src);
+		assert_eq!(cst.to_wood(), parse_multiline_termpose_with_comments(src).unwrap());
+		let server = cst.children().next().unwrap();
+		let quotings:Vec<(String, Quoting)> = server.children().skip(1).filter_map(|c| c.children().nth(1)).filter_map(|c| match *c { CstNode::Leaf(ref l)=> Some((l.v.clone(), l.quoting)), _=> None }).collect();
+		assert_eq!(quotings, vec!(("80".to_string(), Quoting::Bare), ("hi there".to_string(), Quoting::Quoted), ("first\nsecond".to_string(), Quoting::Multiline), ("a\tb".to_string(), Quoting::Quoted)));
+		for f in ["tests.term", "longterm.term", "shortterm.term"].iter() {
+			let text = ::std::fs::read_to_string(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(f)).unwrap();
+			let cst = parse_cst(&text).unwrap();
+			assert_eq!(cst.to_string(), text);
+			assert_eq!(cst.to_wood(), parse_multiline_termpose(&text).unwrap());
+		}
+	}
+	
+	#[test]
+	fn editing() {
+		let mut cst = parse_cst_with_comments("a  # keep\n  (b  c)\n  d:\"e\"").unwrap();
+		{
+			let a = match cst { CstNode::Branch(ref mut b)=> b.children_mut().next().unwrap(), _=> unreachable!() };
+			let mut kids:Vec<&mut CstNode> = match *a { CstNode::Branch(ref mut b)=> b.children_mut().collect(), _=> unreachable!() };
+			if let CstNode::Leaf(ref mut l) = *kids[0] { l.set_v("x y".into()); }
+			*kids[1] = CstNode::from_wood(&wood!(p (q "") r));
+			if let CstNode::Branch(ref mut d) = *kids[2] {
+				if let Some(&mut CstNode::Leaf(ref mut l)) = d.children_mut().nth(1) { l.set_v("f\"".into()); }
+			}
+		}
+		assert_eq!(cst.to_string(), "\"x y\"  # keep\n  (p (q \"\") r)\n  d:\"f\\\"\"");
+		assert_eq!(cst.to_wood(), parse_multiline_termpose_with_comments(&cst.to_string()).unwrap());
+	}
+}
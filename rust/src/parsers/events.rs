@@ -0,0 +1,350 @@
+//termpose as a sequence of events rather than a tree, for documents too big to hold. This isn't a parser of its own, it's an adapter over the ordinary one that buffers a line at a time. Lines are taken one at a time and each is parsed on its own by the ordinary parser, into a small wood that's turned into events and dropped. A line that has lines indented under it is parsed once more with a sentinel child line under it, to find which of its branches the children go in. A line's events are sent as soon as the next line shows whether anything is indented under it, so memory use depends on how long lines are and how deeply the document nests, not on how long it is. A multiline or raw string counts as one line, and is held whole until it ends
+
+use super::*;
+use std::io::BufRead;
+
+/// receives the events of a termpose document from parse_termpose_events, in the order they occur in the text
+pub trait WoodHandler {
+	/// a branch starts here
+	fn on_branch_open(&mut self, _line:isize, _column:isize) {}
+	fn on_leaf(&mut self, _v:&str, _span:Span) {}
+	/// the branch opened last ends. span is the whole of it
+	fn on_branch_close(&mut self, _span:Span) {}
+}
+
+/// an event from WoodEvents. These are the same as WoodHandler's
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+	/// a branch starts here
+	Open{ line:isize, column:isize },
+	Leaf(String, Span),
+	/// the branch opened last ends. The span is the whole of it
+	Close(Span),
+}
+
+impl WoodHandler for VecDeque<Event> {
+	fn on_branch_open(&mut self, line:isize, column:isize) { self.push_back(Event::Open{ line, column }); }
+	fn on_leaf(&mut self, v:&str, span:Span) { self.push_back(Event::Leaf(v.to_string(), span)); }
+	fn on_branch_close(&mut self, span:Span) { self.push_back(Event::Close(span)); }
+}
+
+//any leaf would do, it's told apart from leaves in the text by being on a line of its own
+const SENTINEL:&str = "children";
+
+//a line, with any lines that continue it, the inside of a raw string, or the lines of a multiline string
+struct Line {
+	text: String,
+	indent: String,
+	line: isize,
+	offset: usize,
+	in_raw: bool,
+	multiline: bool, //known to have opened a multiline string, so the deeper lines after it are in that
+	blank: String, //blank lines since, which are only part of it if it turns out to continue after them
+}
+
+//a line whose children are being read
+struct Level {
+	indent: String,
+	child_indent: String,
+	open: Vec<(isize, isize, usize)>, //the line, column and start of each branch that was left open for the children to go in
+}
+
+//turns lines into events by parsing them with the ordinary parser
+pub(super) struct LineBuffered {
+	pending: Option<Line>, //the last line read, whose events wait on the next
+	levels: Vec<Level>,
+	line: isize,
+	offset: usize,
+	last_end: (usize, isize, isize), //the end, end_line and end_column of the last thing sent
+}
+
+fn indentation(p:&str)-> &str { &p[..p.len() - p.trim_start_matches(|c| c == ' ' || c == '\t').len()] }
+fn deeper(indent:&str, than:&str)-> bool { indent.len() > than.len() && indent.starts_with(than) }
+
+//the last leaf, or empty branch, in pre-order
+fn rightmost(w:&Wood)-> &Wood {
+	match *w {
+		Branchv(ref b) if !b.v.is_empty()=> rightmost(b.v.last().unwrap()),
+		_=> w,
+	}
+}
+
+impl LineBuffered {
+	pub(super) fn new()-> Self {
+		LineBuffered{ pending:None, levels:Vec::new(), line:0, offset:0, last_end:(0, 0, 0) }
+	}
+	
+	pub(super) fn push_line<H:WoodHandler>(&mut self, p:&str, h:&mut H)-> Result<(), PositionedError> {
+		let (line, mut offset) = (self.line, self.offset);
+		self.line += count_lines(p);
+		self.offset += p.len();
+		let p = if offset == 0 {
+			offset = bom_len(p);
+			&p[offset..]
+		}else{ p };
+		if let Some(ref mut pend) = self.pending {
+			if pend.in_raw {
+				pend.text.push_str(&replace(&mut pend.blank, String::new()));
+				pend.text.push_str(p);
+				pend.in_raw ^= toggles_raw(p);
+				return Ok(());
+			}
+		}
+		let indent = indentation(p);
+		if p[indent.len()..].trim().is_empty() {
+			if let Some(ref mut pend) = self.pending {
+				pend.blank.push_str(p);
+				//a blank line as indented as the multiline string it's in is part of it, even at the end
+				if pend.multiline && deeper(indent, &pend.indent) { pend.text.push_str(&replace(&mut pend.blank, String::new())); }
+			}
+			return Ok(());
+		}
+		if let Some(mut pend) = self.pending.take() {
+			if deeper(indent, &pend.indent) {
+				if !pend.multiline {
+					match self.open_children(&pend, h)? {
+						Some(open)=> self.levels.push(Level{ indent:pend.indent.clone(), child_indent:indent.to_string(), open }),
+						None=> pend.multiline = true,
+					}
+				}
+				if pend.multiline {
+					pend.text.push_str(&replace(&mut pend.blank, String::new()));
+					pend.text.push_str(p);
+					self.pending = Some(pend);
+					return Ok(());
+				}
+			}else{
+				self.send_line(&pend, h)?;
+				self.close_to(indent, line, h)?;
+			}
+		}
+		self.pending = Some(Line{ text:p.to_string(), indent:indent.to_string(), line, offset, in_raw:toggles_raw(p), multiline:false, blank:String::new() });
+		Ok(())
+	}
+	
+	pub(super) fn finish<H:WoodHandler>(&mut self, h:&mut H)-> Result<(), PositionedError> {
+		if let Some(pend) = self.pending.take() { self.send_line(&pend, h)?; }
+		self.close_to("", self.line, h)
+	}
+	
+	//closes the lines that a line with this indent isn't under
+	fn close_to<H:WoodHandler>(&mut self, indent:&str, line:isize, h:&mut H)-> Result<(), PositionedError> {
+		while let Some(level) = self.levels.pop() {
+			if deeper(indent, &level.indent) {
+				if indent != level.child_indent {
+					return Err(PositionedError::new(line, indent.chars().count() as isize + 1, "inconsistent indentation".into()));
+				}
+				self.levels.push(level);
+				break;
+			}
+			let (end, end_line, end_column) = self.last_end;
+			for &(line, column, start) in level.open.iter().rev() {
+				h.on_branch_close(Span{ start, end, line, column, end_line, end_column });
+			}
+		}
+		Ok(())
+	}
+	
+	fn parse(&self, l:&Line, text:&str)-> Result<Wood, PositionedError> {
+		let mut root = parse_multiline_termpose(text).map_err(|mut e| { e.line += l.line; e })?;
+		shift_positions(&mut root, l.line, l.offset as isize);
+		Ok(root)
+	}
+	
+	fn send_line<H:WoodHandler>(&mut self, l:&Line, h:&mut H)-> Result<(), PositionedError> {
+		let root = self.parse(l, &l.text)?;
+		for w in root.contents() { self.send(w, h); }
+		Ok(())
+	}
+	
+	fn send<H:WoodHandler>(&mut self, w:&Wood, h:&mut H) {
+		let s = w.span();
+		match *w {
+			Leafv(ref l)=> h.on_leaf(&l.v, s),
+			Branchv(ref b)=> {
+				h.on_branch_open(s.line, s.column);
+				for c in b.v.iter() { self.send(c, h); }
+				h.on_branch_close(s);
+			}
+		}
+		self.last_end = (s.end, s.end_line, s.end_column);
+	}
+	
+	//sends the line as far as where the lines indented under it go, which it finds by parsing the line with a sentinel child and seeing where that ends up. Returns the branches that are left open, or None if the sentinel ended up in a multiline string the line opens
+	fn open_children<H:WoodHandler>(&mut self, l:&Line, h:&mut H)-> Result<Option<Vec<(isize, isize, usize)>>, PositionedError> {
+		let mut text = l.text.clone();
+		if !text.ends_with('\n') && !text.ends_with('\r') { text.push('\n'); }
+		let sentinel_line = l.line + count_lines(&text);
+		text.push_str(&l.indent);
+		text.push(' ');
+		text.push_str(SENTINEL);
+		let root = self.parse(l, &text)?;
+		let is_sentinel = match *rightmost(&root) {
+			Leafv(ref s)=> s.v == SENTINEL && s.line == sentinel_line,
+			Branchv(_)=> false,
+		};
+		if !is_sentinel { return Ok(None); }
+		let mut open = Vec::new();
+		let roots = match root { Branchv(ref b)=> &b.v, Leafv(_)=> unreachable!() };
+		let (mut at, before) = roots.split_last().unwrap();
+		for c in before { self.send(c, h); }
+		//the sentinel is last, so the branches it's in are the last of their parents
+		while let Branchv(ref b) = *at {
+			let s = at.span();
+			h.on_branch_open(s.line, s.column);
+			open.push((s.line, s.column, s.start));
+			let (last, before) = b.v.split_last().unwrap();
+			for c in before { self.send(c, h); }
+			at = last;
+		}
+		Ok(Some(open))
+	}
+}
+
+/// reads a termpose document a line at a time, sending events to h rather than building the wood of the whole document, so that a few things can be picked out of documents too big to hold in memory. This is a line-buffered adapter over the ordinary parser, not an event parser of its own: each line still becomes a wood of its own while it's being parsed, twice for lines with children, and a multiline or raw string is buffered whole, so it's slower than parse_multiline_termpose and only saves memory on documents of many lines. The events are those of the roots parse_multiline_termpose would return, one after another, with no branch around them all. Stops at the first error
+pub fn parse_termpose_events<R:BufRead, H:WoodHandler>(mut r:R, h:&mut H)-> Result<(), WoodError> {
+	let mut p = LineBuffered::new();
+	let mut line = String::new();
+	loop {
+		line.clear();
+		if r.read_line(&mut line).map_err(WoodError::IoError)? == 0 { break; }
+		p.push_line(&line, h).map_err(WoodError::ParserError)?;
+	}
+	p.finish(h).map_err(WoodError::ParserError)
+}
+
+/// the events of a termpose document, pulled out of it a line at a time, for consumers that would rather drive the parsing than be called by it, EG, a deserializer or a converter to another format. They're what parse_termpose_events would send. Iteration ends after the first error
+pub struct WoodEvents<'a> {
+	src: &'a str,
+	at: usize, //where the next line starts
+	parser: LineBuffered,
+	queue: VecDeque<Event>,
+	done: bool,
+}
+
+impl<'a> WoodEvents<'a> {
+	pub fn new(src:&'a str)-> Self {
+		WoodEvents{ src, at:0, parser:LineBuffered::new(), queue:VecDeque::new(), done:false }
+	}
+}
+
+impl<'a> Iterator for WoodEvents<'a> {
+	type Item = Result<Event, PositionedError>;
+	fn next(&mut self)-> Option<Self::Item> {
+		loop {
+			if let Some(e) = self.queue.pop_front() { return Some(Ok(e)); }
+			if self.done { return None; }
+			let r = if self.at < self.src.len() {
+				let rest = &self.src[self.at..];
+				let line = &rest[..rest.find('\n').map_or(rest.len(), |i| i + 1)];
+				self.at += line.len();
+				self.parser.push_line(line, &mut self.queue)
+			}else{
+				self.done = true;
+				self.parser.finish(&mut self.queue)
+			};
+			if let Err(e) = r {
+				self.done = true;
+				return Some(Err(e));
+			}
+		}
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	//builds the wood back up, to check that the events describe exactly what the ordinary parser would have made
+	struct Builder { stack:Vec<Vec<Wood>>, opened:Vec<(isize, isize)> }
+	impl WoodHandler for Builder {
+		fn on_branch_open(&mut self, line:isize, column:isize) {
+			self.stack.push(Vec::new());
+			self.opened.push((line, column));
+		}
+		fn on_leaf(&mut self, v:&str, s:Span) {
+			self.stack.last_mut().unwrap().push(Leafv(Leaf{ line:s.line, column:s.column, meta:meta_with_extent(Extent{ start:s.start, end:s.end, end_line:s.end_line, end_column:s.end_column }), v:v.to_string() }));
+		}
+		fn on_branch_close(&mut self, s:Span) {
+			let v = self.stack.pop().unwrap();
+			assert_eq!(self.opened.pop().unwrap(), (s.line, s.column));
+			self.stack.last_mut().unwrap().push(Branchv(Branch{ line:s.line, column:s.column, meta:meta_with_extent(Extent{ start:s.start, end:s.end, end_line:s.end_line, end_column:s.end_column }), v }));
+		}
+	}
+	fn rebuild(s:&str)-> Result<Wood, WoodError> {
+		let mut b = Builder{ stack:vec!(Vec::new()), opened:Vec::new() };
+		parse_termpose_events(s.as_bytes(), &mut b)?;
+		assert_eq!(b.stack.len(), 1);
+		Ok(b.stack.pop().unwrap().into())
+	}
+	
+	#[test]
+	fn events() {
+		let cases = [
+			"a b\n  c", "a:\n  b", "x a:\n  b", "a (b\n  c\n  d", "a:b\n  c", "a \"\n  xx\n\n  yy\nb", "a \"x\n  b",
+			"a\n  \"\n    str\n  b", "a\n\n  b\n    c\n  d\ne", "r \"\"\"\nraw\n  \"\"\" s\n  t", "\u{feff}a b\n  c\r\n  d\r\n",
+			"a\n\tb\n\t\tc\n\td", "(a b)\n  c", "a b\n  c d\n    e\n  f:g h\n",
+		];
+		let files:Vec<String> = ["tests.term", "longterm.term", "shortterm.term"].iter().map(|f| ::std::fs::read_to_string(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(f)).unwrap()).collect();
+		for s in cases.iter().map(|s| *s).chain(files.iter().map(|f| f.as_str())) {
+			let whole = parse_multiline_termpose(s).unwrap();
+			let rebuilt = rebuild(s).unwrap();
+			assert_eq!(rebuilt, whole, "{:?}", s);
+			for ((r, _, _), (w, _, _)) in rebuilt.iter_dfs().skip(1).zip(whole.iter_dfs().skip(1)) {
+				assert_eq!(r.span(), w.span(), "{:?} {}", s, w.to_string());
+			}
+		}
+		
+		for bad in ["a\n  b\n    c\n   d", "a\n  b)\n", "a \"\"\"\nnever closed"].iter() {
+			let e = parse_multiline_termpose(bad).unwrap_err();
+			match rebuild(bad) {
+				Err(WoodError::ParserError(ref p))=> assert_eq!((p.line, p.column, &p.msg), (e.line, e.column, &e.msg)),
+				_=> panic!("{:?} should have failed", bad),
+			}
+		}
+	}
+	
+	#[test]
+	fn pulling() {
+		let src = "a b\n  c \"d\"\ne";
+		let events:Vec<Event> = WoodEvents::new(src).map(|e| e.unwrap()).collect();
+		let w = parse_multiline_termpose(src).unwrap();
+		let leaf = |w:&Wood| Event::Leaf(w.initial_str().to_string(), w.span());
+		assert_eq!(events, vec!(
+			Event::Open{ line:0, column:1 },
+			Event::Open{ line:0, column:1 }, leaf(&w[0][0][0]), leaf(&w[0][0][1]), Event::Close(w[0][0].span()),
+			Event::Open{ line:1, column:3 }, leaf(&w[0][1][0]), leaf(&w[0][1][1]), Event::Close(w[0][1].span()),
+			Event::Close(w[0].span()),
+			leaf(&w[1]),
+		));
+		let mut queued = VecDeque::new();
+		parse_termpose_events(src.as_bytes(), &mut queued).unwrap();
+		assert_eq!(events, Vec::from(queued));
+		
+		let mut bad = WoodEvents::new("a\nb)\nc");
+		assert_eq!(bad.next().unwrap().unwrap(), Event::Leaf("a".into(), parse_termpose("a").unwrap().span()));
+		assert_eq!(bad.next().unwrap().unwrap_err().line, 1);
+		assert!(bad.next().is_none());
+		assert_eq!(WoodEvents::new("").count(), 0);
+	}
+	
+	#[test]
+	fn picking_out_fields() {
+		//counts the ports in a long document, holding only a line or two of it at a time
+		struct Ports { depth:usize, after_port:bool, total:u64 }
+		impl WoodHandler for Ports {
+			fn on_branch_open(&mut self, _:isize, _:isize) { self.depth += 1; }
+			fn on_leaf(&mut self, v:&str, _:Span) {
+				if self.after_port { self.total += v.parse::<u64>().unwrap(); }
+				self.after_port = self.depth == 2 && v == "port";
+			}
+			fn on_branch_close(&mut self, _:Span) { self.depth -= 1; }
+		}
+		let mut doc = String::new();
+		for i in 0..1000 { doc.push_str(&format!("server\n  name s{}\n  port {}\n", i, i)); }
+		let mut p = Ports{ depth:0, after_port:false, total:0 };
+		parse_termpose_events(doc.as_bytes(), &mut p).unwrap();
+		assert_eq!(p.total, (0..1000).sum::<u64>());
+	}
+}
@@ -0,0 +1,305 @@
+//reparsing after an edit without reparsing everything, for editors that want a tree on every keystroke. Only the smallest indented block the edit falls in, a line and the lines indented under it, is parsed again. Each node's position is kept relative to its parent's, so what comes after the edit is moved by moving the few nodes beside the path down to it, not everything in them
+
+use super::*;
+use std::ops::Range;
+
+/// a termpose document that's kept parsed as it's edited, see edit
+pub struct IncrementalTermpose {
+	src: String,
+	root: Wood, //what parse_multiline_termpose(src) gives, but with each node's line and byte offsets counted from its parent's, see relativize
+}
+
+//makes w's position, which is absolute, relative to the given one, and that of everything in it relative to its parent's
+fn relativize(w:&mut Wood, line:isize, start:isize) {
+	let (wl, ws) = position(w);
+	if let Branchv(ref mut b) = *w {
+		for c in b.v.iter_mut() { relativize(c, wl, ws); }
+	}
+	shift_node(w, -line, -start);
+}
+//undoes relativize, given where w's parent starts
+fn absolutize(w:&mut Wood, line:isize, start:isize) {
+	shift_node(w, line, start);
+	let (wl, ws) = position(w);
+	if let Branchv(ref mut b) = *w {
+		for c in b.v.iter_mut() { absolutize(c, wl, ws); }
+	}
+}
+fn position(w:&Wood)-> (isize, isize) { (w.line_and_col().0, w.span().start as isize) }
+
+fn branch_at<'a>(mut w:&'a Wood, path:&[usize])-> &'a Branch {
+	for &i in path { w = &w[i]; }
+	match *w { Branchv(ref b)=> b, Leafv(_)=> unreachable!() }
+}
+fn branch_at_mut<'a>(mut w:&'a mut Wood, path:&[usize])-> &'a mut Branch {
+	for &i in path { w = &mut assume_branch_mut(w).v[i]; }
+	assume_branch_mut(w)
+}
+
+//sets where a branch with indented lines ends, which is where its last element does
+fn end_with_last(w:&mut Wood) {
+	let (line, start) = position(w);
+	let b = assume_branch_mut(w);
+	if let Some(l) = b.v.last().map(|l| l.span()) {
+		let e = &mut b.meta_mut().extent;
+		e.end = (start + l.end as isize) as usize;
+		e.end_line = line + l.end_line;
+		e.end_column = l.end_column;
+	}
+}
+
+//where the line that at is on starts
+fn line_start(src:&str, at:usize)-> usize {
+	src[..at].rfind(|c| c == '\n' || c == '\r').map_or(0, |i| i + 1)
+}
+
+//the first index in r that pred is false for, pred being true for every index before it and false for every one after
+fn partition<F:Fn(usize)-> bool>(r:Range<usize>, pred:F)-> usize {
+	let (mut lo, mut hi) = (r.start, r.end);
+	while lo < hi {
+		let mid = lo + (hi - lo)/2;
+		if pred(mid) { lo = mid + 1; }else{ hi = mid; }
+	}
+	lo
+}
+
+//a branch the edit falls among the indented lines of, or the root, whose lines are the roots
+struct Level {
+	path: Vec<usize>,
+	line: isize, //where the branch starts
+	start: usize,
+	end: usize, //where the block of its last indented line ends
+	lines: Range<usize>, //its elements that start indented lines of their own, each the first of a block of lines
+	indent: usize, //how long those lines' indentation is
+	touched: Range<usize>, //the blocks the edit touches
+}
+impl Level {
+	fn new(b:&Branch, path:Vec<usize>, line:isize, start:usize, end:usize, src:&str, range:&Range<usize>)-> Option<Level> {
+		let abs = |i:usize| start + b.v[i].span().start;
+		let lines = if path.is_empty() { 0..b.v.len() }else{
+			//they all come after the elements on the branch's first line
+			let starts_line = |i:usize| b.v[i].line_and_col().0 > 0 && src[line_start(src, abs(i))..abs(i)].chars().all(|c| c == ' ' || c == '\t');
+			partition(0..b.v.len(), |i| !starts_line(i))..b.v.len()
+		};
+		if lines.start == lines.end { return None; }
+		let indent = if path.is_empty() { 0 }else{ abs(lines.start) - line_start(src, abs(lines.start)) }; //the first root can be indented, but the others can't
+		let mut level = Level{ path, line, start, end, lines, indent, touched:0..0 };
+		if !level.path.is_empty() && range.start < level.block_start(b, src, level.lines.start) { return None; }
+		let first = partition(level.lines.clone(), |i| level.block_end(b, src, i) < range.start);
+		let last = partition(level.lines.clone(), |i| level.block_start(b, src, i) <= range.end);
+		level.touched = first..last;
+		Some(level)
+	}
+	//the block of lines element i starts. Any blank lines before the first root go with it
+	fn block_start(&self, b:&Branch, src:&str, i:usize)-> usize {
+		if self.path.is_empty() && i == 0 { 0 }else{ line_start(src, self.start + b.v[i].span().start) }
+	}
+	fn block_end(&self, b:&Branch, src:&str, i:usize)-> usize {
+		if i + 1 < self.lines.end { self.block_start(b, src, i + 1) }else{ self.end }
+	}
+}
+
+impl IncrementalTermpose {
+	pub fn new(src:String)-> Result<Self, PositionedError> {
+		let mut root = parse_multiline_termpose(&src)?;
+		relativize(&mut root, 0, 0);
+		Ok(IncrementalTermpose{ src, root })
+	}
+	pub fn src(&self)-> &str { &self.src }
+	/// what parse_multiline_termpose(self.src()) returns, positions and all. This goes through the whole document, subtree only goes through what's asked for
+	pub fn wood(&self)-> Wood {
+		let mut w = self.root.clone();
+		absolutize(&mut w, 0, 0);
+		w
+	}
+	/// the node at the end of path, from the root, with the positions parse_multiline_termpose would give it. EG, `subtree` of each of what edit says it reparsed
+	pub fn subtree(&self, path:&[usize])-> Option<Wood> {
+		let (mut line, mut start) = (0, 0);
+		let mut w = &self.root;
+		for &i in path {
+			let (l, s) = position(w);
+			line += l;
+			start += s;
+			w = match *w { Branchv(ref b)=> b.v.get(i)?, Leafv(_)=> return None };
+		}
+		let mut ret = w.clone();
+		absolutize(&mut ret, line, start);
+		Some(ret)
+	}
+
+	/// replaces the bytes in range with replacement, and brings the tree up to date. Only the smallest block of lines the edit falls in, a line and the lines indented under it, is parsed again, or the blocks around it too, if the edit changes how they fit together, EG, by unindenting a line or leaving a raw string open. Returns which were reparsed, the path, from the root, of the branch they're in, and the range of its elements that replaced them. If the new text doesn't parse, nothing changes, and the error is the one parse_multiline_termpose would give
+	pub fn edit(&mut self, range:Range<usize>, replacement:&str)-> Result<(Vec<usize>, Range<usize>), PositionedError> {
+		if self.root.contents().len() == 0 { return self.reparse_all(range, replacement); }
+		//from the root down to the smallest block the edit falls in
+		let mut levels = vec!(Level::new(branch_at(&self.root, &[]), Vec::new(), position(&self.root).0, 0, self.src.len(), &self.src, &range).unwrap());
+		loop {
+			let next = {
+				let l = levels.last().unwrap();
+				if l.touched.len() != 1 { break; }
+				let i = l.touched.start;
+				let b = branch_at(&self.root, &l.path);
+				match b.v[i] {
+					Branchv(ref c)=> {
+						let mut path = l.path.clone();
+						path.push(i);
+						Level::new(c, path, l.line + c.line, l.start + c.meta().extent.start, l.block_end(b, &self.src, i), &self.src, &range)
+					}
+					Leafv(_)=> None,
+				}
+			};
+			match next {
+				Some(l)=> levels.push(l),
+				None=> break,
+			}
+		}
+		//reparsing the blocks the edit touches, and if they don't parse on their own, the blocks around those
+		let mut li = levels.len() - 1;
+		loop {
+			let (region_start, region_end, region_line) = {
+				let l = &levels[li];
+				let b = branch_at(&self.root, &l.path);
+				let start = l.block_start(b, &self.src, l.touched.start);
+				(start, l.block_end(b, &self.src, l.touched.end - 1), if start == 0 { 0 }else{ l.line + b.v[l.touched.start].line_and_col().0 })
+			};
+			let mut region = self.src[region_start..range.start].to_string();
+			region.push_str(replacement);
+			region.push_str(&self.src[range.end..region_end]);
+			let indent = levels[li].indent;
+			//the first line has to still be at the block's level
+			let in_place = li == 0 || (region.get(..indent) == self.src.get(region_start..region_start + indent) && region[indent..].starts_with(|c| !(c == ' ' || c == '\t' || c == '\n' || c == '\r')));
+			if !in_place { li -= 1; continue; }
+			match parse_termpose_block(&region, indent) {
+				Ok(parsed)=> {
+					let parsed = match parsed { Branchv(b)=> b.v, Leafv(_)=> unreachable!() };
+					if parsed.is_empty() {
+						//the branch's indented lines are all gone, and that changes what it is
+						if li > 0 { li -= 1; continue; }
+						if levels[0].touched.len() == levels[0].lines.len() { return self.reparse_all(range, replacement); }
+					}
+					let lines = count_lines(&region) - count_lines(&self.src[region_start..region_end]);
+					let bytes = replacement.len() as isize - (range.end - range.start) as isize;
+					let reparsed = self.splice(&levels[..li + 1], parsed, region_line, region_start, lines, bytes);
+					self.src.replace_range(range, replacement);
+					return Ok((levels[li].path.clone(), reparsed));
+				}
+				Err((mut e, ran_out))=> {
+					if ran_out && li > 0 { li -= 1; continue; }
+					let roots = &mut levels[0];
+					if ran_out && roots.touched.end < roots.lines.end {
+						//a raw string was left open, so it's read on into the roots after, as many more each time
+						roots.touched.end = (roots.touched.end + roots.touched.len()).min(roots.lines.end);
+						continue;
+					}
+					e.line += region_line;
+					return Err(e);
+				}
+			}
+		}
+	}
+
+	//puts what the touched blocks of the last level parsed as in their place, and moves everything after them
+	fn splice(&mut self, levels:&[Level], mut parsed:Vec<Wood>, region_line:isize, region_start:usize, lines:isize, bytes:isize)-> Range<usize> {
+		let l = levels.last().unwrap();
+		for w in parsed.iter_mut() { relativize(w, l.line - region_line, l.start as isize - region_start as isize); }
+		let n = parsed.len();
+		let first = l.touched.start;
+		branch_at_mut(&mut self.root, &l.path).v.splice(l.touched.clone(), parsed);
+		//each level's elements after the one the edit was in move, and it ends where its last element does
+		let mut after = first + n;
+		for l in levels.iter().rev() {
+			for w in branch_at_mut(&mut self.root, &l.path).v[after..].iter_mut() { shift_node(w, lines, bytes); }
+			let w = match l.path.split_last() {
+				Some((&i, parent))=> {
+					after = i + 1;
+					&mut branch_at_mut(&mut self.root, parent).v[i]
+				}
+				None=> &mut self.root,
+			};
+			end_with_last(w);
+		}
+		first..first + n
+	}
+
+	fn reparse_all(&mut self, range:Range<usize>, replacement:&str)-> Result<(Vec<usize>, Range<usize>), PositionedError> {
+		let mut src = self.src.clone();
+		src.replace_range(range, replacement);
+		*self = IncrementalTermpose::new(src)?;
+		Ok((Vec::new(), 0..self.root.contents().len()))
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn same_everywhere(a:&Wood, b:&Wood)-> bool {
+		a == b && a.iter_dfs().zip(b.iter_dfs()).all(|((x, _, _), (y, _, _))| x.span() == y.span())
+	}
+
+	#[test]
+	fn incremental() {
+		let mut d = IncrementalTermpose::new("a b\n  c\nd\n\ne (f)\n  g\n".into()).unwrap();
+		assert_eq!(d.edit(14..15, "ff x").unwrap(), (vec!(), 2..3));
+		assert!(same_everywhere(&d.wood(), &parse_multiline_termpose("a b\n  c\nd\n\ne (ff x)\n  g\n").unwrap()));
+		//indenting a root makes it part of the one before
+		assert_eq!(d.edit(8..8, "  ").unwrap(), (vec!(), 0..1));
+		assert!(same_everywhere(&d.wood(), &parse_multiline_termpose("a b\n  c\n  d\n\ne (ff x)\n  g\n").unwrap()));
+		let before = d.wood();
+		assert!(d.edit(2..3, ")").is_err());
+		assert!(same_everywhere(&d.wood(), &before));
+		assert_eq!(CstNode::from_parsed(&d.wood(), d.src()).unwrap(), parse_cst(d.src()).unwrap());
+
+		//only the innermost block is reparsed, and what's after it is right without being reparsed
+		let src = "config\n  server\n    port 80\n    host x\n  client\n    retries 3\n";
+		let mut d = IncrementalTermpose::new(src.into()).unwrap();
+		let at = src.find("80").unwrap();
+		assert_eq!(d.edit(at..at + 2, "8080\n    tls").unwrap(), (vec!(0, 1), 1..3));
+		let whole = parse_multiline_termpose(d.src()).unwrap();
+		assert!(same_everywhere(&d.wood(), &whole));
+		assert!(same_everywhere(&d.subtree(&[0, 2]).unwrap(), &whole[0][2]));
+		//unindenting a line takes it out of the block, so the blocks around it are reparsed
+		let at = d.src().find("    host").unwrap();
+		assert_eq!(d.edit(at..at + 2, "").unwrap(), (vec!(0), 1..3));
+		assert!(same_everywhere(&d.wood(), &parse_multiline_termpose(d.src()).unwrap()));
+		//a raw string left open in a block runs on past it, until something closes it
+		let at = d.src().find("retries").unwrap();
+		assert_eq!(d.edit(at..at, "\"\"\"").unwrap_err().msg, "raw string was never closed");
+		let end = d.src().len();
+		assert_eq!(d.edit(end..end, "last \"a\"\"\"\n").unwrap(), (vec!(), 0..2));
+		assert_eq!(d.edit(at..at, "\"\"\"").unwrap(), (vec!(), 0..1));
+		assert!(same_everywhere(&d.wood(), &parse_multiline_termpose(d.src()).unwrap()));
+
+		//lots of arbitrary edits, each checked against parsing the whole thing
+		let pieces = ["", "\n", "  ", "\t", "x", "(", ")", ":", "\"", "\n  y z", "\n\n", "\"\"\"", "q\n", "\n    w"];
+		let mut seed = 7u64;
+		let mut rand = |n:usize| { seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); (seed >> 33) as usize % n.max(1) };
+		let nested:String = (0..60).map(|i| format!("{}n{} x\n", "  ".repeat(i % 6), i)).collect();
+		for f in ["tests.term", "longterm.term", "nested"].iter() {
+			let src = if *f == "nested" { nested.clone() }else{ ::std::fs::read_to_string(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(f)).unwrap() };
+			let mut d = IncrementalTermpose::new(src).unwrap();
+			for _ in 0..300 {
+				let src = d.src().to_string();
+				let mut start = rand(src.len() + 1);
+				while !src.is_char_boundary(start) { start -= 1; }
+				let mut end = (start + rand(12)).min(src.len());
+				while !src.is_char_boundary(end) { end -= 1; }
+				let piece = pieces[rand(pieces.len())];
+				let mut new_src = src[..start].to_string();
+				new_src.push_str(piece);
+				new_src.push_str(&src[end..]);
+				match (d.edit(start..end, piece), parse_multiline_termpose(&new_src)) {
+					(Ok(_), Ok(whole))=> {
+						assert_eq!(d.src(), new_src);
+						assert!(same_everywhere(&d.wood(), &whole), "{:?} replacing {:?} in {}", piece, start..end, f);
+					}
+					(Err(a), Err(b))=> {
+						assert_eq!((a.line, a.column, a.msg), (b.line, b.column, b.msg));
+						assert_eq!(d.src(), src);
+					}
+					(a, b)=> panic!("{:?} replacing {:?} in {}: {:?} vs {:?}", piece, start..end, f, a.map(|_| ()), b.map(|_| ())),
+				}
+			}
+		}
+	}
+}
@@ -11,11 +11,34 @@ fn get_back_mut<T>(v:&mut Vec<T>)-> &mut T {
 	else{ panic!("this vec should never be empty"); }
 }
 
+/// made with PositionedError::new, or limit_exceeded, as there may be more to it later
 #[derive(Debug)]
+#[non_exhaustive]
 pub struct PositionedError{
 	pub line:isize,
 	pub column:isize,
 	pub msg:String,
+	/// set if the text may have been fine, but parsing stopped because it went over one of the limits in ParseOptions
+	pub limit:Option<LimitExceeded>,
+	/// the file the text came from, if it was read by parse_file
+	pub file:Option<Arc<str>>,
+}
+
+/// which limit from ParseOptions was gone over, and what it was
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LimitExceeded {
+	Depth(usize),
+	Nodes(usize),
+	LeafLength(usize),
+}
+impl Display for LimitExceeded {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+		match *self {
+			LimitExceeded::Depth(n)=> write!(f, "nested more than {} deep", n),
+			LimitExceeded::Nodes(n)=> write!(f, "more than {} nodes", n),
+			LimitExceeded::LeafLength(n)=> write!(f, "a leaf longer than {} bytes", n),
+		}
+	}
 }
 
 impl Error for PositionedError {
@@ -24,9 +47,19 @@ impl Error for PositionedError {
 }
 impl Display for PositionedError {
 	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
-		Debug::fmt(self, f)
+		match self.file {
+			Some(ref file)=> write_located(f, Some(file), self.line, self.column, &self.msg),
+			None=> Debug::fmt(self, f),
+		}
 	}
 }
+impl PositionedError {
+	pub fn new(line:isize, column:isize, msg:String)-> Self { PositionedError{ line, column, msg, limit:None, file:None } }
+	/// the error for going over l, which is also its message
+	pub fn limit_exceeded(line:isize, column:isize, l:LimitExceeded)-> Self { PositionedError{ line, column, msg:l.to_string(), limit:Some(l), file:None } }
+	/// the error, as being about the named file, which it will be displayed with
+	pub fn in_file(self, file:Arc<str>)-> Self { PositionedError{ file:Some(file), ..self } }
+}
 
 
 
@@ -63,13 +96,46 @@ fn yank_first<T>(v:Vec<T>)-> T { //you must ensure that the v contains at least
 fn accrete_branch(v:&mut Wood)-> &mut Vec<Wood> {
 	unsafe{
 		replace_self(v, |vv|{
-			let (line, col) = vv.line_and_col();
-			Branchv(Branch{line:line, column:col, v:vec!(vv)})
+			let s = vv.span();
+			Branchv(Branch{line:s.line, column:s.column, meta:meta_with_extent(Extent{ start:s.start, ..Extent::default() }), v:vec!(vv)})
 		}); //safe: branch creation doesn't panic
 	}
 	&mut assume_branch_mut(v).v
 }
 
+//the parsers only know where leaves and parens end, branches that weren't closed by a paren end where their last element ends, which is only known once everything's been read. Doesn't recurse, as the wood may be deep
+fn close_extents(v:&mut Wood){
+	//every branch comes before the branches in it, so going through them backwards, each is closed after its contents
+	let mut branches:Vec<*mut Branch> = Vec::new();
+	let mut stack:Vec<*mut Wood> = vec!(v);
+	while let Some(w) = stack.pop() {
+		if let Branchv(ref mut b) = *unsafe{ &mut *w } { //safe: each node is only reached once, and nothing is moved while the pointers are around
+			branches.push(b);
+			for c in b.v.iter_mut() { stack.push(c); }
+		}
+	}
+	for b in branches.into_iter().rev() {
+		let b = unsafe{ &mut *b };
+		if let Some(l) = b.v.last() {
+			let ls = l.span();
+			if ls.end > b.meta().extent.end {
+				let e = &mut b.meta_mut().extent;
+				e.end = ls.end;
+				e.end_line = ls.end_line;
+				e.end_column = ls.end_column;
+			}
+		}
+	}
+}
+
+//drops wood without recursing, for wood that might be deeper than the stack allows
+pub(super) fn dismantle(w:Wood){
+	let mut stack = vec!(w);
+	while let Some(w) = stack.pop() {
+		if let Branchv(b) = w { stack.extend(b.v); }
+	}
+}
+
 unsafe fn replace_self<T, F>(v:&mut T, f:F) where F : FnOnce(T)-> T { //for replacing a thing with a transformation of itself. It's actually fairly safe, you just have to guarantee that this transformation, f, doesn't panic, or else this function will drop an uninitialized, or v's drop will be called twice, or something. I don't even know, so don't don't panic or else!
 	let res = f(replace(v, uninitialized()));
 	forget(replace(v, res));
@@ -81,6 +147,42 @@ unsafe fn str_from_bounds<'a>(o:*const u8, f:*const u8)-> &'a str {
 
 fn is_whitespace(c:char)-> bool { c == ' ' || c == '\t' }
 
+//whether next is the second half of a line break starting with c, so the two only count as one: the \r\n windows writes, or the \n\r some old software does. Any other \r is a line break of its own
+fn line_break_continues(c:char, next:Option<char>)-> bool {
+	(c == '\r' && next == Some('\n')) || (c == '\n' && next == Some('\r'))
+}
+
+//how many bytes of byte order mark s starts with. It's skipped, but positions still count from the start of s
+fn bom_len(s:&str)-> usize { if s.starts_with('\u{feff}') { '\u{feff}'.len_utf8() }else{ 0 } }
+
+//the char of a \x or \u escape, next giving the chars after the x or u. \xNN is exactly two hex digits, \u{N} is one to six of them in braces
+fn read_code_escape<F>(unicode:bool, mut next:F)-> Result<char, &'static str> where F: FnMut()-> Option<char> {
+	let mut code = 0u32;
+	if unicode {
+		let fail = "\\u must be followed by a code point in braces, like \\u{1F600}";
+		if next() != Some('{') { return Err(fail); }
+		let mut digits = 0;
+		loop {
+			match next() {
+				Some('}') if digits > 0 => break,
+				Some(c) if digits < 6 && c.is_digit(16) => {
+					code = code*16 + c.to_digit(16).unwrap();
+					digits += 1;
+				}
+				_=> return Err(fail),
+			}
+		}
+	}else{
+		for _ in 0..2 {
+			match next().and_then(|c| c.to_digit(16)) {
+				Some(d)=> code = code*16 + d,
+				None=> return Err("\\x must be followed by two hex digits"),
+			}
+		}
+	}
+	std::char::from_u32(code).ok_or("that isn't a unicode scalar value, so it can't be in a string")
+}
+
 fn do_indent(indent:&str, indent_depth:usize, out:&mut String){
 	for _ in 0..indent_depth { out.push_str(indent); }
 }
@@ -103,6 +205,29 @@ pub use self::termpose_parser::*;
 mod woodslist_parser;
 pub use self::woodslist_parser::*;
 
+mod stream;
+pub use self::stream::*;
+
+mod comments;
+pub use self::comments::*;
+
+mod cst;
+pub use self::cst::*;
+
+mod events;
+pub use self::events::*;
+
+mod incremental;
+pub use self::incremental::*;
+
+mod pretty;
+pub use self::pretty::*;
+
+#[cfg(feature = "tokio")]
+mod async_stream;
+#[cfg(feature = "tokio")]
+pub use self::async_stream::*;
+
 
 
 #[cfg(test)]
@@ -235,6 +360,222 @@ mod tests {
 		out
 	}
 	
+	#[test]
+	fn spans() {
+		let src = "a (b \"c d\")\n  é:ff\nlong\n  \"\n    multiline\n    string";
+		let w = parse_multiline_termpose(src).unwrap();
+		let text = |w:&Wood| { let s = w.span(); &src[s.start..s.end] };
+		assert_eq!(text(&w[0]), "a (b \"c d\")\n  é:ff");
+		//the indented line makes the first line's contents into a branch of their own
+		assert_eq!(text(&w[0][0]), "a (b \"c d\")");
+		assert_eq!(text(&w[0][0][1]), "(b \"c d\")");
+		assert_eq!(text(&w[0][0][1][1]), "\"c d\"");
+		assert_eq!(text(&w[0][1]), "é:ff");
+		let ff = w[0][1][1].span();
+		assert_eq!((ff.line, ff.column, ff.end_line, ff.end_column), (1, 5, 1, 6));
+		assert_eq!(text(&w[1][1]), "\"\n    multiline\n    string");
+		assert_eq!(w[1][1].span().end_line, 5);
+		assert_eq!(Wood::leaf("synthetic".into()).span().end_line, -1);
+		
+		let src = "(a \"b c\") é";
+		let w = parse_woodslist(src).unwrap();
+		let text = |w:&Wood| { let s = w.span(); &src[s.start..s.end] };
+		assert_eq!(text(&w[0]), "(a \"b c\")");
+		assert_eq!(text(&w[0][1]), "\"b c\"");
+		assert_eq!(text(&w[1]), "é");
+	}
+	
+	#[test]
+	fn streaming() {
+		let check = |src:&str| {
+			let whole = parse_multiline_termpose(src).unwrap();
+			let streamed:Vec<Wood> = Parser::from_reader(src.as_bytes()).map(|r| r.unwrap()).collect();
+			assert_eq!(streamed.len(), whole.contents().len());
+			for (s, w) in streamed.iter().zip(whole.contents()) {
+				assert!(s.identical(w), "{:?} was streamed as {:?}", w, s);
+			}
+		};
+		let check = |src:&str| {
+			check(src);
+			let lazy:Vec<Wood> = parse_documents(src).map(|r| r.unwrap()).collect();
+			assert!(Wood::branch(lazy).identical(&Wood::branch(parse_multiline_termpose(src).unwrap().into_iter().collect())));
+		};
+		check(&read_file_from_root("longterm.term"));
+		check(&windowsify(&read_file_from_root("shortterm.term")));
+		check("a\n  b \"\n    multi\n    line\n\n\nc d:e\n\t \nf");
+		check("");
+		
+		//roots before an error still come through
+		let mut p = Parser::from_reader("a\nb (\nc)\nd".as_bytes());
+		assert_eq!(p.next().unwrap().unwrap(), wood!(a));
+		let unclosed = p.next().unwrap().unwrap();
+		assert_eq!((unclosed[1].span().start, unclosed[1].span().end), (4, 5));
+		match p.next() {
+			Some(Err(WoodError::ParserError(e)))=> assert_eq!(e.line, 2),
+			other=> panic!("expected a parser error, got {:?}", other),
+		}
+		assert!(p.next().is_none());
+		let mut d = parse_documents("a\nb (\nc)\nd");
+		assert_eq!(d.nth(1).unwrap().unwrap().span().start, 2);
+		assert_eq!(d.next().unwrap().unwrap_err().line, 2);
+		assert!(d.next().is_none());
+	}
+	
+	#[test]
+	fn recovering() {
+		let src = "server\n  port 80\n  tags (a b))\n  motd \"hi\\q there\"\n \tbad indent\n  name: x\n: y\nlast\n\"\"\"\nnever closed";
+		let (w, errors) = parse_termpose_recovering(src);
+		assert_eq!(errors.iter().map(|e| (e.line, e.msg.as_str())).collect::<Vec<_>>(), vec!(
+			(2, "unmatched paren"),
+			(3, "escape slash must be followed by a valid escape character code"),
+			(4, "inconsistent indentation"),
+			(6, "no previous term, cannot open a colon here"),
+			(9, "raw string was never closed"),
+		));
+		//everything else is still there
+		assert_eq!(w.contents().len(), 4);
+		assert_eq!(w[0], wood!(server (port 80) (tags (a b) ")") (motd hi "q there\"") "bad indent" (name x)));
+		let placeholders:Vec<&str> = w.iter_dfs().map(|n| n.0).filter(|n| n.is_error_placeholder()).map(|n| n.initial_str()).collect();
+		assert_eq!(placeholders, vec!(")", "q there\"", "bad indent", ": y", "never closed"));
+		assert_eq!(w[0][4].line_and_col(), (4, 3));
+		assert_eq!(w[0][4].meta().unparsed.as_ref().unwrap(), "inconsistent indentation");
+		assert!(!w[0][2][1].is_error_placeholder());
+		assert_eq!(w[2], "last");
+		let (fine, none) = parse_termpose_recovering(src.split(")\n").next().unwrap());
+		assert!(none.is_empty() && fine.contents().len() == 1);
+		//limits still stop it
+		let (empty, limited) = parse_termpose_recovering_with(&ParseOptions{ max_nodes:Some(3), ..ParseOptions::default() }, src);
+		assert!(empty.contents().len() == 0 && limited.last().unwrap().limit.is_some());
+		//where there are no errors it's the same as parse_multiline_termpose, and the first error is the same as its error
+		let text = ::std::fs::read_to_string(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests.term")).unwrap();
+		let mangled = text.replace("  ", " \t").replace("(", "\\q(");
+		for t in [&text, &mangled].iter() {
+			for end in (0..t.len()).filter(|&i| t.is_char_boundary(i)) {
+				let (w, errors) = parse_termpose_recovering(&t[..end]);
+				match parse_multiline_termpose(&t[..end]) {
+					Ok(strict)=> assert!(errors.is_empty() && w == strict),
+					Err(e)=> assert_eq!((errors[0].line, errors[0].column), (e.line, e.column)),
+				}
+			}
+		}
+	}
+	
+	#[test]
+	fn parse_options() {
+		let src = "a\n\tb:c\n\t(d (e))";
+		assert_eq!(parse_termpose_with(&ParseOptions::default(), src).unwrap(), parse_termpose(src).unwrap());
+		let wide = ParseOptions{ tab_width:4, ..ParseOptions::default() };
+		assert_eq!(parse_termpose_with(&wide, src).unwrap()[1].line_and_col(), (1, 5));
+		let err = parse_termpose_with(&ParseOptions{ allow_tabs:false, ..ParseOptions::default() }, src).unwrap_err();
+		assert_eq!((err.line, err.msg.as_str()), (1, "tabs aren't allowed in indentation"));
+		assert!(parse_termpose_with(&ParseOptions{ allow_tabs:false, ..ParseOptions::default() }, "a\n  b\tc").is_ok());
+		assert_eq!(parse_termpose_with(&ParseOptions{ pairing:false, ..ParseOptions::default() }, "a b:c").unwrap(), wood!(a "b:c"));
+		let shallow = ParseOptions{ max_depth:Some(2), ..ParseOptions::default() };
+		assert!(parse_termpose_with(&shallow, "a (b c)").is_ok());
+		let err = parse_termpose_with(&shallow, src).unwrap_err();
+		assert_eq!((err.line, err.column, err.msg.as_str()), (2, 6, "nested more than 2 deep"));
+		assert!(parse_termpose_with(&shallow, "a:b:c:d").is_err());
+	}
+	
+	#[test]
+	fn raw_strings() {
+		let src = "script \"\"\"\necho \"hi\" # not a comment\n  indented\\n:(\n\"\"\" after\nnext a\"\"\"b\"\"\"";
+		let expected = wood!((script "echo \"hi\" # not a comment\n  indented\\n:(\n" after) (next (a b)));
+		assert_eq!(parse_multiline_termpose(src).unwrap(), expected);
+		assert_eq!(parse_multiline_termpose_with_comments(src).unwrap(), expected);
+		assert_eq!(parse_documents(src).map(|r| r.unwrap()).collect::<Vec<Wood>>(), expected.contents().cloned().collect::<Vec<Wood>>());
+		assert_eq!(parse_cst(src).unwrap().to_string(), src);
+		let s = parse_multiline_termpose(src).unwrap()[0][1].span();
+		assert_eq!((s.line, s.column, s.end_line, s.end_column), (0, 8, 3, 3));
+		assert_eq!(parse_termpose("\"\"\"\"\"\"").unwrap(), "");
+		assert_eq!(parse_termpose("a \"\"\"\nb").unwrap_err().msg, "raw string was never closed");
+		
+		let raw_style = TermposeStyle{ raw_strings:true, ..DEFAULT_STYLE.clone() };
+		let w = wood!(config (motd "line one\n\tline two") (quote "a\n\"") plain);
+		let printed = pretty_termpose_detail(&w, false, 2, 73, &raw_style);
+		assert!(printed.contains("\"\"\"\nline one\n\tline two\"\"\"") && printed.contains("quote:a\\n\\\" "));
+		assert_eq!(parse_termpose(&printed).unwrap(), w);
+		assert!(!pretty_termpose(&w).contains("\"\"\""));
+	}
+	
+	#[test]
+	fn code_escapes() {
+		assert_eq!(parse_termpose("a \"\\u{1F600} \\x41\\x0a\" b\\u{e9}").unwrap(), wood!(a "😀 A\n" "bé"));
+		assert_eq!(parse_woodslist("\"\\u{1f600}\\x7E\"").unwrap(), "😀~");
+		for bad in ["\\u{}", "\\u{110000}", "\\u{D800}", "\\u1234", "\\u{1234567}", "\\xG0", "\\x4"].iter() {
+			assert!(parse_termpose(bad).is_err(), "{}", bad);
+			assert!(parse_woodslist(bad).is_err(), "{}", bad);
+		}
+		
+		let w = wood!(a "\u{7}\u{200b}é\n");
+		let printed = |escapes| pretty_termpose_detail(&w, false, 2, 73, &TermposeStyle{ escapes, ..DEFAULT_STYLE.clone() });
+		assert_eq!(printed(EscapePolicy::Minimal), "a \u{7}\u{200b}é\\n");
+		assert_eq!(printed(EscapePolicy::Printable), "a \\x07\\u{200B}é\\n");
+		assert_eq!(printed(EscapePolicy::Ascii), "a \\x07\\u{200B}\\u{E9}\\n");
+		for &e in [EscapePolicy::Minimal, EscapePolicy::Printable, EscapePolicy::Ascii].iter() {
+			assert_eq!(parse_termpose(&printed(e)).unwrap(), w);
+		}
+	}
+	
+	#[test]
+	fn quoting_options() {
+		let singles = ParseOptions{ extra_quotes:vec!('\''), ..ParseOptions::default() };
+		let src = "a 'b \"c\" \\'d' x'y z' \"e 'f\"";
+		assert_eq!(parse_termpose_with(&singles, src).unwrap(), wood!(a "b \"c\" 'd" (x "y z") "e 'f"));
+		assert_eq!(parse_termpose("a 'b c'").unwrap(), wood!(a "'b" "c'"));
+		assert_eq!(parse_termpose_with(&singles, "m '\n  one\n  two").unwrap(), wood!(m "one\ntwo"));
+		let strict = ParseOptions{ forbidden_unquoted:vec!(';', '='), ..ParseOptions::default() };
+		assert_eq!(parse_termpose_with(&strict, "a \"b=c\" d\\x3Be").unwrap(), wood!(a "b=c" "d;e"));
+		let err = parse_termpose_with(&strict, "a\n  b=c").unwrap_err();
+		assert_eq!((err.line, err.column, err.msg.as_str()), (1, 4, "'=' isn't allowed in a leaf unless it's quoted or escaped"));
+		assert!(parse_termpose_with(&strict, ";").is_err());
+	}
+	
+	#[test]
+	fn limits() {
+		let text = read_file_from_root("longterm.term");
+		let nodes = parse_multiline_termpose(&text).unwrap().iter_dfs().count() - 1;
+		let capped = |max_nodes| ParseOptions{ max_nodes:Some(max_nodes), ..ParseOptions::default() };
+		assert!(parse_multiline_termpose_with(&capped(nodes), &text).is_ok());
+		assert_eq!(parse_multiline_termpose_with(&capped(nodes - 1), &text).unwrap_err().limit, Some(LimitExceeded::Nodes(nodes - 1)));
+		
+		let short = ParseOptions{ max_leaf_length:Some(3), ..ParseOptions::default() };
+		assert!(parse_termpose_with(&short, "abc \"def\"").is_ok());
+		let err = parse_termpose_with(&short, "abc\n  \"d e f\"").unwrap_err();
+		assert_eq!((err.line, err.column, err.limit), (1, 7, Some(LimitExceeded::LeafLength(3))));
+		assert_eq!(err.msg, "a leaf longer than 3 bytes");
+		
+		let shallow = ParseOptions{ max_depth:Some(2), ..ParseOptions::default() };
+		assert!(parse_termpose_with(&shallow, "a\n\t(d ())").is_ok());
+		assert_eq!(parse_termpose_with(&shallow, "a:b:c:d").unwrap_err().limit, Some(LimitExceeded::Depth(2)));
+		//stopped long before it gets deep enough to be any trouble, and not malformed, so not stopped for that
+		let deep = "(".repeat(1_000_000);
+		let err = parse_termpose_with(&ParseOptions{ max_depth:Some(100), ..ParseOptions::default() }, &deep).unwrap_err();
+		assert_eq!((err.column, err.limit), (102, Some(LimitExceeded::Depth(100))));
+		assert!(parse_termpose("a )").unwrap_err().limit.is_none());
+	}
+	
+	#[test]
+	fn line_endings() {
+		let w = parse_multiline_termpose("\u{feff}a b\r\n  c\r\n\r\nd").unwrap();
+		assert_eq!(w, wood!(((a b) c) d));
+		assert_eq!((w[0][0][0].span().start, w[0][0][0].line_and_col()), (3, (0, 1)));
+		assert_eq!((w[0][1].line_and_col(), w[1].line_and_col()), ((1, 3), (3, 1)));
+		assert_eq!(parse_woodslist("\u{feff}(a \"b\r\nc\")\r\nd").unwrap(), wood!((a "b\nc") d));
+		assert_eq!(parse_woodslist("\u{feff}x").unwrap().line_and_col(), (0, 1));
+		assert_eq!(parse_termpose_with_comments("\u{feff}# c\r\na").unwrap().comments()[0].column, 1);
+		let src = "\u{feff}a\r\n  b";
+		assert_eq!(parse_cst(src).unwrap().to_string(), src);
+		let streamed:Vec<Wood> = Parser::from_reader("a\r\nb\r\n".as_bytes()).map(|r| r.unwrap()).collect();
+		assert_eq!(streamed[1].line_and_col(), (1, 1));
+		
+		let src = "m \"\r\n  one\r\n  two\r\nr \"\"\"\r\nthree\r\nfour\"\"\"";
+		assert_eq!(parse_multiline_termpose(src).unwrap(), wood!((m "one\ntwo") (r "three\nfour")));
+		let keep = ParseOptions{ keep_cr:true, ..ParseOptions::default() };
+		assert_eq!(parse_multiline_termpose_with(&keep, src).unwrap(), wood!((m "one\r\ntwo") (r "three\r\nfour")));
+		assert_eq!(parse_multiline_termpose_with(&keep, &src.replace("\r\n", "\n")).unwrap(), wood!((m "one\ntwo") (r "three\nfour")));
+	}
+	
 	#[test]
 	fn windows_style_line_endings() {
 		let shortterm = windowsify(read_file_from_root("shortterm.term").as_str());
@@ -254,6 +595,37 @@ mod tests {
 		}
 	}
 	
+	#[test]
+	fn writing() {
+		struct Counting{ out:Vec<u8>, writes:usize, fail_after:usize }
+		impl std::io::Write for Counting {
+			fn write(&mut self, b:&[u8])-> std::io::Result<usize> {
+				if self.out.len() >= self.fail_after { return Err(std::io::Error::new(std::io::ErrorKind::Other, "full")); }
+				self.writes += 1;
+				self.out.extend_from_slice(b);
+				Ok(b.len())
+			}
+			fn flush(&mut self)-> std::io::Result<()> { Ok(()) }
+		}
+		let long = parse_multiline_termpose(&read_file_from_root("longterm.term")).unwrap();
+		let w = Wood::branch((0..20).map(|_| long.clone()).collect());
+		let mut c = Counting{ out:Vec::new(), writes:0, fail_after:usize::MAX };
+		w.write_to(&mut c).unwrap();
+		assert_eq!(String::from_utf8(c.out).unwrap(), to_woodslist(&w));
+		assert!(c.writes > 1);
+		let mut s = String::new();
+		w.write_fmt_to(&mut s).unwrap();
+		assert_eq!(s, to_woodslist(&w));
+		assert_eq!(w.to_string(), to_woodslist(&w));
+		assert_eq!(format!("{:#}", w), indented_woodslist(&w));
+		assert_eq!(format!("{:#}", wood!(a (b c))), "(a (b c))");
+		assert!(w.write_to(&mut Counting{ out:Vec::new(), writes:0, fail_after:10000 }).is_err());
+		
+		let mut out = Vec::new();
+		serialize_to_writer(&vec!(vec!(1u32, 2), vec!(3)), &mut out).unwrap();
+		assert_eq!(String::from_utf8(out).unwrap(), serialize(&vec!(vec!(1u32, 2), vec!(3))));
+	}
+	
 	#[test]
 	fn woodslist_parser_skips_first_newline() {
 		let w = parse_woodslist("aaa \"\naa sdi \n  idj\" a").unwrap();
@@ -0,0 +1,237 @@
+//printing termpose the way a person would write it. Each branch goes on one line if it fits in what's left of the line after the indentation, otherwise it's written as its first element with the rest on the indented lines after it, each of which gets the same choice. So small structures stay compact wherever they are and big ones are broken up as far as they need to be, and leaves with line breaks in them become multiline strings where they can. What comes out always parses back to the same wood
+
+use super::*;
+
+/// how Wood::to_pretty_string_with lays things out. The default is what to_pretty_string does
+#[derive(Clone, Debug)]
+pub struct PrintOptions {
+	pub style: TermposeStyle,
+	/// what each level of indentation is written as. Default two spaces
+	pub indent: String,
+	/// how many chars wide lines can be, indentation included. A branch that won't fit on its line is broken up over indented lines. Lines only go over when there's something too long that can't be broken, a leaf, or the first element of a branch, which always goes on one line. Default 80
+	pub max_width: usize,
+	/// how many chars a tab in indent counts for. Default 4
+	pub tab_width: usize,
+	/// whether a leaf with line breaks in it is written as a multiline string, a " with the string on the indented lines after it, where it's the last thing on its line and the string can be written that way. Otherwise it gets escapes, or is a raw string if the style says so. Default true
+	pub multiline_strings: bool,
+}
+impl Default for PrintOptions {
+	fn default()-> Self {
+		PrintOptions{ style:DEFAULT_STYLE.clone(), indent:"  ".into(), max_width:80, tab_width:4, multiline_strings:true }
+	}
+}
+
+//whether v comes back the same from a multiline string. The parser takes the string's indentation from its first line, drops blank lines from either end, and only keeps blank lines in the middle if they're indented, and nothing in it can be escaped
+fn fits_multiline(v:&str, policy:EscapePolicy)-> bool {
+	v.contains('\n') && !v.contains('\r') && !v.starts_with(is_whitespace) && !v.ends_with('\n') &&
+		v.split('\n').all(|l| l.is_empty() || !l.chars().all(is_whitespace)) &&
+		!v.chars().any(|c| policy.escapes_as_code(c))
+}
+
+struct Printer<'a> {
+	options: &'a PrintOptions,
+}
+
+impl<'a> Printer<'a> {
+	fn style(&self)-> &TermposeStyle { &self.options.style }
+	
+	fn leaf(&self, l:&Leaf, out:&mut String) {
+		if l.v.is_empty() {
+			out.push_str("\"\"");
+		}else{
+			stringify_leaf_termpose(l, out, self.style());
+		}
+	}
+	fn is_multiline(&self, w:&Wood)-> bool {
+		match *w {
+			Leafv(ref l)=> self.options.multiline_strings && fits_multiline(&l.v, self.style().escapes),
+			Branchv(_)=> false,
+		}
+	}
+	fn pairs(&self, b:&Branch)-> bool { b.v.len() == 2 && b.v[0].is_leaf() }
+	
+	//w as it's written in the middle of a line, parens and all
+	fn inline(&self, w:&Wood, out:&mut String) {
+		match *w {
+			Leafv(ref l)=> self.leaf(l, out),
+			Branchv(ref b)=> if self.pairs(b) {
+				self.inline(&b.v[0], out);
+				out.push(self.style().pairing);
+				self.inline(&b.v[1], out);
+			}else{
+				out.push(self.style().open);
+				self.terms(&b.v, None, out);
+				out.push(self.style().close);
+			},
+		}
+	}
+	//how many chars inline would write, or something over limit if it's more than that
+	fn inline_width(&self, w:&Wood, limit:usize)-> usize {
+		match *w {
+			Leafv(ref l)=> {
+				let mut s = String::new();
+				self.leaf(l, &mut s);
+				s.chars().count()
+			}
+			Branchv(ref b)=> {
+				let mut n = if self.pairs(b) { 0 }else{ 2 };
+				for (i, c) in b.v.iter().enumerate() {
+					if i != 0 { n += 1; }
+					if n > limit { break; }
+					n += self.inline_width(c, limit - n);
+				}
+				n
+			}
+		}
+	}
+	//the terms of a line, space separated. If the line ends at depth, the last can be a multiline string
+	fn terms(&self, v:&[Wood], ends_line:Option<usize>, out:&mut String) {
+		for (i, c) in v.iter().enumerate() {
+			if i != 0 { out.push(' '); }
+			match ends_line {
+				Some(depth) if i == v.len() - 1 && self.is_multiline(c)=> self.multiline(c, depth, out),
+				_=> self.inline(c, out),
+			}
+		}
+	}
+	fn terms_width(&self, v:&[Wood], limit:usize)-> usize {
+		let mut n = v.len().saturating_sub(1);
+		for (i, c) in v.iter().enumerate() {
+			if n > limit { break; }
+			n += if i == v.len() - 1 && self.is_multiline(c) { 1 }else{ self.inline_width(c, limit - n) };
+		}
+		n
+	}
+	fn multiline(&self, w:&Wood, depth:usize, out:&mut String) {
+		if let Leafv(ref l) = *w {
+			out.push('"');
+			for line in l.v.split('\n') {
+				self.newline(depth + 1, out);
+				out.push_str(line);
+			}
+		}
+	}
+	fn newline(&self, depth:usize, out:&mut String) {
+		out.push('\n');
+		for _ in 0..depth { out.push_str(&self.options.indent); }
+	}
+	
+	//how many chars are left of a line at depth after its indentation
+	fn room(&self, depth:usize)-> usize {
+		let indent:usize = self.options.indent.chars().map(|c| if c == '\t' { self.options.tab_width }else{ 1 }).sum();
+		self.options.max_width.saturating_sub(indent*depth)
+	}
+	
+	//w as a line of its own at depth, followed by whatever lines it needs after it. out is just past the line's indentation
+	fn line(&self, w:&Wood, depth:usize, out:&mut String) {
+		let limit = self.room(depth);
+		match *w {
+			Leafv(_)=> if self.is_multiline(w) { self.multiline(w, depth, out) }else{ self.inline(w, out) },
+			Branchv(ref b)=> if b.v.len() > 1 {
+				if self.terms_width(&b.v, limit) <= limit {
+					self.terms(&b.v, Some(depth), out);
+				}else{
+					//the first element, all on the first line, is what the lines under it go with
+					match b.v[0] {
+						Branchv(ref h) if h.v.len() > 1=> self.terms(&h.v, None, out),
+						ref h=> self.inline(h, out),
+					}
+					for c in b.v[1..].iter() {
+						self.newline(depth + 1, out);
+						self.line(c, depth + 1, out);
+					}
+				}
+			}else{
+				match b.v.first() {
+					//an open paren on its own takes the lines under it as one branch, so that's a branch with only that in it
+					Some(&Branchv(ref only)) if !only.v.is_empty() && self.inline_width(w, limit) > limit=> {
+						out.push(self.style().open);
+						for c in only.v.iter() {
+							self.newline(depth + 1, out);
+							self.line(c, depth + 1, out);
+						}
+					}
+					_=> self.inline(w, out),
+				}
+			},
+		}
+	}
+	//w as a whole document. A branch too long for one line is written as its contents, each a root starting a line of its own, which is how parse_termpose reads more than one root
+	fn roots(&self, w:&Wood, out:&mut String) {
+		match *w {
+			Branchv(ref b) if b.v.len() > 1 && self.terms_width(&b.v, self.options.max_width) > self.options.max_width=> {
+				for (i, c) in b.v.iter().enumerate() {
+					if i != 0 { out.push('\n'); }
+					self.line(c, 0, out);
+				}
+			}
+			_=> self.line(w, 0, out),
+		}
+	}
+}
+
+impl Wood {
+	/// termpose for people to read and edit, laid out as options says. parse_termpose gives back the same wood. To write out what parse_multiline_termpose gave, with each root starting a line of its own, print each root separately, or use pretty_termpose_detail
+	pub fn to_pretty_string_with(&self, options:&PrintOptions)-> String {
+		let mut out = String::new();
+		Printer{ options }.line(self, 0, &mut out);
+		out
+	}
+	/// `to_pretty_string_with(&PrintOptions::default())`
+	pub fn to_pretty_string(&self)-> String { self.to_pretty_string_with(&PrintOptions::default()) }
+}
+
+///Indents and uses pairing when appropriate. Unlike to_pretty_string, a branch too long for one line is written as roots, one to a line, and leaves are never written as multiline strings
+/// # Arguments
+///
+/// * `column_limit` - how many chars wide lines can be, indentation included. Tabs count for tab_size
+pub fn pretty_termpose_detail(w:&Wood, indent_is_tab:bool, tab_size:usize, column_limit:usize, style:&TermposeStyle)-> String {
+	let options = PrintOptions{
+		style: style.clone(),
+		indent: if indent_is_tab { "\t".into() }else{ " ".repeat(tab_size) },
+		max_width: column_limit,
+		tab_width: tab_size,
+		multiline_strings: false,
+	};
+	let mut ret = String::new();
+	Printer{ options:&options }.roots(w, &mut ret);
+	ret
+}
+
+///`pretty_termpose_detail(w, false, 2, 73, &DEFAULT_STYLE)`
+pub fn pretty_termpose(w:&Wood)-> String {
+	pretty_termpose_detail(w, false, 2, 73, &DEFAULT_STYLE)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn pretty_printing() {
+		let w = wood!(server (port 80) (motd "first line\n  second\n\nlast") (names alpha beta "" "gamma delta") ((x) (y z)) (escaped "\ta\nb"));
+		let narrow = PrintOptions{ max_width:20, ..PrintOptions::default() };
+		assert_eq!(w.to_pretty_string_with(&narrow), "server\n  port 80\n  motd \"\n    first line\n      second\n    \n    last\n  names\n    alpha\n    beta\n    \"\"\n    \"gamma delta\"\n  (x) y:z\n  escaped \"\\ta\\nb\"");
+		assert_eq!(wood!(a (b c) (d e f)).to_pretty_string(), "a b:c (d e f)");
+		assert_eq!(wood!((((a b c d e)))).to_pretty_string_with(&PrintOptions{ max_width:13, indent:"\t".into(), ..PrintOptions::default() }), "(\n\ta b c d e");
+		//the deeper it goes the less room there is
+		assert_eq!(wood!(a (b c d) (e (f g h) (i (j k l)))).to_pretty_string_with(&PrintOptions{ max_width:11, ..PrintOptions::default() }), "a\n  b c d\n  e\n    f g h\n    i\n      j k l");
+		assert_eq!(wood!(a "x\ny").to_pretty_string_with(&PrintOptions{ multiline_strings:false, ..PrintOptions::default() }), "a x\\ny");
+		//pretty_termpose writes a long branch as roots
+		assert_eq!(pretty_termpose_detail(&wood!(a (b c d) (e "x\ny")), false, 2, 12, &DEFAULT_STYLE), "a\nb c d\ne x\\ny");
+		
+		let tricky = wood!(((h i) j k) (() "") ("\n") (" x\ny") ("x\n") ("x\n  \ny") ("x\r\ny") ("a\nb" c) (d "e\nf") ((a b c d e f g h i j) (k l m n o p q r s t)));
+		for width in 0..60 {
+			let options = PrintOptions{ max_width:width, ..PrintOptions::default() };
+			assert_eq!(parse_termpose(&tricky.to_pretty_string_with(&options)).unwrap(), tricky);
+			assert_eq!(parse_termpose(&pretty_termpose_detail(&tricky, false, 2, width, &DEFAULT_STYLE)).unwrap(), tricky);
+			for f in ["tests.term", "longterm.term"].iter() {
+				let text = ::std::fs::read_to_string(::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(f)).unwrap();
+				let w = parse_multiline_termpose(&text).unwrap();
+				let printed:Vec<String> = w.contents().map(|r| r.to_pretty_string_with(&options)).collect();
+				assert_eq!(parse_multiline_termpose(&printed.join("\n")).unwrap(), w);
+				assert_eq!(parse_multiline_termpose(&pretty_termpose_detail(&w, true, 4, width, &DEFAULT_STYLE)).unwrap(), w);
+			}
+		}
+	}
+}
@@ -0,0 +1,197 @@
+//parsing termpose a root at a time, out of an io::Read or lazily out of a str, or skipping the roots that fail. Each root starts at an unindented line, and nothing carries over from one to the next, so it's enough to buffer lines until the next unindented one turns up, then parse what was buffered before it. Memory use is bounded by the size of the biggest root, not by anything smaller
+
+use super::*;
+use std::io::{Read, BufRead, BufReader};
+
+/// iterates over the roots of a termpose document as they're read, so that only one of them needs to be in memory at a time. That's all it saves: each root's text is buffered whole, then parsed into a whole wood, before it's given, so a big document that's all one root is held in memory just as parse_multiline_termpose would hold it. To go through one of those without holding it, use parse_termpose_events, which goes a line at a time. Each root is what would be an element of the branch parse_multiline_termpose returns, with the same positions. Iteration ends after the first error
+pub struct Parser<R> {
+	reader: R,
+	chunker: Chunker,
+	done: bool,
+}
+
+//the part of a streaming parser that doesn't care where the lines come from
+pub(super) struct Chunker {
+	pub(super) style: TermposeStyle,
+	chunk: String, //the lines of the root being read
+	chunk_has_root: bool, //whether chunk has anything in it other than whitespace
+	in_raw: bool, //whether the last line ended inside a raw string, so the next one can't start a root however it's indented
+	line: isize, //where chunk starts
+	offset: usize,
+	pub(super) pending: VecDeque<Wood>,
+}
+impl Chunker {
+	pub(super) fn new()-> Self {
+		Chunker{ style:DEFAULT_STYLE.clone(), chunk:String::new(), chunk_has_root:false, in_raw:false, line:0, offset:0, pending:VecDeque::new() }
+	}
+	//takes the next line, line break and all, parsing what came before it if it starts a new root
+	pub(super) fn push_line(&mut self, line:&str)-> Result<(), PositionedError> {
+		let (crs, starts_root) = line_start(line);
+		self.chunk.push_str(&line[..crs]);
+		let line = &line[crs..];
+		let starts_root = starts_root && !self.in_raw;
+		self.in_raw ^= toggles_raw(line);
+		if starts_root && self.chunk_has_root { self.finish()?; }
+		self.chunk.push_str(line);
+		self.chunk_has_root |= !line.trim().is_empty();
+		Ok(())
+	}
+	//parses whatever's been taken since the last root started
+	pub(super) fn finish(&mut self)-> Result<(), PositionedError> {
+		let chunk = replace(&mut self.chunk, String::new());
+		self.chunk_has_root = false;
+		parse_chunk(&chunk, &self.style, &mut self.line, &mut self.offset, &mut self.pending)
+	}
+}
+
+impl<R:Read> Parser<BufReader<R>> {
+	pub fn from_reader(r:R)-> Self { Parser::from_buf_read(BufReader::new(r)) }
+}
+impl<R:BufRead> Parser<R> {
+	pub fn from_buf_read(r:R)-> Self {
+		Parser{ reader:r, chunker:Chunker::new(), done:false }
+	}
+	pub fn with_style(mut self, style:TermposeStyle)-> Self {
+		self.chunker.style = style;
+		self
+	}
+}
+
+impl<R:BufRead> Iterator for Parser<R> {
+	type Item = Result<Wood, WoodError>;
+	fn next(&mut self)-> Option<Self::Item> {
+		loop {
+			if let Some(w) = self.chunker.pending.pop_front() { return Some(Ok(w)); }
+			if self.done { return None; }
+			let mut line = String::new();
+			let r = match self.reader.read_line(&mut line) {
+				Err(e)=> {
+					self.done = true;
+					return Some(Err(WoodError::IoError(e)));
+				}
+				Ok(0)=> {
+					self.done = true;
+					self.chunker.finish()
+				}
+				Ok(_)=> self.chunker.push_line(&line),
+			};
+			if let Err(e) = r {
+				self.done = true;
+				return Some(Err(WoodError::ParserError(e)));
+			}
+		}
+	}
+}
+
+/// iterates over the roots of a termpose document, parsing each one only when it's reached. See parse_documents
+pub struct Documents<'a> {
+	rest: &'a str,
+	line: isize,
+	offset: usize,
+	pending: VecDeque<Wood>,
+	done: bool,
+}
+impl<'a> Documents<'a> {
+	//the text of the next root, which runs up to the first line after it that starts another
+	fn next_chunk(&mut self)-> Option<&'a str> {
+		if self.done || self.rest.is_empty() { return None; }
+		let mut end = 0;
+		let mut has_root = false;
+		let mut in_raw = false;
+		while end < self.rest.len() {
+			let line_end = self.rest[end..].find('\n').map_or(self.rest.len(), |i| end + i + 1);
+			let (crs, starts_root) = line_start(&self.rest[end..line_end]);
+			if starts_root && has_root && !in_raw {
+				end += crs;
+				break;
+			}
+			in_raw ^= toggles_raw(&self.rest[end..line_end]);
+			has_root |= !self.rest[end..line_end].trim().is_empty();
+			end = line_end;
+		}
+		let (chunk, rest) = self.rest.split_at(end);
+		self.rest = rest;
+		Some(chunk)
+	}
+}
+impl<'a> Iterator for Documents<'a> {
+	type Item = Result<Wood, PositionedError>;
+	fn next(&mut self)-> Option<Self::Item> {
+		loop {
+			if let Some(w) = self.pending.pop_front() { return Some(Ok(w)); }
+			let chunk = self.next_chunk()?;
+			if let Err(e) = parse_chunk(chunk, &DEFAULT_STYLE, &mut self.line, &mut self.offset, &mut self.pending) {
+				self.done = true;
+				return Some(Err(e));
+			}
+		}
+	}
+}
+
+/// each of the roots of a multiline termpose document, parsed lazily, so that a log-style file that's had entries appended to it doesn't have to be parsed all at once, and entries before a syntax error can still be read. The roots are the elements of what parse_multiline_termpose would return, with the same positions
+pub fn parse_documents<'a>(s:&'a str)-> Documents<'a> {
+	Documents{ rest:s, line:0, offset:0, pending:VecDeque::new(), done:false }
+}
+
+//how many '\r's at the start of a line are actually the end of the previous line's newline, as on windows, and whether what comes after them starts a new root
+fn line_start(line:&str)-> (usize, bool) {
+	let crs = line.len() - line.trim_start_matches('\r').len();
+	let starts_root = line[crs..].chars().next().map_or(false, |c| !(c == ' ' || c == '\t' || c == '\n' || c == '\r'));
+	(crs, starts_root)
+}
+
+//whether a raw string opens or closes on the line and is left the other way at the end of it. Each """ opens or closes one
+pub(super) fn toggles_raw(line:&str)-> bool { line.matches("\"\"\"").count() % 2 == 1 }
+
+//parses a chunk that starts at the given line and offset, queues its roots, and moves the line and offset to the end of it, even if it fails
+fn parse_chunk(chunk:&str, style:&TermposeStyle, line:&mut isize, offset:&mut usize, out:&mut VecDeque<Wood>)-> Result<(), PositionedError> {
+	let (start_line, start_offset) = (*line, *offset);
+	*line += count_lines(chunk);
+	*offset += chunk.len();
+	let root = parse_multiline_termpose_style(chunk, style.clone()).map_err(|mut e| { e.line += start_line; e })?;
+	for mut w in root.into_iter() {
+		shift_positions(&mut w, start_line, start_offset as isize);
+		out.push_back(w);
+	}
+	Ok(())
+}
+
+//the line and column the parser would be at after reading s, starting from the given ones
+fn advance(mut line:isize, mut column:isize, s:&str)-> (isize, isize) {
+	let mut i = s.chars().peekable();
+	while let Some(c) = i.next() {
+		if c == '\n' || c == '\r' {
+			line += 1;
+			column = 0;
+			if line_break_continues(c, i.peek().cloned()) { i.next(); }
+		}else{
+			column += 1;
+		}
+	}
+	(line, column)
+}
+
+//how many lines the parser will have counted by the end of s
+pub(super) fn count_lines(s:&str)-> isize { advance(0, 0, s).0 }
+
+pub(super) fn shift_positions(w:&mut Wood, lines:isize, bytes:isize) {
+	if w.line_and_col().0 != -1 { shift_node(w, lines, bytes); }
+	if let Branchv(ref mut b) = *w {
+		for c in b.v.iter_mut() { shift_positions(c, lines, bytes); }
+	}
+}
+
+//moves w's line and byte offsets, but not those of what's in it
+pub(super) fn shift_node(w:&mut Wood, lines:isize, bytes:isize) {
+	let (line, meta) = match *w {
+		Branchv(ref mut b)=> (&mut b.line, &mut b.meta),
+		Leafv(ref mut l)=> (&mut l.line, &mut l.meta),
+	};
+	*line += lines;
+	if let Some(ref mut m) = *meta {
+		let extent = &mut m.extent;
+		extent.end_line += lines;
+		extent.start = (extent.start as isize + bytes) as usize;
+		extent.end = (extent.end as isize + bytes) as usize;
+	}
+}
@@ -6,43 +6,133 @@ struct TermposeParserState<'a>{
 	indent_stack: Vec<&'a str>,
 	indent_branch_stack: Vec<*mut Vec<Wood>>, //the branchs corresponding to each indent level, into which new lines on that level are inserted
 	line_paren_stack: Vec<*mut Wood>,
+	source_start: *const u8, //for working out byte offsets
 	cur_char_ptr: *const u8,
 	//optimization: Consider making these three an untagged union, since only one is used at a time?:
 	stretch_reading_start: *const u8, //used when taking an indent
-	leaf_being_read_into: *mut String,
+	leaf_being_read: *mut Leaf,
 	colon_receptacle: *mut Vec<Wood>,
 	last_completed_term_on_line: *mut Wood, //for attaching the next pairing
 	multilines_indent: &'a str,
+	closing_quote: char, //of the quoted leaf being read
+	node_count: usize,
+	crlf: bool, //whether the last line break was a \r\n
+	limit_hit: Option<LimitExceeded>, //checked after each char
+	errors: Option<Vec<PositionedError>>, //only when recovering, errors are collected here instead of ending the parse
+	bad_indentation: bool, //whether the error just returned was about indentation, which is recovered from differently
+	last_char: Option<char>, //what move_char_ptr_and_update_line_col last gave
+	ran_out: bool, //set on failing in a way more text might have fixed, see parse_termpose_block
 	// previous_line_hanging_term: *mut Wood, //this is the term things will be inserted into if there's an indent.
 	iter: std::str::Chars<'a>,
 	line: isize,
 	column: isize,
 	mode: fn(&mut TermposeParserState<'a>, Option<char>)-> Result<(), PositionedError>,
-	chosen_style: TermposeStyle,
+	options: ParseOptions,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct TermposeStyle {
 	pub open:char,
 	pub close:char,
 	pub pairing:char,
+	/// when printing, leaves with line breaks in them are written as """raw strings""" rather than with escapes, where they can be. The parser reads raw strings either way
+	pub raw_strings:bool,
+	/// which chars get escaped when printing
+	pub escapes:EscapePolicy,
 }
 
-pub static DEFAULT_STYLE:TermposeStyle = TermposeStyle{ open:'(', close:')', pairing:':' };
+pub static DEFAULT_STYLE:TermposeStyle = TermposeStyle{ open:'(', close:')', pairing:':', raw_strings:false, escapes:EscapePolicy::Minimal };
+
+/// how parse_termpose_with reads, for enforcing a house style or turning off syntax that isn't wanted. The default reads the same way parse_termpose does
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+	pub style: TermposeStyle,
+	/// how many columns a tab counts for in the lines and columns given to nodes and errors. Indentation is still compared char by char. Default 1
+	pub tab_width: usize,
+	/// if false, a tab in indentation is an error. Default true
+	pub allow_tabs: bool,
+	/// if false, the pairing char doesn't pair terms and is read as part of leaves like any other. Default true
+	pub pairing: bool,
+	/// how many branches deep a term can be, not counting the one parse_multiline_termpose_with wraps the roots in. Parens, colons and indentation all nest. Default None, no limit. This and the other limits are for parsing text that can't be trusted, going over them is a LimitExceeded error, see PositionedError::limit
+	pub max_depth: Option<usize>,
+	/// chars other than " that quote leaves, each closed by another of itself, like the 's some other implementations accept. They can be escaped inside their own quotes. Only " opens raw strings. Default none
+	pub extra_quotes: Vec<char>,
+	/// chars that are an error in unquoted leaves, unless escaped. Default none
+	pub forbidden_unquoted: Vec<char>,
+	/// how many nodes, leaves and branches, there can be, not counting the root branch. Default None
+	pub max_nodes: Option<usize>,
+	/// how many bytes long a leaf can be. Default None
+	pub max_leaf_length: Option<usize>,
+	/// whether the line breaks in multiline and raw strings keep their \r when they're \r\ns. Default false, so that a file reads the same whichever line endings it was saved with
+	pub keep_cr: bool,
+}
+impl Default for ParseOptions {
+	fn default()-> Self {
+		ParseOptions{ style:DEFAULT_STYLE.clone(), tab_width:1, allow_tabs:true, pairing:true, max_depth:None, extra_quotes:Vec::new(), forbidden_unquoted:Vec::new(), max_nodes:None, max_leaf_length:None, keep_cr:false }
+	}
+}
 
 impl<'a> TermposeParserState<'a> {
 	
-	fn style(&self)-> &TermposeStyle { &self.chosen_style }
+	fn style(&self)-> &TermposeStyle { &self.options.style }
+	fn is_pairing(&self, c:char)-> bool { self.options.pairing && c == self.style().pairing }
+	fn is_quote(&self, c:char)-> bool { c == '"' || self.options.extra_quotes.contains(&c) }
+	fn push_unquoted(&mut self, c:char)-> Result<(), PositionedError> {
+		if self.options.forbidden_unquoted.contains(&c) {
+			return self.a_fail(format!("{:?} isn't allowed in a leaf unless it's quoted or escaped", c));
+		}
+		self.push_to_leaf(c);
+		Ok(())
+	}
+	
+	fn a_fail(&self, message:String)-> Result<(), PositionedError> { Err(PositionedError::new(self.line, self.column, message)) }
+	fn indentation_fail(&mut self, message:&str)-> Result<(), PositionedError> {
+		self.bad_indentation = true;
+		self.a_fail(message.into())
+	}
 	
-	fn a_fail(&self, message:String)-> Result<(), PositionedError> { Err(PositionedError{
-		line: self.line,
-		column: self.column,
-		msg: message,
-	}) }
+	fn mkbranch(&mut self)-> Wood {
+		self.count_node();
+		Branchv(Branch{ line:self.line, column:self.column, meta:meta_with_extent(self.extent_here()), v:Vec::new() })
+	}
+	fn mkleaf(&mut self)-> Wood {
+		self.count_node();
+		Leafv(Leaf{ line:self.line, column:self.column, meta:meta_with_extent(self.extent_here()), v:String::new() })
+	}
+	fn count_node(&mut self) {
+		self.node_count += 1;
+		if let Some(max) = self.options.max_nodes {
+			//+1 for the branch of the line being read, which might yet be collapsed away. The count is checked exactly at the end
+			if self.node_count > max + 1 { self.limit_hit = Some(LimitExceeded::Nodes(max)); }
+		}
+	}
 	
-	fn mkbranch(&self)-> Wood { Branchv(Branch{ line:self.line, column:self.column, v:Vec::new() }) }
+	fn offset_of(&self, p:*const u8)-> usize { p as usize - self.source_start as usize }
+	//an extent starting at the current char, to be ended later
+	fn extent_here(&self)-> Extent { Extent{ start:self.offset_of(self.cur_char_ptr), ..Extent::default() } }
+	//ends the extent just after the current char
+	fn end_extent(&self, e:&mut Extent) {
+		e.end = self.offset_of(self.next_char_ptr());
+		e.end_line = self.line;
+		e.end_column = self.column;
+	}
+	fn extend_leaf(&mut self) {
+		let l = self.leaf_being_read;
+		self.end_extent(unsafe{ &mut (*l).meta_mut().extent }); //safe: leaf_being_read must have been validated before this could be called
+	}
+	fn push_to_leaf(&mut self, c:char) {
+		let len = unsafe{ let v = &mut (*self.leaf_being_read).v; v.push(c); v.len() }; //safe: leaf_being_read must have been validated before this mode could have been entered
+		if let Some(max) = self.options.max_leaf_length {
+			if len > max { self.limit_hit = Some(LimitExceeded::LeafLength(max)); }
+		}
+		self.extend_leaf();
+	}
 	
 	fn start_line(&mut self, c:char)-> Result<(), PositionedError> {
+		self.begin_line();
+		self.start_reading_thing(c)
+	}
+	fn begin_line(&mut self) {
 		let bin:*mut Vec<Wood> = *get_back_mut(&mut self.indent_branch_stack); //there is always at least root in the indent_branch_stack
 		unsafe{
 			(*bin).push(self.mkbranch());
@@ -50,10 +140,10 @@ impl<'a> TermposeParserState<'a> {
 		self.line_paren_stack.clear();
 		self.line_paren_stack.push(get_back_mut(unsafe{ &mut *bin }));
 		self.last_completed_term_on_line = null_mut();
-		self.start_reading_thing(c)
 	}
 	
 	fn consider_collapsing_outer_branch_of_previous_line(&mut self){
+		if self.line_paren_stack.is_empty() { return; } //already done, recovering can end a line more than once
 		let line_term: *mut Wood = self.line_paren_stack[0];
 		let line_branch_length:usize = assume_branch_mut(unsafe{ &mut*line_term }).v.len(); //line_term is always a branch
 		if line_branch_length == 1 {
@@ -63,7 +153,9 @@ impl<'a> TermposeParserState<'a> {
 					yank_first(v)
 				}) //safe: this_line has been proven to be a branch in assume_branch_mut, so assume_branch cannot panic
 			}
+			self.node_count -= 1;
 		}
+		self.line_paren_stack.clear();
 	}
 	
 	fn take_hanging_branch_for_insert(&mut self)-> *mut Vec<Wood> {
@@ -84,6 +176,7 @@ impl<'a> TermposeParserState<'a> {
 				let root_pl_len = assume_branch_mut(rpl).v.len();
 				if root_pl_len > 1 { //then it needs to be its own branch
 					accrete_branch(rpl);
+					self.count_node();
 				}
 				&mut assume_branch_mut(rpl).v
 			}
@@ -92,26 +185,24 @@ impl<'a> TermposeParserState<'a> {
 	
 	fn move_char_ptr_and_update_line_col(&mut self)-> Option<char> {
 		self.cur_char_ptr = self.iter.as_str().as_ptr();
-		self.iter.next().and_then(|c|{
+		let co = self.iter.next().and_then(|c|{
 			if c == '\n' || c == '\r' {
-				//continue over any ensuing '\r's
-				let mut ic = self.iter.clone();
-				//the weird staggered structure reflects the fact that the first \r after a newline is ignored (because of windows)
-				if Some('\r') == ic.next() {
-					self.iter.next();
-					while Some('\r') == ic.next() {
-						self.iter.next();
-						self.line += 1;
-					}
-				}
+				let next = self.iter.clone().next();
+				self.crlf = c == '\r' && next == Some('\n');
+				if line_break_continues(c, next) { self.iter.next(); }
 				self.line += 1;
 				self.column = 0;
 				Some('\n') //note, if it was a pesky '\r', it wont come through that way
+			}else if c == '\t' {
+				self.column += self.options.tab_width as isize;
+				Some(c)
 			}else {
 				self.column += 1;
 				Some(c)
 			}
-		})
+		});
+		self.last_char = co;
+		co
 	}
 	
 	fn next_char_ptr(&self)-> *const u8 { self.iter.as_str().as_ptr() }
@@ -119,7 +210,8 @@ impl<'a> TermposeParserState<'a> {
 	// fn next_line(&mut self){ self.line += 1; self.column = 0; }
 	
 	fn open_paren(&mut self) {
-		let lti = self.mkbranch();
+		let mut lti = self.mkbranch();
+		self.end_extent(&mut assume_branch_mut(&mut lti).meta_mut().extent); //in case it's never closed
 		let branch_for_insert = self.take_hanging_branch_for_insert();
 		unsafe{(*branch_for_insert).push(lti)};
 		self.line_paren_stack.push(unsafe{&mut *get_back_mut(&mut*branch_for_insert)});
@@ -129,6 +221,8 @@ impl<'a> TermposeParserState<'a> {
 		self.colon_receptacle = null_mut();
 		if self.line_paren_stack.len() > 1 {
 			self.last_completed_term_on_line = unsafe{ &mut **get_back_mut(&mut self.line_paren_stack)};
+			let closed = self.last_completed_term_on_line;
+			self.end_extent(&mut assume_branch_mut(unsafe{ &mut *closed }).meta_mut().extent);
 			self.line_paren_stack.pop(); //safe: we just checked and confirmed there's something there
 			Ok(())
 		}else{
@@ -143,28 +237,30 @@ impl<'a> TermposeParserState<'a> {
 		self.colon_receptacle = {
 			let lt = self.take_last_completed_term_on_line();
 			if lt != null_mut() {
+				self.count_node();
 				unsafe{ accrete_branch(&mut *lt) }
 			}else{
-				return Err(PositionedError{line: self.line, column: self.column, msg:"no previous term, cannot open a colon here".into()});
+				return Err(PositionedError::new(self.line, self.column, "no previous term, cannot open a colon here".into()));
 			}
 		};
 		Ok(())
 	}
 	fn begin_leaf(&mut self, branch_for_insert:*mut Vec<Wood>) {
-		let to_push = Leafv(Leaf{line:self.line, column:self.column, v:String::new()});
+		let to_push = self.mkleaf();
 		unsafe{(*branch_for_insert).push(to_push)};
 		self.last_completed_term_on_line = unsafe{get_back_mut(&mut *branch_for_insert)};
-		self.leaf_being_read_into = &mut unsafe{assume_leaf_mut(get_back_mut(&mut *branch_for_insert))}.v;
+		self.leaf_being_read = unsafe{assume_leaf_mut(get_back_mut(&mut *branch_for_insert))};
+		self.extend_leaf(); //over the opening quote
 	}
 	fn begin_leaf_with_char(&mut self, branch_for_insert:*mut Vec<Wood>, c:char)-> Result<(), PositionedError> {
-		let to_push = Leafv(Leaf{line:self.line, column:self.column, v:String::new()});
+		let to_push = self.mkleaf();
 		unsafe{(*branch_for_insert).push(to_push)};
 		self.last_completed_term_on_line = unsafe{get_back_mut(&mut *branch_for_insert)};
-		self.leaf_being_read_into = &mut unsafe{assume_leaf_mut(get_back_mut(&mut *branch_for_insert))}.v;
+		self.leaf_being_read = unsafe{assume_leaf_mut(get_back_mut(&mut *branch_for_insert))};
 		if c == '\\' {
 			try!(self.read_escaped_char());
 		}else{
-			unsafe{(*self.leaf_being_read_into).push(c)};
+			try!(self.push_unquoted(c));
 		};
 		Ok(())
 	}
@@ -179,12 +275,12 @@ impl<'a> TermposeParserState<'a> {
 				self.open_paren();
 				self.mode = Self::seeking_term;
 			},
-			'"'=> {
+			c if self.is_quote(c)=> {
 				let l = self.take_hanging_branch_for_insert();
 				self.begin_leaf(l);
-				self.mode = Self::eating_quoted_string;
+				self.begin_string(c);
 			},
-			c if c == self.style().pairing=> {
+			c if self.is_pairing(c)=> {
 				try!(self.open_colon());
 				self.mode = Self::seeking_term;
 			},
@@ -199,17 +295,22 @@ impl<'a> TermposeParserState<'a> {
 	
 	fn pop_indent_stack_down(&mut self, this_indent:&'a str)-> Result<(), PositionedError> {
 		loop{
-			let containing_indent = *get_back_mut(&mut self.indent_stack); //we can be assured that there is always something at back, because a str can't be smaller than the root indent "" and not be a prefix of it
+			let containing_indent = *get_back_mut(&mut self.indent_stack); //we can be assured that there is always something at back, because a str can't be smaller than the root indent "" and not be a prefix of it, and a block's indent isn't popped, see below
 			if this_indent.len() == containing_indent.len() {
 				if this_indent == containing_indent {
 					//found it
 					return Ok(());
 				}else{
-					return self.a_fail("inconsistent indentation".into());
+					return self.indentation_fail("inconsistent indentation");
 				}
 			}else if this_indent.len() > containing_indent.len() {
 				//oh no, it's too short to be with the last level and too long to be with the next level, it must not be in the allowed set
-				return self.a_fail("inconsistent indentation".into());
+				return self.indentation_fail("inconsistent indentation");
+			}
+			if self.indent_stack.len() == 1 {
+				//only happens in parse_termpose_block, to a line indented less than the block is. It may belong to something the block is in
+				self.ran_out = true;
+				return self.indentation_fail("inconsistent indentation");
 			}
 			self.indent_stack.pop();
 			self.indent_branch_stack.pop();
@@ -228,17 +329,20 @@ impl<'a> TermposeParserState<'a> {
 	{
 		//there's definitely a thing here, ending indentation
 		let this_indent = unsafe{str_from_bounds(self.stretch_reading_start, self.cur_char_ptr)};
+		if !self.options.allow_tabs && this_indent.contains('\t') {
+			return self.indentation_fail("tabs aren't allowed in indentation");
+		}
 		let containing_indent = *get_back_mut(&mut self.indent_stack); //safe: indent function always has something in it
 		if containing_indent.len() == this_indent.len() {
 			if containing_indent != this_indent {
-				return self.a_fail("inconsistent indentation".into());
+				return self.indentation_fail("inconsistent indentation");
 			}
 			//no indent:
 			self.end_unindented_line();
 			ec(self)
 		}else if this_indent.len() < containing_indent.len() {
 			if !containing_indent.starts_with(this_indent) {
-				return self.a_fail("inconsistent indentation".into());
+				return self.indentation_fail("inconsistent indentation");
 			}
 			//no indent:
 			self.end_unindented_line();
@@ -247,7 +351,7 @@ impl<'a> TermposeParserState<'a> {
 			sc(self)
 		}else{ //greater
 			if !this_indent.starts_with(containing_indent) {
-				return self.a_fail("inconsistent indentation".into());
+				return self.indentation_fail("inconsistent indentation");
 			}
 			gc(self, this_indent)
 		}
@@ -313,7 +417,7 @@ impl<'a> TermposeParserState<'a> {
 					self.stretch_reading_start = self.next_char_ptr();
 					self.mode = Self::eating_indentation;
 				},
-				c if c == self.style().pairing => {
+				c if self.is_pairing(c) => {
 					try!(self.open_colon());
 				},
 				_=> {
@@ -327,7 +431,7 @@ impl<'a> TermposeParserState<'a> {
 	}
 
 	fn read_escaped_char(&mut self)-> Result<(), PositionedError> {
-		let push = |slf:&mut Self, c:char| unsafe{((*slf.leaf_being_read_into)).push(c)}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
+		let push = |slf:&mut Self, c:char| slf.push_to_leaf(c);
 		let match_fail_message = "escape slash must be followed by a valid escape character code";
 		if let Some(nc) = self.move_char_ptr_and_update_line_col() {
 			match nc {
@@ -336,7 +440,14 @@ impl<'a> TermposeParserState<'a> {
 				't'=> { push(self, '\t'); }
 				'h'=> { push(self, '☃'); }
 				'"'=> { push(self, '"'); }
+				c if c == self.closing_quote=> { push(self, c); }
 				'\\'=> { push(self, '\\'); }
+				'x' | 'u'=> {
+					match read_code_escape(nc == 'u', || self.move_char_ptr_and_update_line_col()) {
+						Ok(c)=> push(self, c),
+						Err(m)=> return self.a_fail(m.into()),
+					}
+				}
 				_=> { return self.a_fail(match_fail_message.into()); }
 			}
 		}else{
@@ -347,12 +458,12 @@ impl<'a> TermposeParserState<'a> {
 	}
 
 	fn eating_quoted_string(&mut self, co:Option<char>)-> Result<(), PositionedError> {
-		let push_char = |slf:&mut Self, c:char| unsafe{((*slf.leaf_being_read_into)).push(c)}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
+		let push_char = |slf:&mut Self, c:char| slf.push_to_leaf(c);
 		if let Some(c) = co {
 			match c {
 				'\n'=> {
 					self.stretch_reading_start = self.next_char_ptr();
-					let ar = unsafe{&mut *self.leaf_being_read_into};
+					let ar = unsafe{&mut (*self.leaf_being_read).v};
 					if ar.chars().all(is_whitespace) {
 						//begin multiline string
 						ar.clear();
@@ -364,7 +475,8 @@ impl<'a> TermposeParserState<'a> {
 				'\\'=> {
 					try!(self.read_escaped_char());
 				},
-				'"'=> {
+				c if c == self.closing_quote=> {
+					self.extend_leaf();
 					self.mode = Self::seeking_immediately_after_thing;
 				},
 				_=> {
@@ -377,8 +489,25 @@ impl<'a> TermposeParserState<'a> {
 		Ok(())
 	}
 
+	fn eating_raw_string(&mut self, co:Option<char>)-> Result<(), PositionedError> {
+		if let Some(c) = co {
+			if c == '"' && self.iter.as_str().starts_with("\"\"") {
+				self.move_char_ptr_and_update_line_col();
+				self.move_char_ptr_and_update_line_col();
+				self.extend_leaf();
+				self.mode = Self::seeking_immediately_after_thing;
+			}else{
+				if c == '\n' && self.options.keep_cr && self.crlf { self.push_to_leaf('\r'); }
+				self.push_to_leaf(c);
+			}
+			Ok(())
+		}else{
+			self.ran_out = true;
+			self.a_fail("raw string was never closed".into())
+		}
+	}
+
 	fn eating_leaf(&mut self, co:Option<char>)-> Result<(), PositionedError> {
-		let push_char = |slf:&mut Self, c:char| unsafe{((*slf.leaf_being_read_into)).push(c)}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
 		if let Some(c) = co {
 			match c {
 				' ' | '\t' => {
@@ -391,10 +520,10 @@ impl<'a> TermposeParserState<'a> {
 				'\\'=> {
 					try!(self.read_escaped_char());
 				},
-				'"'=> {
-					self.notice_quote_immediately_after_thing();
+				c if self.is_quote(c)=> {
+					self.notice_quote_immediately_after_thing(c);
 				},
-				c if c == self.style().pairing=> {
+				c if self.is_pairing(c)=> {
 					try!(self.open_colon());
 					self.mode = Self::seeking_term;
 				},
@@ -406,7 +535,7 @@ impl<'a> TermposeParserState<'a> {
 					self.notice_paren_immediately_after_thing();
 				},
 				_=> {
-					push_char(self, c);
+					try!(self.push_unquoted(c));
 				}
 			}
 		}else{
@@ -421,27 +550,47 @@ impl<'a> TermposeParserState<'a> {
 			panic!("notice_paren_immediately_after_thing was called with no previous thing");
 		}
 		accrete_branch(unsafe{&mut *bl});
+		self.count_node();
 		self.line_paren_stack.push(bl);
 		self.mode = Self::seeking_term;
 	}
 	
-	fn notice_quote_immediately_after_thing(&mut self){
+	fn notice_quote_immediately_after_thing(&mut self, quote:char){
 		let lt: *mut Vec<Wood> = unsafe{ &mut assume_branch_mut(&mut **get_back_mut(&mut self.line_paren_stack)).v };
 		if unsafe{(*lt).len()} == 0 {
 			panic!("notice_quote_immediately_after_thing should not be called after entering an empty paren");
 		}
 		let bt = get_back_mut(unsafe{ &mut*lt });
+		self.count_node();
 		let nl = accrete_branch(bt);
-		nl.push(Leafv(Leaf{line:self.line, column:self.column, v:String::new()}));
-		self.leaf_being_read_into = &mut assume_leaf_mut(get_back_mut(nl)).v; //safe: just made that
-		self.mode = Self::eating_quoted_string;
+		nl.push(self.mkleaf());
+		self.leaf_being_read = assume_leaf_mut(get_back_mut(nl)); //safe: just made that
+		self.extend_leaf();
+		self.begin_string(quote);
+	}
+	
+	//just after the opening quote of the leaf being read. A " may be the first of three that open a raw string
+	fn begin_string(&mut self, quote:char){
+		self.closing_quote = quote;
+		if quote == '"' && self.iter.as_str().starts_with("\"\"") {
+			self.move_char_ptr_and_update_line_col();
+			self.move_char_ptr_and_update_line_col();
+			self.extend_leaf();
+			//a line break straight after the quotes isn't part of the string, so that it can start on a line of its own
+			if self.iter.as_str().starts_with(|c| c == '\n' || c == '\r') {
+				self.move_char_ptr_and_update_line_col();
+			}
+			self.mode = Self::eating_raw_string;
+		}else{
+			self.mode = Self::eating_quoted_string;
+		}
 	}
 
 	fn seeking_immediately_after_thing(&mut self, co:Option<char>)-> Result<(), PositionedError> { //generally called after ')' or a closing '"', because '"'s and self.style().opens have slightly different meaning in that context
 		if let Some(c) = co {
 			match c {
-				'"'=> {
-					self.notice_quote_immediately_after_thing();
+				c if self.is_quote(c)=> {
+					self.notice_quote_immediately_after_thing(c);
 				},
 				c if c == self.style().close=> {
 					try!(self.close_paren());
@@ -449,7 +598,7 @@ impl<'a> TermposeParserState<'a> {
 				c if c == self.style().open=> {
 					self.notice_paren_immediately_after_thing();
 				},
-				c if c == self.style().pairing=> {
+				c if self.is_pairing(c)=> {
 					try!(self.open_colon());
 					self.mode = Self::seeking_term;
 				},
@@ -492,7 +641,7 @@ impl<'a> TermposeParserState<'a> {
 						},
 						|slf:&mut Self, this_indent:&'a str|{
 							slf.multilines_indent = this_indent;
-							unsafe{(*slf.leaf_being_read_into).push(c)};
+							slf.push_to_leaf(c);
 							slf.mode = Self::eating_multiline_content;
 							Ok(())
 						},
@@ -509,12 +658,12 @@ impl<'a> TermposeParserState<'a> {
 		if let Some(c) = co {
 			match c {
 				'\n'=> {
-					// unsafe{(*self.leaf_being_read_into).push(c)}; //actually, we'll only take the character once it's been confirmed that the indent goes all the way up
+					// unsafe{(*self.leaf_being_read).v.push(c)}; //actually, we'll only take the character once it's been confirmed that the indent goes all the way up
 					self.stretch_reading_start = self.next_char_ptr();
 					self.mode = Self::eating_multiline_later_indent;
 				},
 				_=> {
-					unsafe{(*self.leaf_being_read_into).push(c)};
+					self.push_to_leaf(c);
 				}
 			}
 		}else{
@@ -530,13 +679,14 @@ impl<'a> TermposeParserState<'a> {
 					let curstr = unsafe{str_from_bounds(self.stretch_reading_start, self.next_char_ptr())}; //safe: these u8 pointers are fine
 					if curstr.len() == self.multilines_indent.len() {
 						if curstr != self.multilines_indent {
-							return self.a_fail("inconsistent indentation".into());
+							return self.indentation_fail("inconsistent indentation");
 						}
-						unsafe{(*self.leaf_being_read_into).push('\n')}; //only now do we finalize the newline given
+						if self.options.keep_cr && self.crlf { unsafe{(*self.leaf_being_read).v.push('\r')}; }
+						unsafe{(*self.leaf_being_read).v.push('\n')}; //only now do we finalize the newline given
 						self.mode = Self::eating_multiline_content;
 					}else{
 						if !self.multilines_indent.starts_with(curstr) {
-							return self.a_fail("inconsistent indentation".into());
+							return self.indentation_fail("inconsistent indentation");
 						}
 					}
 				},
@@ -559,38 +709,195 @@ impl<'a> TermposeParserState<'a> {
 		Ok(())
 	}
 
+	
+	//recovering
+	
+	//after an error that isn't a limit. The rest of the line goes into a placeholder where the error was, and parsing carries on from the next line. A line with inconsistent indentation becomes a placeholder at the innermost level it's no less indented than
+	fn recover(&mut self, e:PositionedError) {
+		let msg = e.msg.clone();
+		if let Some(ref mut errors) = self.errors { errors.push(e); }
+		if replace(&mut self.bad_indentation, false) {
+			match self.last_char {
+				//found partway through the indentation of a multiline string
+				Some(' ') | Some('\t')=> self.mode = Self::eating_bad_indentation,
+				Some(c)=> self.place_misindented_line(c, msg),
+				None=> self.end_unindented_line(),
+			}
+		}else{
+			match self.last_char {
+				//the leaf being read ran into the end of the line or the input, as a raw string that's never closed or an escape cut short would. There's nothing left to skip, so that leaf is the placeholder
+				None=> {
+					self.mark_leaf_being_read(msg);
+					self.end_unindented_line();
+				}
+				Some('\n')=> {
+					self.mark_leaf_being_read(msg);
+					self.stretch_reading_start = self.next_char_ptr();
+					self.mode = Self::eating_indentation;
+				}
+				Some(c)=> self.begin_placeholder(c, msg),
+			}
+		}
+	}
+	fn mark_leaf_being_read(&mut self, msg:String) {
+		unsafe{ (*self.leaf_being_read).meta_mut().unparsed = Some(msg); } //safe: the only errors at the end of a line or the input are in the leaf being read
+	}
+	//a placeholder starting with c, which the rest of the line will go into
+	fn begin_placeholder(&mut self, c:char, msg:String) {
+		let mut to_push = self.mkleaf();
+		to_push.meta_mut().unparsed = Some(msg);
+		let bin = self.take_hanging_branch_for_insert();
+		unsafe{(*bin).push(to_push)};
+		self.leaf_being_read = unsafe{assume_leaf_mut(get_back_mut(&mut *bin))};
+		self.last_completed_term_on_line = null_mut();
+		self.push_to_leaf(c);
+		self.mode = Self::skipping_line;
+	}
+	fn place_misindented_line(&mut self, c:char, msg:String) {
+		let this_indent = unsafe{str_from_bounds(self.stretch_reading_start, self.cur_char_ptr)};
+		self.end_unindented_line();
+		while get_back_mut(&mut self.indent_stack).len() > this_indent.len() { //"" is never longer, so root stays
+			self.indent_stack.pop();
+			self.indent_branch_stack.pop();
+		}
+		self.begin_line();
+		self.begin_placeholder(c, msg);
+	}
+	
+	fn skipping_line(&mut self, co:Option<char>)-> Result<(), PositionedError> {
+		match co {
+			Some('\n')=> {
+				self.stretch_reading_start = self.next_char_ptr();
+				self.mode = Self::eating_indentation;
+			}
+			Some(c)=> self.push_to_leaf(c),
+			None=> self.end_unindented_line(),
+		}
+		Ok(())
+	}
+	
+	fn eating_bad_indentation(&mut self, co:Option<char>)-> Result<(), PositionedError> {
+		match co {
+			Some(' ') | Some('\t')=> {},
+			Some('\n')=> {
+				self.stretch_reading_start = self.next_char_ptr();
+				self.mode = Self::eating_indentation;
+			}
+			Some(c)=> self.place_misindented_line(c, "inconsistent indentation".into()),
+			None=> self.end_unindented_line(),
+		}
+		Ok(())
+	}
+
 } //TermposeParserState
 
 
 ///Returns a Branch containing all of the Woods at root level, even if there is only one Wood, it will be wrapped in an additional Branch
 pub fn parse_multiline_termpose_style<'a>(s:&'a str, style:TermposeStyle)-> Result<Wood, PositionedError> {
+	parse_multiline_termpose_with(&ParseOptions{ style, ..ParseOptions::default() }, s)
+}
+
+///parse_multiline_termpose, reading as the options say
+pub fn parse_multiline_termpose_with<'a>(options:&ParseOptions, s:&'a str)-> Result<Wood, PositionedError> {
+	parse_collecting(options, s, false, "").0
+}
+
+/// parses as much of a multiline termpose document as it can, for editors, which need a tree even while the text is half written. Where there's a syntax error, the rest of its line is put in a placeholder leaf (see Wood::is_error_placeholder) and parsing carries on from the next line, so everything else is still there. A line that's indented inconsistently becomes a placeholder on the innermost level it's no less indented than. Returns what parse_multiline_termpose would, and every error, in order. Going over a limit still stops parsing, that error is the last, and the wood is empty
+pub fn parse_termpose_recovering_with(options:&ParseOptions, s:&str)-> (Wood, Vec<PositionedError>) {
+	match parse_collecting(options, s, true, "") {
+		(Ok(w), errors, _)=> (w, errors),
+		(Err(e), mut errors, _)=> {
+			errors.push(e);
+			(branch!(), errors)
+		}
+	}
+}
+/// `parse_termpose_recovering_with(&ParseOptions::default(), s)`
+pub fn parse_termpose_recovering(s:&str)-> (Wood, Vec<PositionedError>) {
+	parse_termpose_recovering_with(&ParseOptions::default(), s)
+}
+
+//parses the lines of a block indented by indent chars, the first line having exactly that indentation, as they'd be parsed in the middle of a document, see IncrementalTermpose. Failing, it also says whether the text ran out in a way that more of the document after it might have fixed, in a raw string or with a line indented less than the block
+pub(super) fn parse_termpose_block(s:&str, indent:usize)-> Result<Wood, (PositionedError, bool)> {
+	match parse_collecting(&ParseOptions::default(), s, false, &s[..indent]) {
+		(Ok(w), _, _)=> Ok(w),
+		(Err(e), _, ran_out)=> Err((e, ran_out)),
+	}
+}
+
+//if recovering, errors other than limits are collected rather than returned. Lines are read as if they're within a block indented by indent, and whether it failed in a way the text after it might have fixed is returned too
+fn parse_collecting<'a>(options:&ParseOptions, s:&'a str, recovering:bool, indent:&'a str)-> (Result<Wood, PositionedError>, Vec<PositionedError>, bool) {
+	let body = &s[bom_len(s)..];
 	let mut state = TermposeParserState::<'a>{
 		root: branch!(), //a yet empty line
-		indent_stack: vec!(""),
-		stretch_reading_start: s.as_ptr(),
-		cur_char_ptr: s.as_ptr(),
+		indent_stack: vec!(indent),
+		source_start: s.as_ptr(),
+		stretch_reading_start: body.as_ptr(),
+		cur_char_ptr: body.as_ptr(),
 		colon_receptacle: null_mut(),
 		last_completed_term_on_line: null_mut(),
-		leaf_being_read_into: null_mut(),
-		iter: s.chars(),
+		leaf_being_read: null_mut(),
+		iter: body.chars(),
 		line: 0,
 		column: 0,
 		multilines_indent: "",
+		closing_quote: '"',
+		node_count: 0,
+		crlf: false,
+		limit_hit: None,
+		errors: if recovering { Some(Vec::new()) }else{ None },
+		bad_indentation: false,
+		last_char: None,
+		ran_out: false,
 		line_paren_stack: vec!(),
 		indent_branch_stack: vec!(),
 		mode: TermposeParserState::<'a>::seeking_beginning,
-		chosen_style: style,
+		options: options.clone(),
 	};
 	state.indent_branch_stack = vec!(&mut assume_branch_mut(&mut state.root).v);
 	
 	
+	//whatever was built is taken apart without recursing when parsing fails, in case it's deeper than the stack would allow
+	let fail = |state:&mut TermposeParserState, e:PositionedError|{
+		dismantle(replace(&mut state.root, branch!()));
+		(Err(e), state.errors.take().unwrap_or_else(Vec::new), state.ran_out)
+	};
 	loop {
 		let co = state.move_char_ptr_and_update_line_col();
-		try!((state.mode)(&mut state, co));
+		if let Err(e) = (state.mode)(&mut state, co) {
+			if state.errors.is_none() { return fail(&mut state, e); }
+			state.recover(e);
+		}
+		if let Some(max) = options.max_depth {
+			//how deep the innermost open paren is, at least, for stopping very deep input early. Each indent level is a branch, the line's branch may yet be collapsed away, so it isn't counted. Colons can nest further, they're caught below
+			if state.indent_stack.len() + state.line_paren_stack.len() > max + 3 {
+				state.limit_hit = Some(LimitExceeded::Depth(max));
+			}
+		}
+		if let Some(l) = state.limit_hit {
+			let e = PositionedError::limit_exceeded(state.line, state.column, l);
+			return fail(&mut state, e);
+		}
 		if co == None { break; }
 	}
 	
-	Ok(state.root)
+	if let Some(max) = options.max_nodes {
+		if state.node_count > max {
+			let l = LimitExceeded::Nodes(max);
+			let e = PositionedError::limit_exceeded(state.line, state.column, l);
+			return fail(&mut state, e);
+		}
+	}
+	if let Some(max) = options.max_depth {
+		let too_deep = state.root.iter_dfs().find(|n| n.1 > max + 1).map(|n| n.0.span()); //+1 for the root
+		if let Some(at) = too_deep {
+			let l = LimitExceeded::Depth(max);
+			return fail(&mut state, PositionedError::limit_exceeded(at.line, at.column, l));
+		}
+	}
+	close_extents(&mut state.root);
+	let errors = state.errors.take().unwrap_or_else(Vec::new);
+	(Ok(replace(&mut state.root, branch!())), errors, false)
 }
 
 ///Returns a Branch containing all of the Woods at root level, even if there is only one Wood, it will be wrapped in an additional Branch
@@ -600,7 +907,12 @@ pub fn parse_multiline_termpose<'a>(s:&'a str)-> Result<Wood, PositionedError> {
 
 ///If multiple Woods are at root level in the input, it will wrap them all in a Branch Wood. Otherwise, if there's only one, it wont. This is probably the behaviour you will expect, most of the time, but if I didn't explain it here it might have derailed you, the rest of the time.
 pub fn parse_termpose<'a>(s:&'a str)-> Result<Wood, PositionedError> {
-	parse_multiline_termpose(s).map(|t|{
+	parse_termpose_with(&ParseOptions::default(), s)
+}
+
+///parse_termpose, reading as the options say
+pub fn parse_termpose_with<'a>(options:&ParseOptions, s:&'a str)-> Result<Wood, PositionedError> {
+	parse_multiline_termpose_with(options, s).map(|t|{
 		let l = assume_branch(t); //parse_multiline_termpose only returns branchs
 		if l.v.len() == 1 {
 			yank_first(l.v) //just confirmed it's there
@@ -622,151 +934,18 @@ pub fn parse_termpose<'a>(s:&'a str)-> Result<Wood, PositionedError> {
 
 ///Blurts it into a single line. (Might be woodslist compatable??)
 pub fn stringify_leaf_termpose(v:&Leaf, s:&mut String, style:&TermposeStyle){
+	if style.raw_strings && fits_raw_string(&v.v, style.escapes) {
+		s.push_str("\"\"\"\n");
+		s.push_str(&v.v);
+		s.push_str("\"\"\"");
+		return;
+	}
 	let needs_quotes = v.v.chars().any(|c|{ c == ' ' || c == style.pairing || c == '\t' || c == style.open || c == style.close });
 	if needs_quotes { s.push('"'); }
-	push_escaped(s, v.v.as_str());
+	push_escaped_with(s, v.v.as_str(), style.escapes);
 	if needs_quotes { s.push('"'); }
 }
 
-fn inline_stringify_termpose_branch_baseline(b:&Branch, s:&mut String, style:&TermposeStyle){
-	//space separated
-	let mut i = b.v.iter();
-	if let Some(ref first) = i.next() {
-		inline_stringify_termpose(first, s, style);
-		while let Some(ref nexto) = i.next() {
-			s.push(' ');
-			inline_stringify_termpose(nexto, s, style);
-		}
-	}
-}
-fn inline_stringify_termpose_branch(b:&Branch, s:&mut String, style:&TermposeStyle){
-	if b.v.len() == 2 && b.v[0].is_leaf() {
-		inline_stringify_termpose(&b.v[0], s, style);
-		s.push(style.pairing);
-		inline_stringify_termpose(&b.v[1], s, style);
-	}else{
-		s.push(style.open);
-		inline_stringify_termpose_branch_baseline(b, s, style);
-		s.push(style.close);
-	}
-}
-fn inline_stringify_termpose(w:&Wood, s:&mut String, style:&TermposeStyle){
-	match *w {
-		Branchv(ref b)=> {
-			inline_stringify_termpose_branch(b, s, style);
-		}
-		Leafv(ref v)=> {
-			stringify_leaf_termpose(v, s, style);
-		}
-	}
-}
-fn termpose_inline_length_estimate_branch_baseline(b:&Branch)-> usize {
-	if b.v.len() > 0 {
-		let spaces_length = b.v.len() - 1;
-		b.v.iter().fold(spaces_length, |n, w|{
-			n + termpose_inline_length_estimate(w)
-		})
-	}else{
-		2
-	}
-}
-fn termpose_inline_length_estimate_for_branch(b:&Branch)-> usize {
-	if b.v.len() == 2 && b.v[0].is_leaf() {
-		//do a pairing
-		return
-			1 +
-			termpose_inline_length_estimate(&b.v[0]) +
-			termpose_inline_length_estimate(&b.v[1]);
-	}else{
-		//+2 for parens
-		if b.v.len() > 0 {
-			let spaces_length = b.v.len() - 1;
-			2 + b.v.iter().fold(spaces_length, |n, w|{
-				n + termpose_inline_length_estimate(w)
-			})
-		}else{
-			2
-		}
-	}
-}
-fn termpose_inline_length_estimate(w:&Wood)-> usize {
-	match w {
-		&Branchv(ref b)=> {
-			termpose_inline_length_estimate_for_branch(b)
-		}
-		&Leafv(ref l)=> {
-			l.v.len()
-		}
-	}
-}
-fn maybe_inline_termpose_stringification_baseline<'a>(w:&'a Wood, column_limit:usize, out:&mut String, style:&TermposeStyle)-> Option<&'a Branch> { //returns Some Branch that w is iff it did NOT insert it inline (because it didn't have room)
-	match w {
-		&Branchv(ref b)=> {
-			if termpose_inline_length_estimate_branch_baseline(b) > column_limit {
-				return Some(b);
-			}else{
-				inline_stringify_termpose_branch_baseline(b, out, style);
-			}
-		}
-		&Leafv(ref l)=> {
-			stringify_leaf_termpose(l, out, style);
-		}
-	}
-	None
-}
-fn do_termpose_stringification(w:&Wood, indent:&str, indent_depth:usize, column_limit:usize, out:&mut String, style:&TermposeStyle){
-	out.push('\n');
-	do_indent(indent, indent_depth, out);
-	if let Some(b) = maybe_inline_termpose_stringification_baseline(w, column_limit, out, style) {
-		let mut bi = b.v.iter();
-		if let Some(fw) = bi.next() {
-			if let Some(_fwb) = maybe_inline_termpose_stringification_baseline(fw, column_limit, out, style) {
-				//then the first one wont fit in the first one position
-				out.push(style.open);
-				for iw in b.v.iter() {
-					do_termpose_stringification(iw, indent, indent_depth + 1, column_limit, out, style);
-				}
-			}else{
-				for iw in bi {
-					do_termpose_stringification(iw, indent, indent_depth + 1, column_limit, out, style);
-				}
-			}
-		}
-	}
-}
-
-
-///Indents and uses pairing when appropriate.
-
-/// # Arguments
-///
-/// * `column_limit` - column_limit ignores indentation, only limits the length of what's beyond the indentation. The reason is... for a start, that's simpler to implement. If it had a strict limit, deeply indented code would get sort of squashed as it approaches the side, which is visually awkward, and eventually it would have to be allowed to penetrate through the limit, and I didn't want to code that. If you don't expect to indent deeply, this shouldn't make much of a difference to you. Pull requests for a more strictly constraining column limit are welcome.
-pub fn pretty_termpose_detail(w:&Wood, indent_is_tab:bool, tab_size:usize, column_limit:usize, style:&TermposeStyle)-> String {
-	let indent_string:String;
-	let indent:&str;
-	if indent_is_tab {
-		indent = "\t";
-	}else{
-		indent_string = " ".repeat(tab_size);
-		indent = indent_string.as_str();
-	}
-	
-	let mut ret = String::new();
-	
-	//we may need to do a special case for the first level if it's a long branch, in which case, every element should be at depth zero. This differs from the normal case where 
-	if let Some(b) = maybe_inline_termpose_stringification_baseline(w, column_limit, &mut ret, style) {
-		for iw in b.v.iter() {
-			do_termpose_stringification(iw, indent, 0, column_limit, &mut ret, style);
-		}
-	}
-	
-	ret
-}
-
-///`pretty_termpose_detail(w, false, 2, 73, &DEFAULT_STYLE)`
-pub fn pretty_termpose(w:&Wood)-> String {
-	pretty_termpose_detail(w, false, 2, 73, &DEFAULT_STYLE)
-}
-
-
+//whether v would come back the same if it were printed as a raw string, with nothing in it the policy wants escaped. \rs would be read as line breaks
+pub(super) fn fits_raw_string(v:&str, policy:EscapePolicy)-> bool { v.contains('\n') && !v.contains("\"\"\"") && !v.ends_with('"') && !v.contains('\r') && !v.chars().any(|c| policy.escapes_as_code(c)) }
 
@@ -3,9 +3,11 @@ use super::*;
 
 struct SexpParserState<'a>{
 	root: Wood,
-	paren_stack: Vec<*mut Vec<Wood>>,
-	leaf_being_read_into: *mut String,
+	paren_stack: Vec<*mut Branch>,
+	leaf_being_read: *mut Leaf,
 	iter: std::iter::Peekable<std::str::Chars<'a>>,
+	char_start: usize, //byte offset of the current char
+	offset: usize, //byte offset of the next char
 	line: isize,
 	column: isize,
 	mode: fn(&mut SexpParserState<'a>, Option<char>)-> Result<(), PositionedError>,
@@ -13,16 +15,29 @@ struct SexpParserState<'a>{
 
 impl<'a> SexpParserState<'a> {
 	
-	fn a_fail(&self, message:String)-> Result<(), PositionedError> { Err(PositionedError{
-		line: self.line,
-		column: self.column,
-		msg: message,
-	}) }
+	fn a_fail(&self, message:String)-> Result<(), PositionedError> { Err(PositionedError::new(self.line, self.column, message)) }
 	
-	fn mkbranch(&self)-> Wood { Branchv(Branch{ line:self.line, column:self.column, v:Vec::new() }) }
+	fn mkbranch(&self)-> Wood { Branchv(Branch{ line:self.line, column:self.column, meta:meta_with_extent(self.extent_here()), v:Vec::new() }) }
+	
+	//an extent starting at the current char, to be ended later
+	fn extent_here(&self)-> Extent { Extent{ start:self.char_start, ..Extent::default() } }
+	//ends the extent just after the current char
+	fn end_extent(&self, e:&mut Extent) {
+		e.end = self.offset;
+		e.end_line = self.line;
+		e.end_column = self.column;
+	}
+	fn extend_leaf(&mut self) {
+		let l = self.leaf_being_read;
+		self.end_extent(unsafe{ &mut (*l).meta_mut().extent }); //safe: leaf_being_read must have been validated before this could be called
+	}
+	fn push_to_leaf(&mut self, c:char) {
+		unsafe{ (*self.leaf_being_read).v.push(c) }; //safe: leaf_being_read must have been validated before this mode could have been entered
+		self.extend_leaf();
+	}
 	
 	fn branch_for_insert(&mut self)-> *mut Vec<Wood> {
-		*get_back_mut(&mut self.paren_stack)
+		unsafe{ &mut (**get_back_mut(&mut self.paren_stack)).v }
 	}
 	// fn iterate_char_iter(&mut self)-> Option<char> {
 	// 	self.cur_char_ptr = self.iter.as_str().as_ptr();
@@ -30,15 +45,17 @@ impl<'a> SexpParserState<'a> {
 	// }
 	
 	fn open_paren(&mut self) {
-		let lti = self.mkbranch();
+		let mut lti = self.mkbranch();
+		self.end_extent(&mut assume_branch_mut(&mut lti).meta_mut().extent); //in case it's never closed
 		let branch_for_insert = self.branch_for_insert();
 		unsafe{(*branch_for_insert).push(lti)};
-		self.paren_stack.push(&mut assume_branch_mut(get_back_mut(unsafe{&mut *branch_for_insert})).v);
+		self.paren_stack.push(assume_branch_mut(get_back_mut(unsafe{&mut *branch_for_insert})));
 		self.mode = Self::seeking_term;
 	}
 	fn close_paren(&mut self)-> Result<(), PositionedError> {
 		if self.paren_stack.len() > 1 {
-			self.paren_stack.pop();
+			let b = self.paren_stack.pop().unwrap(); //safe: we just checked
+			self.end_extent(unsafe{ &mut (*b).meta_mut().extent });
 			self.mode = Self::seeking_term;
 			Ok(())
 		}else{
@@ -46,14 +63,15 @@ impl<'a> SexpParserState<'a> {
 		}
 	}
 	fn begin_leaf(&mut self){
-		let to_push = Leafv(Leaf{line:self.line, column:self.column, v:String::new()});
+		let to_push = Leafv(Leaf{line:self.line, column:self.column, meta:meta_with_extent(self.extent_here()), v:String::new()});
 		let branch_for_insert = self.branch_for_insert();
 		unsafe{(*branch_for_insert).push(to_push)};
-		self.leaf_being_read_into = &mut unsafe{assume_leaf_mut(get_back_mut(&mut *branch_for_insert))}.v;
+		self.leaf_being_read = unsafe{assume_leaf_mut(get_back_mut(&mut *branch_for_insert))};
 	}
 	
 	fn begin_quoted(&mut self){
 		self.begin_leaf();
+		self.extend_leaf(); //over the opening quote
 		//potentially skip the first character if it's a newline
 		match self.iter.peek() {
 			Some(&'\n') | Some(&'\r') => {
@@ -98,7 +116,7 @@ impl<'a> SexpParserState<'a> {
 	}
 
 	fn read_escaped_char(&mut self)-> Result<(), PositionedError> {
-		let push = |slf:&mut Self, c:char| unsafe{((*slf.leaf_being_read_into)).push(c)}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
+		let push = |slf:&mut Self, c:char| slf.push_to_leaf(c);
 		let nco = self.move_char_ptr_and_update_line_col();
 		let match_fail_message = "escape slash must be followed by a valid escape character code";
 		if let Some(nc) = nco {
@@ -109,6 +127,12 @@ impl<'a> SexpParserState<'a> {
 				'h'=> { push(self, '☃'); }
 				'"'=> { push(self, '"'); }
 				'\\'=> { push(self, '\\'); }
+				'x' | 'u'=> {
+					match read_code_escape(nc == 'u', || self.move_char_ptr_and_update_line_col()) {
+						Ok(c)=> push(self, c),
+						Err(m)=> return self.a_fail(m.into()),
+					}
+				}
 				_=> { return self.a_fail(match_fail_message.into()); }
 			}
 		}else{
@@ -118,17 +142,13 @@ impl<'a> SexpParserState<'a> {
 	}
 	
 	fn move_char_ptr_and_update_line_col(&mut self)-> Option<char> {
+			self.char_start = self.offset;
 			self.iter.next().and_then(|c|{
+				self.offset += c.len_utf8();
 				if c == '\n' || c == '\r' {
-					//continue over any ensuing '\r's
-					let mut ic = self.iter.clone();
-					//the weird staggered structure reflects the fact that the first \r after a newline is ignored (because of windows)
-					if Some('\r') == ic.next() {
+					if line_break_continues(c, self.iter.peek().cloned()) {
 						self.iter.next();
-						while Some('\r') == ic.next() {
-							self.iter.next();
-							self.line += 1;
-						}
+						self.offset += 1;
 					}
 					self.line += 1;
 					self.column = 0;
@@ -141,13 +161,14 @@ impl<'a> SexpParserState<'a> {
 		}
 
 	fn eating_quoted_string(&mut self, co:Option<char>)-> Result<(), PositionedError> {
-		let push_char = |slf:&mut Self, c:char| unsafe{((*slf.leaf_being_read_into)).push(c)}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
+		let push_char = |slf:&mut Self, c:char| slf.push_to_leaf(c);
 		if let Some(c) = co {
 			match c {
 				'\\'=> {
 					try!(self.read_escaped_char());
 				},
 				'"'=> {
+					self.extend_leaf();
 					self.mode = Self::seeking_term;
 				},
 				_=> {
@@ -159,7 +180,7 @@ impl<'a> SexpParserState<'a> {
 	}
 
 	fn eating_leaf(&mut self, co:Option<char>)-> Result<(), PositionedError> {
-		let push_char = |slf:&mut Self, c:char| unsafe{((*slf.leaf_being_read_into)).push(c)}; //safe: leaf_being_read_into must have been validated before this mode could have been entered
+		let push_char = |slf:&mut Self, c:char| slf.push_to_leaf(c);
 		if let Some(c) = co {
 			match c {
 				'\n' | '\r' => {
@@ -195,13 +216,15 @@ pub fn parse_multiline_woodslist<'a>(s:&'a str)-> Result<Wood, PositionedError>
 	let mut state = SexpParserState::<'a>{
 		root: branch!(), //a yet empty line
 		paren_stack: Vec::new(),
-		leaf_being_read_into: null_mut(),
-		iter: s.chars().peekable(),
+		leaf_being_read: null_mut(),
+		char_start: bom_len(s),
+		offset: bom_len(s),
+		iter: s[bom_len(s)..].chars().peekable(),
 		line: 0,
 		column: 0,
 		mode: SexpParserState::<'a>::seeking_term,
 	};
-	state.paren_stack = vec!(&mut assume_branch_mut(&mut state.root).v);
+	state.paren_stack = vec!(assume_branch_mut(&mut state.root));
 	
 	
 	loop {
@@ -211,6 +234,7 @@ pub fn parse_multiline_woodslist<'a>(s:&'a str)-> Result<Wood, PositionedError>
 		if co == None { break; }
 	}
 	
+	close_extents(&mut state.root);
 	Ok(state.root)
 }
 
@@ -263,6 +287,59 @@ pub fn inline_stringify_woodslist(w:&Wood, s:&mut String){
 		}
 	}
 }
+//how much writing out gathers before handing it on
+const WRITE_CHUNK:usize = 8192;
+
+//inline_stringify_woodslist, handing what's written so far to emit whenever there's a chunk of it, so only about a chunk's held at a time
+fn write_woodslist_chunked<E, F>(w:&Wood, buf:&mut String, emit:&mut F)-> Result<(), E> where F: FnMut(&str)-> Result<(), E> {
+	match *w {
+		Branchv(ref b)=> {
+			buf.push('(');
+			for (i, c) in b.v.iter().enumerate() {
+				if i != 0 { buf.push(' '); }
+				write_woodslist_chunked(c, buf, emit)?;
+			}
+			buf.push(')');
+		}
+		Leafv(ref v)=> {
+			stringify_leaf_woodslist(v, buf);
+		}
+	}
+	if buf.len() >= WRITE_CHUNK {
+		emit(buf)?;
+		buf.clear();
+	}
+	Ok(())
+}
+fn write_woodslist<E, F>(w:&Wood, mut emit:F)-> Result<(), E> where F: FnMut(&str)-> Result<(), E> {
+	let mut buf = String::with_capacity(WRITE_CHUNK);
+	write_woodslist_chunked(w, &mut buf, &mut emit)?;
+	if !buf.is_empty() { emit(&buf)?; }
+	Ok(())
+}
+
+impl Wood {
+	/// writes what to_string would return to out, a few kilobytes at a time, rather than building the whole string first
+	pub fn write_to<W>(&self, out:&mut W)-> std::io::Result<()> where W: std::io::Write + ?Sized {
+		write_woodslist(self, |s| out.write_all(s.as_bytes()))
+	}
+	/// write_to, for a std::fmt::Write, like a String or a Formatter
+	pub fn write_fmt_to<W>(&self, out:&mut W)-> std::fmt::Result where W: std::fmt::Write + ?Sized {
+		write_woodslist(self, |s| out.write_str(s))
+	}
+}
+
+/// woodslist. `{}` is to_woodslist, all on one line, and `{:#}` is indented_woodslist, over as many lines as it needs
+impl Display for Wood {
+	fn fmt(&self, f:&mut Formatter)-> Result<(), std::fmt::Error> {
+		if f.alternate() {
+			f.write_str(&indented_woodslist(self))
+		}else{
+			self.write_fmt_to(f)
+		}
+	}
+}
+
 fn woodslist_inline_length_estimate_for_branch(b:&Branch)-> usize {
 	let mut ret = 2; //2 for parens
 	if b.v.len() > 0 {
@@ -0,0 +1,379 @@
+//structural diffs between woods
+
+use super::*;
+
+/// one edit. Paths are the indices taken at each level to get from the root to the node, as in iter_dfs. Each op's path refers to the wood as it is by the time that op is applied, after the ops before it
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp {
+	/// v goes in at the path, shifting the nodes after it along
+	Insert{ path:Vec<usize>, v:Wood },
+	/// the node at the path, which was old, is removed
+	Remove{ path:Vec<usize>, old:Wood },
+	/// the node at the path, which was old, is replaced with new
+	Change{ path:Vec<usize>, old:Wood, new:Wood },
+}
+impl PatchOp {
+	pub fn path(&self)-> &[usize] {
+		match *self {
+			PatchOp::Insert{ ref path, .. }=> path,
+			PatchOp::Remove{ ref path, .. }=> path,
+			PatchOp::Change{ ref path, .. }=> path,
+		}
+	}
+	/// the op that undoes this one
+	pub fn inverse(&self)-> PatchOp {
+		match *self {
+			PatchOp::Insert{ ref path, ref v }=> PatchOp::Remove{ path:path.clone(), old:v.clone() },
+			PatchOp::Remove{ ref path, ref old }=> PatchOp::Insert{ path:path.clone(), v:old.clone() },
+			PatchOp::Change{ ref path, ref old, ref new }=> PatchOp::Change{ path:path.clone(), old:new.clone(), new:old.clone() },
+		}
+	}
+}
+
+/// the edits that turn one wood into another, see diff. It woodifies as a list of ops like `insert (1 2) v`, `remove (0) old` and `change () old new`, so it can be rendered as termpose and stored
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WoodPatch {
+	pub ops: Vec<PatchOp>,
+}
+impl WoodPatch {
+	pub fn is_empty(&self)-> bool { self.ops.is_empty() }
+	pub fn len(&self)-> usize { self.ops.len() }
+	/// the patch that undoes this one
+	pub fn inverse(&self)-> WoodPatch {
+		WoodPatch{ ops:self.ops.iter().rev().map(|o| o.inverse()).collect() }
+	}
+}
+
+/// from apply_patch, when the wood isn't what the patch expected. op is the index of the op that couldn't be applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchConflict {
+	pub op: usize,
+	pub path: Vec<usize>,
+	pub msg: String,
+}
+impl Display for PatchConflict {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+		write!(f, "op {} of the patch, at {:?}: {}", self.op, self.path, self.msg)
+	}
+}
+impl Error for PatchConflict {
+	fn description(&self) -> &str { self.msg.as_str() }
+}
+
+fn node_at_mut<'a>(v:&'a mut Wood, path:&[usize])-> Option<&'a mut Wood> {
+	let mut at = v;
+	for &i in path {
+		at = match *at {
+			Branchv(ref mut b)=> b.v.get_mut(i)?,
+			Leafv(_)=> return None,
+		};
+	}
+	Some(at)
+}
+
+//the children of the parent of the node at path, and the node's index among them
+fn siblings_mut<'a>(v:&'a mut Wood, path:&[usize])-> Result<(&'a mut Vec<Wood>, usize), String> {
+	let (&i, parent_path) = path.split_last().ok_or_else(|| "the root has no siblings, so nothing can be inserted or removed there".to_string())?;
+	match node_at_mut(v, parent_path) {
+		Some(&mut Branchv(ref mut b))=> Ok((&mut b.v, i)),
+		Some(&mut Leafv(_))=> Err("its parent is a leaf".into()),
+		None=> Err("its parent isn't there".into()),
+	}
+}
+
+fn apply_op(v:&mut Wood, op:&PatchOp)-> Result<(), String> {
+	match *op {
+		PatchOp::Insert{ ref path, v:ref new }=> {
+			let (siblings, i) = siblings_mut(v, path)?;
+			if i > siblings.len() {
+				return Err(format!("there are only {} elements here, so nothing can be inserted at {}", siblings.len(), i));
+			}
+			siblings.insert(i, new.clone());
+		}
+		PatchOp::Remove{ ref path, ref old }=> {
+			let (siblings, i) = siblings_mut(v, path)?;
+			match siblings.get(i) {
+				Some(w) if w == old => {}
+				Some(w)=> return Err(format!("expected {} to be here, but found {}", old.to_string(), w.to_string())),
+				None=> return Err(format!("expected {} to be here, but there's nothing", old.to_string())),
+			}
+			siblings.remove(i);
+		}
+		PatchOp::Change{ ref path, ref old, ref new }=> {
+			match node_at_mut(v, path) {
+				Some(w)=> {
+					if *w != *old {
+						return Err(format!("expected {} to be here, but found {}", old.to_string(), w.to_string()));
+					}
+					*w = new.clone();
+				}
+				None=> return Err(format!("expected {} to be here, but there's nothing", old.to_string())),
+			}
+		}
+	}
+	Ok(())
+}
+
+impl Wood {
+	/// applies the ops of the patch in order. Removes and changes check that what they're removing or changing is what it was when the patch was made, if it isn't, or a path leads nowhere, that's a conflict, and every op that had been applied is undone, leaving self as it was
+	pub fn apply_patch(&mut self, patch:&WoodPatch)-> Result<(), PatchConflict> {
+		for (i, op) in patch.ops.iter().enumerate() {
+			if let Err(msg) = apply_op(self, op) {
+				for done in patch.ops[..i].iter().rev() {
+					apply_op(self, &done.inverse()).expect("undoing an op that was just applied");
+				}
+				return Err(PatchConflict{ op:i, path:op.path().to_vec(), msg });
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Woodable for PatchOp {
+	fn woodify(&self) -> Wood {
+		match *self {
+			PatchOp::Insert{ ref path, ref v }=> branch!("insert", path.woodify(), v.clone()),
+			PatchOp::Remove{ ref path, ref old }=> branch!("remove", path.woodify(), old.clone()),
+			PatchOp::Change{ ref path, ref old, ref new }=> branch!("change", path.woodify(), old.clone(), new.clone()),
+		}
+	}
+}
+impl Dewoodable for PatchOp {
+	fn dewoodify(v:&Wood) -> Result<PatchOp, DewoodifyError> {
+		let mut args = v.tail();
+		let mut next = |what:&str| args.next().ok_or_else(|| DewoodifyError::new(v, format!("this op is missing its {}", what)));
+		let path = Vec::<usize>::dewoodify(next("path")?)?;
+		Ok(match v.initial_str() {
+			"insert"=> PatchOp::Insert{ path, v:next("value")?.clone() },
+			"remove"=> PatchOp::Remove{ path, old:next("old value")?.clone() },
+			"change"=> PatchOp::Change{ path, old:next("old value")?.clone(), new:next("new value")?.clone() },
+			other=> return Err(DewoodifyError::new(v, format!("\"{}\" isn't an op, expected one of: insert, remove, change", other))),
+		})
+	}
+}
+impl Woodable for WoodPatch {
+	fn woodify(&self) -> Wood { self.ops.woodify() }
+}
+impl Dewoodable for WoodPatch {
+	fn dewoodify(v:&Wood) -> Result<WoodPatch, DewoodifyError> {
+		Ok(WoodPatch{ ops:Vec::dewoodify(v)? })
+	}
+}
+
+//whether two nodes are taken to be the same entry, possibly with different contents. Leaves are only the same if they're equal, branches are the same if they have the same key
+fn corresponds(a:&Wood, b:&Wood)-> bool {
+	a.is_leaf() == b.is_leaf() && a.initial_str() == b.initial_str()
+}
+
+//pairs of indices of the longest common subsequence of corresponding elements of a and b, found with Myers' algorithm, which takes time in proportion to the lengths times how many elements differ rather than to the lengths multiplied, and space only in proportion to the lengths
+fn common_subsequence(a:&[Wood], b:&[Wood])-> Vec<(usize, usize)> {
+	let mut ret = Vec::new();
+	lcs_between(a, b, 0, 0, &mut ret);
+	ret
+}
+
+//ao and bo are where a and b start in the lists they were cut from
+fn lcs_between(a:&[Wood], b:&[Wood], ao:usize, bo:usize, out:&mut Vec<(usize, usize)>) {
+	//whatever they start and end with in common is matched straight off, which for the usual small edit leaves little to search
+	let prefix = a.iter().zip(b.iter()).take_while(|&(x, y)| corresponds(x, y)).count();
+	out.extend((0..prefix).map(|i| (ao + i, bo + i)));
+	let (a, b) = (&a[prefix..], &b[prefix..]);
+	let suffix = a.iter().rev().zip(b.iter().rev()).take_while(|&(x, y)| corresponds(x, y)).count();
+	let (a, b) = (&a[..a.len() - suffix], &b[..b.len() - suffix]);
+	let (ao, bo) = (ao + prefix, bo + prefix);
+	if !a.is_empty() && !b.is_empty() {
+		//the edits on either side of the middle snake are fewer than there are in all, so this comes to an end
+		let (x, y, u, v) = middle_snake(a, b);
+		lcs_between(&a[..x], &b[..y], ao, bo, out);
+		out.extend((0..u - x).map(|i| (ao + x + i, bo + y + i)));
+		lcs_between(&a[u..], &b[v..], ao + u, bo + v, out);
+	}
+	out.extend((0..suffix).map(|i| (ao + a.len() + i, bo + b.len() + i)));
+}
+
+//the run of corresponding elements, (x, y) to (u, v), that a shortest edit of a into b goes through halfway, found by searching from both ends at once until the searches meet. Diagonal k of the edit graph is where x - y = k, and each search keeps how far along each diagonal it's got
+fn middle_snake(a:&[Wood], b:&[Wood])-> (usize, usize, usize, usize) {
+	let (n, m) = (a.len() as isize, b.len() as isize);
+	let delta = n - m;
+	let most = (n + m + 1)/2;
+	let offset = most + 1;
+	let mut forward = vec!(0isize; 2*offset as usize + 1);
+	let mut backward = forward.clone(); //counted from the ends, so backward diagonal c is forward diagonal delta - c
+	let at = |k:isize| (offset + k) as usize;
+	for d in 0..most + 1 {
+		for k in (-d..d + 1).step_by(2) {
+			let mut x = if k == -d || (k != d && forward[at(k - 1)] < forward[at(k + 1)]) { forward[at(k + 1)] }else{ forward[at(k - 1)] + 1 };
+			let (x0, y0) = (x, x - k);
+			while x < n && x - k < m && x - k >= 0 && corresponds(&a[x as usize], &b[(x - k) as usize]) { x += 1; }
+			forward[at(k)] = x;
+			let c = delta - k;
+			if delta%2 != 0 && c >= 1 - d && c <= d - 1 && x + backward[at(c)] >= n {
+				return (x0 as usize, y0 as usize, x as usize, (x - k) as usize);
+			}
+		}
+		for c in (-d..d + 1).step_by(2) {
+			let mut x = if c == -d || (c != d && backward[at(c - 1)] < backward[at(c + 1)]) { backward[at(c + 1)] }else{ backward[at(c - 1)] + 1 };
+			let (x0, y0) = (x, x - c);
+			while x < n && x - c < m && x - c >= 0 && corresponds(&a[(n - 1 - x) as usize], &b[(m - 1 - (x - c)) as usize]) { x += 1; }
+			backward[at(c)] = x;
+			let k = delta - c;
+			if delta%2 == 0 && k >= -d && k <= d && forward[at(k)] + x >= n {
+				return ((n - x) as usize, (m - (x - c)) as usize, (n - x0) as usize, (m - y0) as usize);
+			}
+		}
+	}
+	unreachable!() //the searches meet by the time they've each gone halfway
+}
+
+fn child_path(path:&[usize], i:usize)-> Vec<usize> {
+	let mut p = path.to_vec();
+	p.push(i);
+	p
+}
+
+fn diff_into(a:&Wood, b:&Wood, path:&mut Vec<usize>, ops:&mut Vec<PatchOp>) {
+	if a == b { return; }
+	match (a, b) {
+		(&Branchv(ref ab), &Branchv(ref bb))=> {
+			let (av, bv) = (&ab.v, &bb.v);
+			//the index in the wood as it will be while the patch is being applied
+			let mut at = 0;
+			let (mut ai, mut bi) = (0, 0);
+			let mut anchors = common_subsequence(av, bv);
+			anchors.push((av.len(), bv.len()));
+			for (an, bn) in anchors {
+				//the unmatched runs before the anchor. Pair them up as changes, then remove or insert whatever's left over
+				while ai < an && bi < bn {
+					path.push(at);
+					diff_into(&av[ai], &bv[bi], path, ops);
+					path.pop();
+					ai += 1;
+					bi += 1;
+					at += 1;
+				}
+				while ai < an {
+					ops.push(PatchOp::Remove{ path:child_path(path, at), old:av[ai].clone() });
+					ai += 1;
+				}
+				while bi < bn {
+					ops.push(PatchOp::Insert{ path:child_path(path, at), v:bv[bi].clone() });
+					bi += 1;
+					at += 1;
+				}
+				//the anchor itself, unless it's the end
+				if an < av.len() {
+					path.push(at);
+					diff_into(&av[an], &bv[bn], path, ops);
+					path.pop();
+				}
+				ai += 1;
+				bi += 1;
+				at += 1;
+			}
+		}
+		_=> ops.push(PatchOp::Change{ path:path.clone(), old:a.clone(), new:b.clone() }),
+	}
+}
+
+/// the edits that turn a into b. The children of branches are matched up by their keys, their initial_strs, and matching branches are described by the changes to their contents rather than being replaced outright. Children that don't match anything are inserted, removed, or, where one has been swapped for another, changed
+pub fn diff(a:&Wood, b:&Wood)-> WoodPatch {
+	let mut ops = Vec::new();
+	diff_into(a, b, &mut Vec::new(), &mut ops);
+	WoodPatch{ ops }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn diffing() {
+		let a = parse_termpose("server (host a) (port 80) (tags x y) debug").unwrap();
+		let b = parse_termpose("server (host a) (port 81) (tags x z y) verbose extra").unwrap();
+		let p = diff(&a, &b);
+		assert_eq!(p.ops, vec!(
+			PatchOp::Change{ path:vec!(2, 1), old:wood!(80), new:wood!(81) },
+			PatchOp::Insert{ path:vec!(3, 2), v:wood!(z) },
+			PatchOp::Change{ path:vec!(4), old:wood!(debug), new:wood!(verbose) },
+			PatchOp::Insert{ path:vec!(5), v:wood!(extra) },
+		));
+		assert_eq!(pretty_termpose(&p.woodify()), "change 2:1 80 81\ninsert 3:2 z\nchange (4) debug verbose\ninsert (5) extra");
+		assert_eq!(WoodPatch::dewoodify(&p.woodify()).unwrap(), p);
+		assert!(diff(&a, &a.clone()).is_empty());
+		assert_eq!(diff(&wood!(a b c), &wood!((c))).ops, vec!(
+			PatchOp::Remove{ path:vec!(0), old:wood!(a) },
+			PatchOp::Remove{ path:vec!(0), old:wood!(b) },
+		));
+		assert_eq!(diff(&wood!(a), &wood!((a))).ops, vec!(PatchOp::Change{ path:vec!(), old:wood!(a), new:wood!((a)) }));
+	}
+	
+	#[test]
+	fn patching() {
+		let a = parse_multiline_termpose("
+server
+  host a
+  port 80
+  tags x y
+debug
+").unwrap();
+		let b = parse_multiline_termpose("
+server
+  port 81
+  tags x z y
+  tls on
+verbose
+").unwrap();
+		let p = diff(&a, &b);
+		let mut patched = a.clone();
+		patched.apply_patch(&p).unwrap();
+		assert_eq!(patched, b);
+		patched.apply_patch(&p.inverse()).unwrap();
+		assert_eq!(patched, a);
+		//someone else has changed the port in the meantime
+		let mut theirs = a.clone();
+		theirs.replace(&["server", "port"], wood!(port 8080));
+		let mut edited = theirs.clone();
+		let e = edited.apply_patch(&p).unwrap_err();
+		assert_eq!(e.path, vec!(0, 1, 1));
+		assert_eq!(e.msg, "expected 80 to be here, but found 8080");
+		assert!(edited.identical(&theirs));
+		let e = wood!(a).apply_patch(&WoodPatch{ ops:vec!(PatchOp::Insert{ path:vec!(), v:wood!(b) }) }).unwrap_err();
+		assert_eq!(e.op, 0);
+	}
+	
+	#[test]
+	fn long_lists() {
+		//a whole table for these would be billions of entries
+		let a:Vec<Wood> = (0..60000).map(|i| Wood::leaf(i.to_string())).collect();
+		let mut b = a.clone();
+		b[30000] = "changed".into();
+		b.insert(100, "new".into());
+		b.remove(59000);
+		let (a, b) = (Wood::branch(a), Wood::branch(b));
+		let p = diff(&a, &b);
+		assert_eq!(p.ops.len(), 3);
+		let mut patched = a.clone();
+		patched.apply_patch(&p).unwrap();
+		assert_eq!(patched, b);
+		
+		//against the whole table, on lists with plenty of ways to match up
+		let mut seed = 7u32;
+		let mut list = |n:usize|-> Vec<Wood> {
+			(0..n).map(|_|{ seed = seed.wrapping_mul(1103515245).wrapping_add(12345); Wood::leaf(((seed >> 16)%4).to_string()) }).collect()
+		};
+		for n in 0..40 {
+			let (a, b) = (list(n), list(40 - n));
+			let mut lengths = vec!(vec!(0usize; b.len() + 1); a.len() + 1);
+			for i in (0..a.len()).rev() {
+				for j in (0..b.len()).rev() {
+					lengths[i][j] = if corresponds(&a[i], &b[j]) { lengths[i + 1][j + 1] + 1 } else { lengths[i + 1][j].max(lengths[i][j + 1]) };
+				}
+			}
+			let found = common_subsequence(&a, &b);
+			assert_eq!(found.len(), lengths[0][0]);
+			assert!(found.iter().all(|&(i, j)| corresponds(&a[i], &b[j])));
+			assert!(found.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 < w[1].1));
+		}
+	}
+}
@@ -0,0 +1,229 @@
+//a little query language for picking things out of wood, see Query
+
+use super::*;
+
+/// a compiled query, EG, `deps.*.version`. Steps are separated by dots. Each step is a key, which selects the `(key …)` branches whose initial str is that key, or a `*`, which selects everything. As with get_path, the first step looks among the contents of the wood it's run on and each later step looks among the tail of what the previous step selected. Keys containing dots or brackets can be written in double quotes.
+///
+/// A step can be followed by any number of bracketed filters, which are applied, in order, to what it selected under each parent:
+///
+/// * `[2]` keeps only the third, `[-1]` only the last
+/// * `[1..3]`, `[1..]` and `[..3]` keep a range
+/// * `[=v]` keeps those whose initial str is v, `[^=v]` those whose initial str starts with v, `[$=v]` those whose initial str ends with v and `[*=v]` those whose initial str contains v
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+	steps: Vec<Step>,
+}
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+	key: Option<String>, //None for *
+	filters: Vec<Filter>,
+}
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+	Index(isize),
+	Range(Option<isize>, Option<isize>),
+	Equals(String),
+	Prefix(String),
+	Suffix(String),
+	Contains(String),
+}
+
+/// a query that couldn't be parsed. position is the byte offset in the query where the problem was
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+	pub position: usize,
+	pub msg: String,
+}
+impl Display for QueryError {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+		write!(f, "at {} in the query: {}", self.position, self.msg)
+	}
+}
+impl Error for QueryError {
+	fn description(&self) -> &str { self.msg.as_str() }
+}
+
+fn query_error<T>(position:usize, msg:String)-> Result<T, QueryError> {
+	Err(QueryError{ position, msg })
+}
+
+//negative indices count from the end
+fn resolve_index(i:isize, len:usize)-> usize {
+	if i < 0 {
+		len.saturating_sub(i.unsigned_abs())
+	}else{
+		(i as usize).min(len)
+	}
+}
+
+fn parse_index(s:&str, position:usize)-> Result<isize, QueryError> {
+	s.trim().parse().or_else(|_| query_error(position, format!("\"{}\" isn't an index", s)))
+}
+
+fn parse_filter(s:&str, position:usize)-> Result<Filter, QueryError> {
+	Ok(if s.starts_with("^=") {
+		Filter::Prefix(s[2..].to_string())
+	}else if s.starts_with("$=") {
+		Filter::Suffix(s[2..].to_string())
+	}else if s.starts_with("*=") {
+		Filter::Contains(s[2..].to_string())
+	}else if s.starts_with('=') {
+		Filter::Equals(s[1..].to_string())
+	}else if let Some(dots) = s.find("..") {
+		let bound = |b:&str| if b.trim().is_empty() { Ok(None) } else { parse_index(b, position).map(Some) };
+		Filter::Range(bound(&s[..dots])?, bound(&s[dots + 2..])?)
+	}else{
+		Filter::Index(parse_index(s, position)?)
+	})
+}
+
+impl Query {
+	pub fn parse(q:&str)-> Result<Query, QueryError> {
+		let mut steps = Vec::new();
+		let mut chars = q.char_indices().peekable();
+		loop {
+			//the key
+			let key = match chars.peek().cloned() {
+				Some((_, '*'))=> {
+					chars.next();
+					None
+				}
+				Some((start, '"'))=> {
+					chars.next();
+					let mut k = String::new();
+					loop {
+						match chars.next() {
+							Some((_, '"'))=> break,
+							Some((_, c))=> k.push(c),
+							None=> return query_error(start, "this quote is never closed".into()),
+						}
+					}
+					Some(k)
+				}
+				_=> {
+					let mut k = String::new();
+					while let Some(&(_, c)) = chars.peek() {
+						if c == '.' || c == '[' { break; }
+						k.push(c);
+						chars.next();
+					}
+					Some(k)
+				}
+			};
+			if key.as_ref().map_or(false, |k| k.is_empty()) {
+				let at = chars.peek().map_or(q.len(), |&(i, _)| i);
+				return query_error(at, "expected a key or a * here".into());
+			}
+			//the filters
+			let mut filters = Vec::new();
+			while let Some(&(start, '[')) = chars.peek() {
+				chars.next();
+				let mut f = String::new();
+				loop {
+					match chars.next() {
+						Some((_, ']'))=> break,
+						Some((_, c))=> f.push(c),
+						None=> return query_error(start, "this bracket is never closed".into()),
+					}
+				}
+				filters.push(parse_filter(&f, start + 1)?);
+			}
+			steps.push(Step{ key, filters });
+			match chars.next() {
+				Some((_, '.'))=> {}
+				Some((i, c))=> return query_error(i, format!("expected a . or the end of the query, but found '{}'", c)),
+				None=> break,
+			}
+		}
+		Ok(Query{ steps })
+	}
+	
+	/// everything the query selects, in document order
+	pub fn select<'a>(&self, v:&'a Wood)-> Vec<&'a Wood> {
+		let mut current:Vec<&'a Wood> = vec!(v);
+		for (i, step) in self.steps.iter().enumerate() {
+			let mut next = Vec::new();
+			for parent in current {
+				let children = if i == 0 { parent.contents() } else { parent.tail() };
+				let mut picked:Vec<&'a Wood> = match step.key {
+					Some(ref k)=> children.filter(|c| c.initial_str() == k).collect(),
+					None=> children.collect(),
+				};
+				for f in step.filters.iter() {
+					picked = match *f {
+						Filter::Index(i)=> {
+							let at = if i < 0 { picked.len().checked_sub(i.unsigned_abs()) } else { Some(i as usize) };
+							at.and_then(|at| picked.get(at).cloned()).into_iter().collect()
+						}
+						Filter::Range(from, to)=> {
+							let len = picked.len();
+							let from = from.map_or(0, |b| resolve_index(b, len));
+							let to = to.map_or(len, |b| resolve_index(b, len));
+							if from < to { picked[from..to].to_vec() } else { Vec::new() }
+						}
+						Filter::Equals(ref s)=> picked.into_iter().filter(|c| c.initial_str() == s).collect(),
+						Filter::Prefix(ref s)=> picked.into_iter().filter(|c| c.initial_str().starts_with(s.as_str())).collect(),
+						Filter::Suffix(ref s)=> picked.into_iter().filter(|c| c.initial_str().ends_with(s.as_str())).collect(),
+						Filter::Contains(ref s)=> picked.into_iter().filter(|c| c.initial_str().contains(s.as_str())).collect(),
+					};
+				}
+				next.extend(picked);
+			}
+			current = next;
+		}
+		current
+	}
+}
+
+impl Wood {
+	/// parses the query and runs it, see Query for the syntax. If the same query is going to be run many times, parse it once with Query::parse instead
+	pub fn select(&self, q:&str)-> Result<Vec<&Wood>, QueryError> {
+		Ok(Query::parse(q)?.select(self))
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn deps()-> Wood {
+		parse_multiline_termpose("
+deps
+  serde
+    version 1
+    features derive
+  serde_json
+    version 1.0.9
+  rand
+    version 0.8
+").unwrap()
+	}
+	
+	#[test]
+	fn selecting() {
+		let w = deps();
+		let versions:Vec<String> = w.select("deps.*.version").unwrap().iter().map(|v| v.tail().next().unwrap().initial_str().to_string()).collect();
+		assert_eq!(versions, vec!("1", "1.0.9", "0.8"));
+		assert_eq!(w.select("deps.*[-1]").unwrap(), vec!(&wood!(rand (version "0.8"))));
+		assert_eq!(w.select("deps.*[1..]").unwrap().len(), 2);
+		assert_eq!(w.select("deps.*[..1].*").unwrap().len(), 2);
+		let serdes:Vec<&str> = w.select("deps.*[^=serde]").unwrap().iter().map(|v| v.initial_str()).collect();
+		assert_eq!(serdes, vec!("serde", "serde_json"));
+		assert_eq!(w.select("deps.*[*=an].version").unwrap(), vec!(&wood!(version "0.8")));
+		assert_eq!(w.select("deps.*[$=json][=serde]").unwrap().len(), 0);
+		assert_eq!(w.select("deps.\"serde\".features").unwrap().len(), 1);
+		assert_eq!(w.select("nothing.*").unwrap().len(), 0);
+	}
+	
+	#[test]
+	fn bad_queries() {
+		assert_eq!(Query::parse("deps..version").unwrap_err().position, 5);
+		assert_eq!(Query::parse("deps.*[1").unwrap_err().position, 6);
+		assert_eq!(Query::parse("deps.*[x]").unwrap_err().msg, "\"x\" isn't an index");
+		assert_eq!(Query::parse("deps.*x").unwrap_err().position, 6);
+		//the most negative index there is has no positive counterpart
+		assert!(deps().select("deps.*[-9223372036854775808]").unwrap().is_empty());
+		assert_eq!(deps().select("deps.*[-9223372036854775808..]").unwrap().len(), 3);
+	}
+}
@@ -0,0 +1,196 @@
+//a serde Serializer that produces Wood. The layout is the same as what wood_derive produces: structs are `Name (field value)…`, unit variants are leaves, other variants are `Variant args…`, options are `none` or `(some v)`, maps are branches of `(key value)` pairs
+
+use super::*;
+use serde::ser::{self, Serialize};
+
+impl ser::Error for DewoodifyError {
+	fn custom<T:Display>(msg:T)-> Self {
+		DewoodifyError::located(-1, -1, msg.to_string())
+	}
+}
+
+/// translates anything that implements serde's Serialize to Wood
+pub fn to_wood<T>(v:&T)-> Result<Wood, DewoodifyError> where T: Serialize + ?Sized {
+	v.serialize(Serializer)
+}
+/// translates anything that implements serde's Serialize to termpose
+pub fn to_string<T>(v:&T)-> Result<String, DewoodifyError> where T: Serialize + ?Sized {
+	to_wood(v).map(|w| pretty_termpose(&w))
+}
+
+/// so that wood can be embedded in other serde formats. Branches become sequences and leaves become strings
+impl Serialize for Wood {
+	fn serialize<S>(&self, s:S)-> Result<S::Ok, S::Error> where S: ser::Serializer {
+		match *self {
+			Leafv(ref l)=> s.serialize_str(&l.v),
+			Branchv(ref b)=> s.collect_seq(b.v.iter()),
+		}
+	}
+}
+
+pub struct Serializer;
+
+/// accumulates the elements of sequences, tuples, structs and maps. key holds a map key until its value arrives
+pub struct Compound {
+	v:Vec<Wood>,
+	key:Option<Wood>,
+}
+impl Compound {
+	fn new()-> Self { Compound{ v:Vec::new(), key:None } }
+	fn tagged(tag:&str)-> Self { Compound{ v:vec!(tag.into()), key:None } }
+}
+
+impl ser::Serializer for Serializer {
+	type Ok = Wood;
+	type Error = DewoodifyError;
+	type SerializeSeq = Compound;
+	type SerializeTuple = Compound;
+	type SerializeTupleStruct = Compound;
+	type SerializeTupleVariant = Compound;
+	type SerializeMap = Compound;
+	type SerializeStruct = Compound;
+	type SerializeStructVariant = Compound;
+	
+	fn serialize_bool(self, v:bool)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_i8(self, v:i8)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_i16(self, v:i16)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_i32(self, v:i32)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_i64(self, v:i64)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_i128(self, v:i128)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_u8(self, v:u8)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_u16(self, v:u16)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_u32(self, v:u32)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_u64(self, v:u64)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_u128(self, v:u128)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_f32(self, v:f32)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_f64(self, v:f64)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_char(self, v:char)-> Result<Wood, DewoodifyError> { Ok(v.woodify()) }
+	fn serialize_str(self, v:&str)-> Result<Wood, DewoodifyError> { Ok(v.into()) }
+	fn serialize_bytes(self, v:&[u8])-> Result<Wood, DewoodifyError> { Ok(v.to_vec().woodify()) }
+	fn serialize_none(self)-> Result<Wood, DewoodifyError> { Ok("none".into()) }
+	fn serialize_some<T>(self, v:&T)-> Result<Wood, DewoodifyError> where T: Serialize + ?Sized {
+		Ok(branch!("some", to_wood(v)?))
+	}
+	fn serialize_unit(self)-> Result<Wood, DewoodifyError> { Ok(branch!()) }
+	fn serialize_unit_struct(self, name:&'static str)-> Result<Wood, DewoodifyError> { Ok(branch!(name)) }
+	fn serialize_unit_variant(self, _name:&'static str, _index:u32, variant:&'static str)-> Result<Wood, DewoodifyError> {
+		Ok(variant.into())
+	}
+	fn serialize_newtype_struct<T>(self, _name:&'static str, v:&T)-> Result<Wood, DewoodifyError> where T: Serialize + ?Sized {
+		to_wood(v)
+	}
+	fn serialize_newtype_variant<T>(self, _name:&'static str, _index:u32, variant:&'static str, v:&T)-> Result<Wood, DewoodifyError> where T: Serialize + ?Sized {
+		Ok(branch!(variant, to_wood(v)?))
+	}
+	fn serialize_seq(self, _len:Option<usize>)-> Result<Compound, DewoodifyError> { Ok(Compound::new()) }
+	fn serialize_tuple(self, _len:usize)-> Result<Compound, DewoodifyError> { Ok(Compound::new()) }
+	fn serialize_tuple_struct(self, name:&'static str, _len:usize)-> Result<Compound, DewoodifyError> { Ok(Compound::tagged(name)) }
+	fn serialize_tuple_variant(self, _name:&'static str, _index:u32, variant:&'static str, _len:usize)-> Result<Compound, DewoodifyError> {
+		Ok(Compound::tagged(variant))
+	}
+	fn serialize_map(self, _len:Option<usize>)-> Result<Compound, DewoodifyError> { Ok(Compound::new()) }
+	fn serialize_struct(self, name:&'static str, _len:usize)-> Result<Compound, DewoodifyError> { Ok(Compound::tagged(name)) }
+	fn serialize_struct_variant(self, _name:&'static str, _index:u32, variant:&'static str, _len:usize)-> Result<Compound, DewoodifyError> {
+		Ok(Compound::tagged(variant))
+	}
+}
+
+impl ser::SerializeSeq for Compound {
+	type Ok = Wood;
+	type Error = DewoodifyError;
+	fn serialize_element<T>(&mut self, v:&T)-> Result<(), DewoodifyError> where T: Serialize + ?Sized {
+		self.v.push(to_wood(v)?);
+		Ok(())
+	}
+	fn end(self)-> Result<Wood, DewoodifyError> { Ok(self.v.into()) }
+}
+impl ser::SerializeTuple for Compound {
+	type Ok = Wood;
+	type Error = DewoodifyError;
+	fn serialize_element<T>(&mut self, v:&T)-> Result<(), DewoodifyError> where T: Serialize + ?Sized {
+		ser::SerializeSeq::serialize_element(self, v)
+	}
+	fn end(self)-> Result<Wood, DewoodifyError> { Ok(self.v.into()) }
+}
+impl ser::SerializeTupleStruct for Compound {
+	type Ok = Wood;
+	type Error = DewoodifyError;
+	fn serialize_field<T>(&mut self, v:&T)-> Result<(), DewoodifyError> where T: Serialize + ?Sized {
+		ser::SerializeSeq::serialize_element(self, v)
+	}
+	fn end(self)-> Result<Wood, DewoodifyError> { Ok(self.v.into()) }
+}
+impl ser::SerializeTupleVariant for Compound {
+	type Ok = Wood;
+	type Error = DewoodifyError;
+	fn serialize_field<T>(&mut self, v:&T)-> Result<(), DewoodifyError> where T: Serialize + ?Sized {
+		ser::SerializeSeq::serialize_element(self, v)
+	}
+	fn end(self)-> Result<Wood, DewoodifyError> { Ok(self.v.into()) }
+}
+impl ser::SerializeMap for Compound {
+	type Ok = Wood;
+	type Error = DewoodifyError;
+	fn serialize_key<T>(&mut self, k:&T)-> Result<(), DewoodifyError> where T: Serialize + ?Sized {
+		self.key = Some(to_wood(k)?);
+		Ok(())
+	}
+	fn serialize_value<T>(&mut self, v:&T)-> Result<(), DewoodifyError> where T: Serialize + ?Sized {
+		let k = self.key.take().unwrap_or_else(|| branch!());
+		self.v.push(branch!(k, to_wood(v)?));
+		Ok(())
+	}
+	fn end(self)-> Result<Wood, DewoodifyError> { Ok(self.v.into()) }
+}
+impl ser::SerializeStruct for Compound {
+	type Ok = Wood;
+	type Error = DewoodifyError;
+	fn serialize_field<T>(&mut self, key:&'static str, v:&T)-> Result<(), DewoodifyError> where T: Serialize + ?Sized {
+		self.v.push(branch!(key, to_wood(v)?));
+		Ok(())
+	}
+	fn end(self)-> Result<Wood, DewoodifyError> { Ok(self.v.into()) }
+}
+impl ser::SerializeStructVariant for Compound {
+	type Ok = Wood;
+	type Error = DewoodifyError;
+	fn serialize_field<T>(&mut self, key:&'static str, v:&T)-> Result<(), DewoodifyError> where T: Serialize + ?Sized {
+		ser::SerializeStruct::serialize_field(self, key, v)
+	}
+	fn end(self)-> Result<Wood, DewoodifyError> { Ok(self.v.into()) }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+	
+	#[derive(Serialize)]
+	struct Server { name:String, port:u16, tags:Vec<String>, backup:Option<u16> }
+	
+	#[derive(Serialize)]
+	enum Shape { Empty, Circle(f64), Rect(f64, f64), Named{ name:String } }
+	
+	#[test]
+	fn serializing() {
+		let s = Server{ name:"a".into(), port:81, tags:vec!("x".into(), "y".into()), backup:None };
+		assert_eq!(to_wood(&s).unwrap().to_string(), "(Server (name a) (port 81) (tags (x y)) (backup none))");
+		assert_eq!(to_string(&s).unwrap(), "Server name:a port:81 tags:x:y backup:none");
+		assert_eq!(to_wood(&Some(3)).unwrap().to_string(), "(some 3)");
+		assert_eq!(to_wood(&Shape::Empty).unwrap().to_string(), "Empty");
+		assert_eq!(to_wood(&Shape::Circle(1.5)).unwrap().to_string(), "(Circle 1.5)");
+		assert_eq!(to_wood(&Shape::Rect(1.0, 2.0)).unwrap().to_string(), "(Rect 1 2)");
+		assert_eq!(to_wood(&Shape::Named{ name:"n".into() }).unwrap().to_string(), "(Named (name n))");
+		let mut m = BTreeMap::new();
+		m.insert("k", (1, true));
+		assert_eq!(to_wood(&m).unwrap().to_string(), "((k (1 true)))");
+	}
+	
+	#[test]
+	fn serializing_wood() {
+		let w = parse_termpose("a (b c) ()").unwrap();
+		assert_eq!(to_wood(&w).unwrap(), w);
+		assert_eq!(to_wood(&(1, w)).unwrap().to_string(), "(1 (a (b c) ()))");
+	}
+}
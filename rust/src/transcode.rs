@@ -0,0 +1,205 @@
+//converting between termpose and other serde formats without going through any intermediate rust types, via serde-transcode. Into wood, everything is laid out the way ser.rs would lay it out. Out of wood, there are no types to go by, so the shape of the data is guessed at by Inferring, as far as InferOptions allows
+
+use super::*;
+use serde::de::{self, Visitor, DeserializeSeed};
+use serde_transcode::transcode;
+use std::collections::HashSet;
+
+/// translates whatever a serde Deserializer (EG, serde_json's) contains into Wood
+pub fn to_wood<'de, D>(d:D)-> Result<Wood, DewoodifyError> where D: de::Deserializer<'de> {
+	transcode(d, super::ser::Serializer)
+}
+/// translates whatever a serde Deserializer contains into termpose, with each element of the top level on a line of its own, the way from_termpose reads it. A top level that's neither a list nor a map comes back from from_termpose as a list of one
+pub fn to_termpose<'de, D>(d:D)-> Result<String, DewoodifyError> where D: de::Deserializer<'de> {
+	to_wood(d).map(|w| w.contents().map(|r| r.to_pretty_string()).collect::<Vec<String>>().join("\n"))
+}
+/// writes the wood out through a serde Serializer (EG, serde_json's), guessing at its shape as described for Inferring
+pub fn from_wood<S>(v:&Wood, s:S)-> Result<S::Ok, S::Error> where S: serde::Serializer {
+	from_wood_with(&InferOptions::default(), v, s)
+}
+pub fn from_wood_with<S>(options:&InferOptions, v:&Wood, s:S)-> Result<S::Ok, S::Error> where S: serde::Serializer {
+	transcode(Inferring::with(options, v), s)
+}
+/// parses termpose with parse_multiline_termpose, so each root is an element of the top level, and writes it out through a serde Serializer
+pub fn from_termpose<S>(v:&str, s:S)-> Result<S::Ok, S::Error> where S: serde::Serializer {
+	from_termpose_with(&InferOptions::default(), v, s)
+}
+pub fn from_termpose_with<S>(options:&InferOptions, v:&str, s:S)-> Result<S::Ok, S::Error> where S: serde::Serializer {
+	match parse_multiline_termpose(v) {
+		Ok(w)=> from_wood_with(options, &w, s),
+		Err(e)=> Err(<S::Error as serde::ser::Error>::custom(e)),
+	}
+}
+
+/// what Inferring guesses at. Wood doesn't say whether a leaf is a string or a number, or whether a branch of pairs is a map or a list, so a list of `(key value)` pairs will be taken for a map and the string "5" for a number unless that's turned off. An empty branch is always an empty list, so an empty map comes back as one of those
+#[derive(Clone, Copy, Debug)]
+pub struct InferOptions {
+	/// leaves that read as bools or numbers, and would be written exactly the same way again, are given as bools and numbers. "01234", "+5" and "1e3" stay strings. Default true
+	pub scalars: bool,
+	/// a nonempty branch whose every element is a `(key value)` pair, with no key repeated, is given as a map. Default true
+	pub maps: bool,
+}
+impl Default for InferOptions {
+	fn default()-> Self { InferOptions{ scalars:true, maps:true } }
+}
+
+/// a self-describing view of wood, for formats that need to know what they're looking at. Leaves are strings and branches are lists, apart from what InferOptions says to take for something else
+#[derive(Clone, Copy)]
+pub struct Inferring<'de> {
+	v:&'de Wood,
+	options:InferOptions,
+}
+impl<'de> Inferring<'de> {
+	pub fn new(v:&'de Wood)-> Self { Inferring{ v, options:InferOptions::default() } }
+	pub fn with(options:&InferOptions, v:&'de Wood)-> Self { Inferring{ v, options:*options } }
+	fn at(&self, v:&'de Wood)-> Self { Inferring{ v, options:self.options } }
+}
+
+fn is_map(v:&[Wood])-> bool {
+	let mut keys = HashSet::new();
+	!v.is_empty() && v.iter().all(|w| match *w {
+		Branchv(ref b)=> b.v.len() == 2 && b.v[0].is_leaf() && keys.insert(b.v[0].initial_str()),
+		Leafv(_)=> false,
+	})
+}
+
+//s as a T, if it reads as one and would be written back exactly as it is
+fn exactly<T>(s:&str)-> Option<T> where T: FromStr + ToString {
+	s.parse::<T>().ok().filter(|n| n.to_string() == s)
+}
+
+impl<'de> de::Deserializer<'de> for Inferring<'de> {
+	type Error = DewoodifyError;
+	
+	fn deserialize_any<V>(self, visitor:V)-> Result<V::Value, DewoodifyError> where V: Visitor<'de> {
+		match *self.v {
+			Leafv(ref l)=> {
+				let s = l.v.as_str();
+				if !self.options.scalars {
+					visitor.visit_borrowed_str(s)
+				}else if let Some(b) = exactly::<bool>(s) {
+					visitor.visit_bool(b)
+				}else if let Some(n) = exactly::<u64>(s) {
+					visitor.visit_u64(n)
+				}else if let Some(n) = exactly::<i64>(s) {
+					visitor.visit_i64(n)
+				}else if let Some(n) = exactly::<f64>(s).filter(|n| n.is_finite()) {
+					visitor.visit_f64(n)
+				}else{
+					visitor.visit_borrowed_str(s)
+				}
+			}
+			Branchv(ref b)=> {
+				if self.options.maps && is_map(&b.v) {
+					visitor.visit_map(InferringMap{ it:b.v.iter(), value:None, at:self })
+				}else{
+					visitor.visit_seq(InferringSeq{ it:b.v.iter(), at:self })
+				}
+			}
+		}
+	}
+	
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf option unit unit_struct newtype_struct seq tuple
+		tuple_struct map struct enum identifier ignored_any
+	}
+}
+
+struct InferringSeq<'de> {
+	it:std::slice::Iter<'de, Wood>,
+	at:Inferring<'de>,
+}
+impl<'de> de::SeqAccess<'de> for InferringSeq<'de> {
+	type Error = DewoodifyError;
+	fn next_element_seed<T>(&mut self, seed:T)-> Result<Option<T::Value>, DewoodifyError> where T: DeserializeSeed<'de> {
+		match self.it.next() {
+			Some(w)=> seed.deserialize(self.at.at(w)).map(Some),
+			None=> Ok(None),
+		}
+	}
+	fn size_hint(&self)-> Option<usize> { Some(self.it.len()) }
+}
+
+//only constructed over branches that is_map
+struct InferringMap<'de> {
+	it:std::slice::Iter<'de, Wood>,
+	value:Option<&'de Wood>,
+	at:Inferring<'de>,
+}
+impl<'de> de::MapAccess<'de> for InferringMap<'de> {
+	type Error = DewoodifyError;
+	fn next_key_seed<K>(&mut self, seed:K)-> Result<Option<K::Value>, DewoodifyError> where K: DeserializeSeed<'de> {
+		match self.it.next() {
+			Some(&Branchv(ref b))=> {
+				self.value = Some(&b.v[1]);
+				seed.deserialize(super::de::Deserializer::new(&b.v[0])).map(Some)
+			}
+			_=> Ok(None),
+		}
+	}
+	fn next_value_seed<V>(&mut self, seed:V)-> Result<V::Value, DewoodifyError> where V: DeserializeSeed<'de> {
+		match self.value.take() {
+			Some(w)=> seed.deserialize(self.at.at(w)),
+			None=> Err(de::Error::custom("next_value_seed called before next_key_seed")),
+		}
+	}
+	fn size_hint(&self)-> Option<usize> { Some(self.it.len()) }
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+	use serde::de::IntoDeserializer;
+	use serde::Deserialize;
+	
+	#[derive(Deserialize, Debug, PartialEq)]
+	struct Listing { port:u16, on:bool, ratio:f64, tags:Vec<String>, limits:BTreeMap<String, i32> }
+	
+	#[test]
+	fn transcoding() {
+		let mut m = BTreeMap::new();
+		m.insert("a", 1u32);
+		m.insert("b", 2u32);
+		let d:de::value::MapDeserializer<_, DewoodifyError> = m.into_deserializer();
+		assert_eq!(to_termpose(d).unwrap(), "a 1\nb 2");
+		
+		let w = parse_termpose("(port 80) (on true) (ratio 0.5) (tags (x y)) (limits ((low -3) (high 3)))").unwrap();
+		let l = Listing::deserialize(Inferring::new(&w)).unwrap();
+		assert_eq!(l, Listing{ port:80, on:true, ratio:0.5, tags:vec!("x".into(), "y".into()), limits:vec!(("low".into(), -3), ("high".into(), 3)).into_iter().collect() });
+		//and back into wood, through the inference
+		assert_eq!(from_termpose("tags (x y)\nn 1", super::super::ser::Serializer).unwrap().to_string(), "((tags (x y)) (n 1))");
+	}
+	
+	fn json_round_trip(json:&str) {
+		let termpose = to_termpose(&mut serde_json::Deserializer::from_str(json)).unwrap();
+		let mut out = Vec::new();
+		from_termpose(&termpose, &mut serde_json::Serializer::new(&mut out)).unwrap();
+		let back:serde_json::Value = serde_json::from_slice(&out).unwrap();
+		assert_eq!(back, serde_json::from_str::<serde_json::Value>(json).unwrap(), "through {:?}", termpose);
+	}
+	
+	#[test]
+	fn json() {
+		json_round_trip(r#"{"a":1}"#);
+		json_round_trip(r#"{"name":"main","port":8080,"ratio":0.25,"debug":false,"offset":-3,"tags":["x","y z"],"nested":{"b":[1,[2,3],[]],"c":{"d":"e"}}}"#);
+		json_round_trip(r#"{"zip":"01234","plus":"+5","exp":"1e3","half":"0.50","lines":"first\nsecond","inf":"inf"}"#);
+		json_round_trip(r#"[[1],[[2]],"x",[]]"#);
+		json_round_trip(r#"[{"a":1},{"a":2}]"#);
+		json_round_trip(r#"[["k",1],["k",2]]"#);
+		
+		let to_json = |options:&InferOptions, t:&str|-> String {
+			let mut out = Vec::new();
+			from_termpose_with(options, t, &mut serde_json::Serializer::new(&mut out)).unwrap();
+			String::from_utf8(out).unwrap()
+		};
+		//a list of pairs reads as a map unless that's turned off, and numbers as numbers
+		let pairs = r#"[["a",1],["b",2]]"#;
+		let termpose = to_termpose(&mut serde_json::Deserializer::from_str(pairs)).unwrap();
+		assert_eq!(to_json(&InferOptions::default(), &termpose), r#"{"a":1,"b":2}"#);
+		assert_eq!(to_json(&InferOptions{ maps:false, ..InferOptions::default() }, &termpose), pairs);
+		assert_eq!(to_json(&InferOptions{ scalars:false, maps:false }, &termpose), r#"[["a","1"],["b","2"]]"#);
+	}
+}
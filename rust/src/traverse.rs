@@ -0,0 +1,242 @@
+//walking over every node of a wood. The iterators don't recurse, so arbitrarily deep wood is fine for them, the visitors do
+
+use super::*;
+
+/// the index taken at each level to get to a node from the root, as the traversals give them. Each node's path is its parent's with one more index on the end, and it shares the parent's rather than copying it, so walking deep wood doesn't cost more per node the deeper it goes. to_vec gives the indices
+#[derive(Clone, Default)]
+pub struct WoodPath(Option<Rc<PathLink>>);
+struct PathLink {
+	parent: WoodPath,
+	index: usize,
+	len: usize,
+}
+impl WoodPath {
+	fn child(&self, index:usize)-> WoodPath {
+		WoodPath(Some(Rc::new(PathLink{ parent:self.clone(), index, len:self.len() + 1 })))
+	}
+	pub fn len(&self)-> usize { self.0.as_ref().map_or(0, |l| l.len) }
+	pub fn is_empty(&self)-> bool { self.0.is_none() }
+	/// the index of the node among its siblings. None for the root
+	pub fn last(&self)-> Option<usize> { self.0.as_ref().map(|l| l.index) }
+	/// the path of the node's parent. The root's parent is the root
+	pub fn parent(&self)-> WoodPath { self.0.as_ref().map_or(WoodPath(None), |l| l.parent.clone()) }
+	pub fn to_vec(&self)-> Vec<usize> {
+		let mut ret = vec!(0; self.len());
+		let mut at = &self.0;
+		while let Some(ref l) = *at {
+			ret[l.len - 1] = l.index;
+			at = &l.parent.0;
+		}
+		ret
+	}
+}
+//a long path would otherwise be dropped one recursion per level
+impl Drop for WoodPath {
+	fn drop(&mut self) {
+		let mut next = self.0.take();
+		while let Some(rc) = next {
+			next = match Rc::try_unwrap(rc) {
+				Ok(mut l)=> l.parent.0.take(),
+				Err(_)=> None,
+			};
+		}
+	}
+}
+impl PartialEq for WoodPath {
+	fn eq(&self, other:&WoodPath)-> bool { self.to_vec() == other.to_vec() }
+}
+impl Eq for WoodPath {}
+impl<'a> PartialEq<&'a [usize]> for WoodPath {
+	fn eq(&self, other:&&'a [usize])-> bool { self.to_vec() == *other }
+}
+impl PartialEq<Vec<usize>> for WoodPath {
+	fn eq(&self, other:&Vec<usize>)-> bool { self.to_vec() == *other }
+}
+impl Debug for WoodPath {
+	fn fmt(&self, f:&mut Formatter)-> Result<(), std::fmt::Error> { self.to_vec().fmt(f) }
+}
+impl From<WoodPath> for Vec<usize> {
+	fn from(p:WoodPath)-> Vec<usize> { p.to_vec() }
+}
+
+/// every node in pre-order, with its depth and the index taken at each level to get to it from the root. See Wood::iter_dfs
+pub struct Dfs<'a> {
+	stack: Vec<(&'a Wood, usize, WoodPath)>,
+	max_depth: usize,
+}
+impl<'a> Iterator for Dfs<'a> {
+	type Item = (&'a Wood, usize, WoodPath);
+	fn next(&mut self)-> Option<Self::Item> {
+		let (w, depth, path) = self.stack.pop()?;
+		if let (&Branchv(ref b), true) = (w, depth < self.max_depth) {
+			for (i, c) in b.v.iter().enumerate().rev() {
+				self.stack.push((c, depth + 1, path.child(i)));
+			}
+		}
+		Some((w, depth, path))
+	}
+}
+
+/// every node, level by level, with its depth and the index taken at each level to get to it from the root. See Wood::iter_bfs
+pub struct Bfs<'a> {
+	queue: VecDeque<(&'a Wood, usize, WoodPath)>,
+}
+impl<'a> Iterator for Bfs<'a> {
+	type Item = (&'a Wood, usize, WoodPath);
+	fn next(&mut self)-> Option<Self::Item> {
+		let (w, depth, path) = self.queue.pop_front()?;
+		if let Branchv(ref b) = *w {
+			for (i, c) in b.v.iter().enumerate() {
+				self.queue.push_back((c, depth + 1, path.child(i)));
+			}
+		}
+		Some((w, depth, path))
+	}
+}
+
+/// callbacks for walk, which calls them in document order. If enter_branch returns false, the branch's contents are skipped, but exit_branch is still called
+pub trait WoodVisitor {
+	fn enter_branch(&mut self, _b:&Branch)-> bool { true }
+	fn leaf(&mut self, _l:&Leaf) {}
+	fn exit_branch(&mut self, _b:&Branch) {}
+}
+/// like WoodVisitor, but for walk_mut, so the wood can be changed as it's walked. Changes that enter_branch makes to the branch's contents will be walked, exit_branch sees the contents after they've been walked
+pub trait WoodVisitorMut {
+	fn enter_branch(&mut self, _b:&mut Branch)-> bool { true }
+	fn leaf(&mut self, _l:&mut Leaf) {}
+	fn exit_branch(&mut self, _b:&mut Branch) {}
+}
+
+pub fn walk<V>(w:&Wood, visitor:&mut V) where V: WoodVisitor + ?Sized {
+	match *w {
+		Branchv(ref b)=> {
+			if visitor.enter_branch(b) {
+				for c in b.v.iter() {
+					walk(c, visitor);
+				}
+			}
+			visitor.exit_branch(b);
+		}
+		Leafv(ref l)=> visitor.leaf(l),
+	}
+}
+pub fn walk_mut<V>(w:&mut Wood, visitor:&mut V) where V: WoodVisitorMut + ?Sized {
+	match *w {
+		Branchv(ref mut b)=> {
+			if visitor.enter_branch(b) {
+				for c in b.v.iter_mut() {
+					walk_mut(c, visitor);
+				}
+			}
+			visitor.exit_branch(b);
+		}
+		Leafv(ref mut l)=> visitor.leaf(l),
+	}
+}
+
+impl Wood {
+	pub fn visit<V>(&self, visitor:&mut V) where V: WoodVisitor + ?Sized { walk(self, visitor) }
+	pub fn visit_mut<V>(&mut self, visitor:&mut V) where V: WoodVisitorMut + ?Sized { walk_mut(self, visitor) }
+	/// depth first, parents before their children, starting with self at depth 0 with an empty path
+	pub fn iter_dfs<'a>(&'a self)-> Dfs<'a> { self.iter_dfs_within(usize::MAX) }
+	/// like iter_dfs, but doesn't go any deeper than max_depth
+	pub fn iter_dfs_within<'a>(&'a self, max_depth:usize)-> Dfs<'a> { Dfs{ stack:vec!((self, 0, WoodPath::default())), max_depth } }
+	/// the first node, in depth first order, that satisfies the predicate, along with its path. This searches the whole tree, where find only looks for a key among the contents
+	pub fn find_node<'a, F>(&'a self, mut pred:F)-> Option<(&'a Wood, Vec<usize>)> where F: FnMut(&Wood)-> bool {
+		self.iter_dfs().find(|n| pred(n.0)).map(|(w, _, p)| (w, p.to_vec()))
+	}
+	/// every node, in depth first order, that satisfies the predicate, along with its path
+	pub fn find_all<'a, F>(&'a self, pred:F)-> Vec<(&'a Wood, Vec<usize>)> where F: FnMut(&Wood)-> bool {
+		self.find_all_within(usize::MAX, pred)
+	}
+	/// like find_all, but doesn't look any deeper than max_depth. self is at depth 0
+	pub fn find_all_within<'a, F>(&'a self, max_depth:usize, mut pred:F)-> Vec<(&'a Wood, Vec<usize>)> where F: FnMut(&Wood)-> bool {
+		self.iter_dfs_within(max_depth).filter(|n| pred(n.0)).map(|(w, _, p)| (w, p.to_vec())).collect()
+	}
+	/// breadth first, starting with self at depth 0 with an empty path
+	pub fn iter_bfs<'a>(&'a self)-> Bfs<'a> {
+		let mut queue = VecDeque::new();
+		queue.push_back((self, 0, WoodPath::default()));
+		Bfs{ queue }
+	}
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn traversal_orders() {
+		let w = parse_termpose("a (b c) d").unwrap();
+		let dfs:Vec<(String, usize, Vec<usize>)> = w.iter_dfs().map(|(w, d, p)| (w.to_string(), d, p.to_vec())).collect();
+		assert_eq!(dfs, vec!(
+			("(a (b c) d)".to_string(), 0, vec!()),
+			("a".to_string(), 1, vec!(0)),
+			("(b c)".to_string(), 1, vec!(1)),
+			("b".to_string(), 2, vec!(1, 0)),
+			("c".to_string(), 2, vec!(1, 1)),
+			("d".to_string(), 1, vec!(2)),
+		));
+		let bfs:Vec<String> = w.iter_bfs().map(|(w, _, _)| w.to_string()).collect();
+		assert_eq!(bfs, vec!("(a (b c) d)", "a", "(b c)", "d", "b", "c"));
+		//far deeper than the stack would allow for a recursive walk
+		let mut deep:Wood = "bottom".into();
+		for _ in 0..20000 { deep = Wood::branch(vec!(deep)); }
+		let (_, depth, path) = deep.iter_dfs().last().unwrap();
+		assert_eq!((depth, path.len(), path.last(), path.parent().len()), (20000, 20000, Some(0), 19999));
+		let (_, _, path) = deep.iter_bfs().find(|n| n.0.is_leaf()).unwrap();
+		assert_eq!(path, vec!(0; 20000));
+		dismantle(deep); //dropping it would recurse
+	}
+	
+	#[test]
+	fn predicate_search() {
+		let w = parse_termpose("a (b (todo x)) (todo y) (c (d (todo z)))").unwrap();
+		let is_todo = |n:&Wood| n.is_branch() && n.initial_str() == "todo";
+		assert_eq!(w.find_node(is_todo), Some((&wood!(todo x), vec!(1, 1))));
+		let all:Vec<Vec<usize>> = w.find_all(is_todo).into_iter().map(|n| n.1).collect();
+		assert_eq!(all, vec!(vec!(1, 1), vec!(2), vec!(3, 1, 1)));
+		assert_eq!(w.find_all_within(2, is_todo).len(), 2);
+		assert_eq!(w.find_all_within(0, |n| n.is_leaf()).len(), 0);
+		assert_eq!(w.find_node(|n| *n == "nothing"), None);
+		assert_eq!(w.iter_dfs_within(1).count(), 5);
+	}
+	
+	struct Stats { leaves:usize, deepest:usize, depth:usize, order:String }
+	impl WoodVisitor for Stats {
+		fn enter_branch(&mut self, b:&Branch)-> bool {
+			self.depth += 1;
+			self.deepest = self.deepest.max(self.depth);
+			self.order.push('(');
+			b.v.first().map_or(true, |f| f.initial_str() != "skip")
+		}
+		fn leaf(&mut self, l:&Leaf) {
+			self.leaves += 1;
+			self.order.push_str(&l.v);
+		}
+		fn exit_branch(&mut self, _b:&Branch) {
+			self.depth -= 1;
+			self.order.push(')');
+		}
+	}
+	
+	//uppercases leaves and drops empty branches
+	struct Tidy;
+	impl WoodVisitorMut for Tidy {
+		fn leaf(&mut self, l:&mut Leaf) { l.v = l.v.to_uppercase(); }
+		fn exit_branch(&mut self, b:&mut Branch) {
+			b.v.retain(|c| match *c { Branchv(ref cb)=> cb.v.len() != 0, _=> true });
+		}
+	}
+	
+	#[test]
+	fn visitors() {
+		let mut w = parse_termpose("a (b (c)) (skip d) ()").unwrap();
+		let mut s = Stats{ leaves:0, deepest:0, depth:0, order:String::new() };
+		w.visit(&mut s);
+		assert_eq!((s.leaves, s.deepest, s.order.as_str()), (3, 3, "(a(b(c))()())"));
+		w.visit_mut(&mut Tidy);
+		assert_eq!(w, parse_termpose("A (B (C)) (SKIP D)").unwrap());
+	}
+}
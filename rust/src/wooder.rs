@@ -1,13 +1,26 @@
 
 use super::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, BTreeMap, BTreeSet};
 use std::cmp::Eq;
 use std::hash::Hash;
 use std::iter::FromIterator;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::ops::{Range, RangeInclusive};
+use std::ffi::{OsStr, OsString};
+use std::rc::{Rc, Weak};
+use std::cell::OnceCell;
+use std::sync::LazyLock;
 
 
 /// Specifies a bijection between T and Wood
-pub trait Biwooder<T> : Wooder<T> + Dewooder<T> {}
+pub trait Biwooder<T> : Wooder<T> + Dewooder<T> {
+	/// Reuses this biwooder for another type B, given conversions each way. EG, for a newtype, `Iden.map(|a:u16| Port(a), |b:&Port| b.0)`
+	fn map<B, To, From>(self, to:To, from:From)-> MappedBi<Self, To, From, T>
+		where Self:Sized, To:Fn(T)-> B, From:Fn(&B)-> T
+	{
+		MappedBi(self, to, from, PhantomData)
+	}
+}
 
 impl<T, X> Biwooder<T> for X where X:Wooder<T> + Dewooder<T> {}
 
@@ -23,13 +36,24 @@ impl<'a> FieldScanning<'a>{
 	pub fn new(v:&'a Wood) -> Self {
 		FieldScanning{ v:v, li:v.tail().as_slice(), eye:0, }
 	}
+	/// for branches that don't start with a tag, where every element is a field
+	pub fn new_headless(v:&'a Wood) -> Self {
+		FieldScanning{ v:v, li:v.contents().as_slice(), eye:0, }
+	}
 	pub fn seek(&mut self, key:&str) -> Result<&Wood, DewoodifyError> {
+		match self.seek_opt(key)? {
+			Some(s)=> Ok(s),
+			None=> Err(DewoodifyError::new(self.v, format!("could not find key \"{}\"", key))),
+		}
+	}
+	/// like seek, but a missing key is Ok(None) rather than an error. A key that's present but has no value is still an error.
+	pub fn seek_opt(&mut self, key:&str) -> Result<Option<&'a Wood>, DewoodifyError> {
 		for _ in 0..self.li.len() {
 			let c = &self.li[self.eye];
 			if c.initial_str() == key {
 				return
 					if let Some(s) = c.tail().next() {
-						Ok(s)
+						Ok(Some(s))
 					}else{
 						Err(DewoodifyError::new(c, format!("expected a subwood, but the wood has no tail")))
 					}
@@ -37,7 +61,79 @@ impl<'a> FieldScanning<'a>{
 			self.eye += 1;
 			if self.eye >= self.li.len() { self.eye = 0; }
 		}
-		Err(DewoodifyError::new(self.v, format!("could not find key \"{}\"", key)))
+		Ok(None)
+	}
+}
+
+/// FieldScanning for DewoodableOwned. Takes each field's value out of the wood instead of borrowing it, leaving an empty leaf in its place.
+pub struct FieldTaking {
+	pub v:Wood,
+	pub eye:usize,
+	skip:usize, //1 if the first element is the tag
+}
+impl FieldTaking {
+	pub fn new(v:Wood) -> Self {
+		FieldTaking{ v:v, eye:0, skip:1 }
+	}
+	/// for branches that don't start with a tag, where every element is a field
+	pub fn new_headless(v:Wood) -> Self {
+		FieldTaking{ v:v, eye:0, skip:0 }
+	}
+	/// how many fields there are, taken or not
+	pub fn len(&self) -> usize { self.v.contents().len().saturating_sub(self.skip) }
+	pub fn is_empty(&self) -> bool { self.len() == 0 }
+	fn field_mut(&mut self, i:usize) -> &mut Wood {
+		match self.v {
+			Branchv(ref mut b)=> &mut b.v[i],
+			ref mut l=> l,
+		}
+	}
+	pub fn take(&mut self, key:&str) -> Result<Wood, DewoodifyError> {
+		match self.take_opt(key)? {
+			Some(s)=> Ok(s),
+			None=> Err(DewoodifyError::new(&self.v, format!("could not find key \"{}\"", key))),
+		}
+	}
+	/// like take, but a missing key is Ok(None) rather than an error. A key that's present but has no value is still an error.
+	pub fn take_opt(&mut self, key:&str) -> Result<Option<Wood>, DewoodifyError> {
+		let len = self.len();
+		for _ in 0..len {
+			let i = self.skip + self.eye;
+			let c = self.field_mut(i);
+			if c.initial_str() == key {
+				return match *c {
+					Branchv(ref mut b) if b.v.len() > 1=> Ok(Some(::std::mem::replace(&mut b.v[1], Wood::leaf(String::new())))),
+					ref c=> Err(DewoodifyError::new(c, format!("expected a subwood, but the wood has no tail"))),
+				}
+			}
+			self.eye += 1;
+			if self.eye >= len { self.eye = 0; }
+		}
+		Ok(None)
+	}
+}
+
+
+/// For fields that may be absent. Reads the field named `key` out of a FieldScanning, yielding None (or a default) when it isn't there, and writes it as `(key value)` only when there's a value to write.
+pub struct OptionalField<'a, SubTran> {
+	pub key:&'a str,
+	pub sub:SubTran,
+}
+impl<'a, SubTran> OptionalField<'a, SubTran> {
+	pub fn new(key:&'a str, sub:SubTran)-> Self { OptionalField{ key, sub } }
+	pub fn dewoodify_from<T>(&self, scanning:&mut FieldScanning) -> Result<Option<T>, DewoodifyError> where SubTran:Dewooder<T> {
+		match scanning.seek_opt(self.key)? {
+			Some(w)=> self.sub.dewoodify(w).map(Some),
+			None=> Ok(None),
+		}
+	}
+	pub fn dewoodify_or<T>(&self, scanning:&mut FieldScanning, default:T) -> Result<T, DewoodifyError> where SubTran:Dewooder<T> {
+		self.dewoodify_from(scanning).map(|o| o.unwrap_or(default))
+	}
+	pub fn woodify_into<T>(&self, v:&Option<T>, output:&mut Vec<Wood>) where SubTran:Wooder<T> {
+		if let Some(ref inner) = *v {
+			output.push(branch!(self.key, self.sub.woodify(inner)));
+		}
 	}
 }
 
@@ -103,11 +199,34 @@ impl<B> OptionalBoxBiwooder<B> where B:?Sized {
 }
 
 
+/// For biwooders that can't be constexprs but that you want in a static. Constructed from a closure the first time it's used, EG `static PORTS:LazyBiwooder<BoundedSeqBi<Iden>> = LazyBiwooder::new(|| BoundedSeqBi::new(Iden, 1, 8));`
+pub struct LazyBiwooder<B, F = fn()-> B>(LazyLock<B, F>);
+impl<B, F> LazyBiwooder<B, F> where F:FnOnce()-> B {
+	pub const fn new(f:F)-> Self { LazyBiwooder(LazyLock::new(f)) }
+}
+impl<T, B, F> Wooder<T> for LazyBiwooder<B, F> where B:Wooder<T>, F:FnOnce()-> B {
+	fn woodify(&self, v:&T) -> Wood { self.0.woodify(v) }
+}
+impl<T, B, F> Dewooder<T> for LazyBiwooder<B, F> where B:Dewooder<T>, F:FnOnce()-> B {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> { self.0.dewoodify(v) }
+}
+
+
 pub const fn biwooder_from_fns<W, D>(wf:W, df:D)-> CompositeBiwooder<LambdaWooder<W>, LambdaDewooder<D>>{
 	CompositeBiwooder(LambdaWooder(wf), LambdaDewooder(df))
 }
 
 
+/// see Biwooder::map
+pub struct MappedBi<SubTran, To, From, A>(pub SubTran, pub To, pub From, PhantomData<fn(A)-> A>);
+impl<A, B, SubTran, To, From> Wooder<B> for MappedBi<SubTran, To, From, A> where SubTran:Wooder<A>, From:Fn(&B)-> A {
+	fn woodify(&self, v:&B) -> Wood { self.0.woodify(&(self.2)(v)) }
+}
+impl<A, B, SubTran, To, From> Dewooder<B> for MappedBi<SubTran, To, From, A> where SubTran:Dewooder<A>, To:Fn(A)-> B {
+	fn dewoodify(&self, v:&Wood) -> Result<B, DewoodifyError> { self.0.dewoodify(v).map(&self.1) }
+}
+
+
 
 
 #[derive(Copy, Clone)]
@@ -138,7 +257,8 @@ impl<'a, T, SubTran> Wooder<Vec<T>> for TaggedSequenceBi<'a, SubTran> where SubT
 	}
 }
 
-fn ensure_tag<'b>(v:&'b Wood, tag:&str) -> Result<std::slice::Iter<'b, Wood>, DewoodifyError> {
+/// Checks that the wood starts with the given tag leaf, and returns an iterator over whatever comes after it
+pub fn ensure_tag<'b>(v:&'b Wood, tag:&str) -> Result<std::slice::Iter<'b, Wood>, DewoodifyError> {
 	let mut i = v.contents();
 	if let Some(name_wood) = i.next() {
 		match *name_wood {
@@ -159,6 +279,16 @@ fn ensure_tag<'b>(v:&'b Wood, tag:&str) -> Result<std::slice::Iter<'b, Wood>, De
 	}
 }
 
+/// for things like `(some v)`, where a tag is followed by exactly one value
+fn single_after_tag<'b>(v:&'b Wood, tag:&str) -> Result<&'b Wood, DewoodifyError> {
+	let mut i = ensure_tag(v, tag)?;
+	match (i.next(), i.len()) {
+		(Some(inner), 0)=> Ok(inner),
+		(None, _)=> Err(DewoodifyError::new(v, format!("expected a value after \"{}\", but there was nothing", tag))),
+		(Some(_), extra)=> Err(DewoodifyError::new(v, format!("expected a single value after \"{}\", but there were {}", tag, extra + 1))),
+	}
+}
+
 impl<'a, T, SubTran> Dewooder<Vec<T>> for TaggedSequenceBi<'a, SubTran> where SubTran:Dewooder<T> {
 	fn dewoodify(&self, v:&Wood) -> Result<Vec<T>, DewoodifyError> {
 		let mut ret = Vec::new();
@@ -168,19 +298,45 @@ impl<'a, T, SubTran> Dewooder<Vec<T>> for TaggedSequenceBi<'a, SubTran> where Su
 	}
 }
 
+/// `tag` followed by the contents of w, or by w itself if it's a leaf
+fn spliced_after_tag(tag:&str, w:Wood)-> Vec<Wood> {
+	match w {
+		Branchv(Branch{v:args, ..})=> {
+			let mut ret = Vec::with_capacity(args.len() + 1);
+			ret.push(tag.into());
+			ret.extend(args.into_iter());
+			ret
+		}
+		leaf=> vec!(tag.into(), leaf),
+	}
+}
+/// the inverse of spliced_after_tag: what comes after a tag, as a branch, or as a leaf if it's a single leaf
+fn args_after_tag(rest:&[Wood])-> Wood {
+	if rest.len() == 1 && rest[0].is_leaf() { rest[0].clone() } else { Wood::branch(rest.to_vec()) }
+}
+
+/// Puts a tag leaf in front of whatever SubTran produces, checking for it when dewoodifying. If SubTran produces a branch, its elements go directly after the tag, so `Tagged("list", SequenceBi(Iden))` is equivalent to `TaggedSequenceBi("list", Iden)`. When dewoodifying, SubTran receives what comes after the tag as a branch, or, if that's a single leaf, the leaf alone.
+#[derive(Copy, Clone)]
+pub struct Tagged<'a, SubTran>(pub &'a str, pub SubTran);
+impl<'a, T, SubTran> Wooder<T> for Tagged<'a, SubTran> where SubTran:Wooder<T> {
+	fn woodify(&self, v:&T) -> Wood {
+		spliced_after_tag(self.0, self.1.woodify(v)).into()
+	}
+}
+impl<'a, T, SubTran> Dewooder<T> for Tagged<'a, SubTran> where SubTran:Dewooder<T> {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let rest = ensure_tag(v, self.0)?;
+		self.1.dewoodify(&args_after_tag(rest.as_slice()))
+	}
+}
+
 
 
-fn dewoodify_pair<K, V, KeyTran, ValTran>(kt:&KeyTran, vt:&ValTran, v:&Wood) -> Result<(K,V), DewoodifyError>
-	where KeyTran:Dewooder<K>, ValTran:Dewooder<V>
-{
+fn check_pair(v:&Wood) -> Result<(), DewoodifyError> {
 	match *v {
 		Branchv(ref lc)=> {
 			if lc.v.len() == 2 {
-				unsafe{
-					let k = kt.dewoodify(lc.v.get_unchecked(0))?; // safe: we just checked the length
-					let v = vt.dewoodify(lc.v.get_unchecked(1))?; //
-					Ok((k, v))
-				}
+				Ok(())
 			}else{
 				Err(DewoodifyError::new_with_cause(v, format!("expected a pair, two elements, but the branch here has {}", lc.v.len()), None))
 			}
@@ -190,6 +346,20 @@ fn dewoodify_pair<K, V, KeyTran, ValTran>(kt:&KeyTran, vt:&ValTran, v:&Wood) ->
 		}
 	}
 }
+fn dewoodify_pair<K, V, KeyTran, ValTran>(kt:&KeyTran, vt:&ValTran, v:&Wood) -> Result<(K,V), DewoodifyError>
+	where KeyTran:Dewooder<K>, ValTran:Dewooder<V>
+{
+	check_pair(v)?;
+	let li = v.contents().as_slice();
+	Ok((kt.dewoodify(&li[0])?, vt.dewoodify(&li[1])?))
+}
+//dewoodify_pair, taking the pair apart rather than borrowing it
+fn dewoodify_pair_owned<K, V>(v:Wood) -> Result<(K,V), DewoodifyError> where K:DewoodableOwned, V:DewoodableOwned {
+	check_pair(&v)?;
+	let mut li = v.into_iter();
+	let k = K::dewoodify_owned(li.next().unwrap())?;
+	Ok((k, V::dewoodify_owned(li.next().unwrap())?))
+}
 
 #[derive(Copy, Clone)]
 pub struct PairBi<KeyTran, ValTran>(KeyTran, ValTran);
@@ -234,7 +404,7 @@ fn dewoodify_map<'a, K, V, KeyTran, ValTran, I>(ktr:&KeyTran, vtr:&ValTran, i:I,
 
 impl<K, V> Woodable for HashMap<K, V> where
 	K: Eq + Hash + Woodable,
-	V: Eq + Hash + Woodable,
+	V: Woodable,
 {
 	fn woodify(&self) -> Wood {
 		let mut ret = Vec::new();
@@ -245,7 +415,7 @@ impl<K, V> Woodable for HashMap<K, V> where
 impl<K, V> Dewoodable for HashMap<K, V>
 	where
 		K: Eq + Hash + Dewoodable,
-		V: Eq + Hash + Dewoodable,
+		V: Dewoodable,
 {
 	fn dewoodify(v:&Wood) -> Result<HashMap<K,V>, DewoodifyError> {
 		let mut ret = Vec::new();
@@ -253,6 +423,11 @@ impl<K, V> Dewoodable for HashMap<K, V>
 		Ok(HashMap::from_iter(ret.into_iter()))
 	}
 }
+impl<K, V> DewoodableOwned for HashMap<K, V> where K: Eq + Hash + DewoodableOwned, V: DewoodableOwned {
+	fn dewoodify_owned(v:Wood) -> Result<HashMap<K,V>, DewoodifyError> {
+		v.into_iter().map(dewoodify_pair_owned).collect()
+	}
+}
 
 #[derive(Clone)]
 pub struct HashMapBi<KeyTran, ValTran>(KeyTran, ValTran);
@@ -260,7 +435,6 @@ impl<K, V, KeyTran, ValTran> Wooder<HashMap<K, V>> for HashMapBi<KeyTran, ValTra
 	where
 		KeyTran:Wooder<K>, ValTran:Wooder<V>,
 		K: Eq + Hash,
-		V: Eq + Hash,
 {
 	fn woodify(&self, v:&HashMap<K, V>) -> Wood {
 		let mut ret = Vec::new();
@@ -272,7 +446,6 @@ impl<K, V, KeyTran, ValTran> Dewooder<HashMap<K, V>> for HashMapBi<KeyTran, ValT
 	where
 		KeyTran:Dewooder<K>, ValTran:Dewooder<V>,
 		K: Eq + Hash,
-		V: Eq + Hash,
 {
 	fn dewoodify(&self, v:&Wood) -> Result<HashMap<K,V>, DewoodifyError> {
 		let mut ret = Vec::new();
@@ -287,7 +460,6 @@ impl<'a, K, V, KeyTran, ValTran> Wooder<HashMap<K, V>> for TaggedHashMapBi<'a, K
 	where
 		KeyTran:Wooder<K>, ValTran:Wooder<V>,
 		K: Eq + Hash,
-		V: Eq + Hash,
 {
 	fn woodify(&self, v:&HashMap<K, V>) -> Wood {
 		let mut ret = Vec::new();
@@ -300,7 +472,6 @@ impl<'a, K, V, KeyTran, ValTran> Dewooder<HashMap<K, V>> for TaggedHashMapBi<'a,
 	where
 		KeyTran:Dewooder<K>, ValTran:Dewooder<V>,
 		K: Eq + Hash,
-		V: Eq + Hash,
 {
 	fn dewoodify(&self, v:&Wood) -> Result<HashMap<K,V>, DewoodifyError> {
 		let mut ret = Vec::new();
@@ -312,70 +483,2225 @@ impl<'a, K, V, KeyTran, ValTran> Dewooder<HashMap<K, V>> for TaggedHashMapBi<'a,
 
 
 
+impl<K, V> Woodable for BTreeMap<K, V> where
+	K: Ord + Woodable,
+	V: Woodable,
+{
+	fn woodify(&self) -> Wood {
+		BTreeMapBi(Iden, Iden).woodify(self)
+	}
+}
+impl<K, V> Dewoodable for BTreeMap<K, V> where
+	K: Ord + Dewoodable,
+	V: Dewoodable,
+{
+	fn dewoodify(v:&Wood) -> Result<BTreeMap<K,V>, DewoodifyError> {
+		BTreeMapBi(Iden, Iden).dewoodify(v)
+	}
+}
+impl<K, V> DewoodableOwned for BTreeMap<K, V> where K: Ord + DewoodableOwned, V: DewoodableOwned {
+	fn dewoodify_owned(v:Wood) -> Result<BTreeMap<K,V>, DewoodifyError> {
+		v.into_iter().map(dewoodify_pair_owned).collect()
+	}
+}
 
-#[cfg(test)]
-mod tests {
-	use super::*;
-	
-	#[test]
-	fn idempotent_int() {
-		assert!(90isize == Iden.dewoodify(&Iden.woodify(&90isize)).unwrap());
+/// Like HashMapBi, but the output is always in key order
+#[derive(Copy, Clone)]
+pub struct BTreeMapBi<KeyTran, ValTran>(pub KeyTran, pub ValTran);
+impl<K, V, KeyTran, ValTran> Wooder<BTreeMap<K, V>> for BTreeMapBi<KeyTran, ValTran>
+	where
+		KeyTran:Wooder<K>, ValTran:Wooder<V>,
+		K: Ord,
+{
+	fn woodify(&self, v:&BTreeMap<K, V>) -> Wood {
+		let mut ret = Vec::new();
+		woodify_map(&self.0, &self.1, v.iter(), &mut ret);
+		ret.into()
 	}
-	
-	#[test]
-	fn tricky_branch_parse() {
-		let brancho = branch!(branch!("tricky", "branch"), branch!("parse"));
-		let tranner:SequenceBi<SequenceBi<Iden>> = SequenceBi(SequenceBi(Iden));
-		let lv:Vec<Vec<String>> = tranner.dewoodify(&brancho).unwrap();
-		assert!(lv.len() == 2);
-		assert!(lv[0].len() == 2);
-		assert!(lv[0][0].len() == 6);
-		assert!(tranner.woodify(&lv).to_string() == "((tricky branch) (parse))");
+}
+impl<K, V, KeyTran, ValTran> Dewooder<BTreeMap<K, V>> for BTreeMapBi<KeyTran, ValTran>
+	where
+		KeyTran:Dewooder<K>, ValTran:Dewooder<V>,
+		K: Ord,
+{
+	fn dewoodify(&self, v:&Wood) -> Result<BTreeMap<K,V>, DewoodifyError> {
+		let mut ret = Vec::new();
+		dewoodify_map(&self.0, &self.1, v.contents(), &mut ret)?;
+		Ok(BTreeMap::from_iter(ret.into_iter()))
 	}
-	
-	#[test]
-	fn do_hash_map() {
-		let t = parse_termpose("a:b c:d d:e e:f").unwrap();
-		let bt = TaggedHashMapBi("ob", Iden, Iden);
-		let utr: Result<HashMap<String, String>, DewoodifyError> = bt.dewoodify(&t);
-		assert!(utr.is_err());
-		let tt = parse_termpose("ob a:b c:d d:e e:f").unwrap();
-		let hm:HashMap<String, String> = TaggedHashMapBi("ob", Iden, Iden).dewoodify(&tt).unwrap();
-		assert!(hm.get("a").unwrap() == "b");
-		assert!(hm.get("d").unwrap() == "e");
+}
+
+
+impl<T> Woodable for BTreeSet<T> where T: Ord + Woodable {
+	fn woodify(&self) -> Wood {
+		BTreeSetBi(Iden).woodify(self)
 	}
-	
-	fn give_hm() -> HashMap<String, String> {
-		[
-			("a".into(), "b".into()),
-			("c".into(), "d".into()),
-		].iter().cloned().collect()
+}
+impl<T> Dewoodable for BTreeSet<T> where T: Ord + Dewoodable {
+	fn dewoodify(v:&Wood) -> Result<BTreeSet<T>, DewoodifyError> {
+		BTreeSetBi(Iden).dewoodify(v)
 	}
-	
-	#[test]
-	fn implicit_biwooders() {
-		let t = parse_termpose("a:b c:d").unwrap();
-		let exh = give_hm();
-		
-		let exu:HashMap<String, String> = dewoodify(&t).unwrap();
-		
-		assert!(exu == exh)
+}
+
+#[derive(Copy, Clone)]
+pub struct BTreeSetBi<SubTran>(pub SubTran);
+impl<T, SubTran> Wooder<BTreeSet<T>> for BTreeSetBi<SubTran> where SubTran:Wooder<T>, T:Ord {
+	fn woodify(&self, v:&BTreeSet<T>) -> Wood {
+		let mut ret = Vec::new();
+		woodify_seq_into(&self.0, v.iter(), &mut ret);
+		ret.into()
 	}
-	
-	const SPECIAL_SEQ_BIWOODER:SequenceBi<Iden> =
-		SequenceBi(Iden);
-	#[test]
-	fn static_biwooder() {
-		let r:Result<Wood, _> = parse_termpose("c c c c c a");
-		let v:Vec<char> = SPECIAL_SEQ_BIWOODER.dewoodify(&r.unwrap()).unwrap();
-		assert_eq!(v.len(), 6usize);
-		assert_eq!(v[5], 'a');
+}
+impl<T, SubTran> Dewooder<BTreeSet<T>> for BTreeSetBi<SubTran> where SubTran:Dewooder<T>, T:Ord {
+	fn dewoodify(&self, v:&Wood) -> Result<BTreeSet<T>, DewoodifyError> {
+		let mut ret = Vec::new();
+		dewoodify_seq_into(&self.0, v.contents(), &mut ret)?;
+		Ok(BTreeSet::from_iter(ret.into_iter()))
+	}
+}
+
+
+impl<T> Woodable for HashSet<T> where T: Eq + Hash + Woodable {
+	fn woodify(&self) -> Wood {
+		SetTran::new(Iden).woodify(self)
+	}
+}
+impl<T> Dewoodable for HashSet<T> where T: Eq + Hash + Dewoodable {
+	fn dewoodify(v:&Wood) -> Result<HashSet<T>, DewoodifyError> {
+		SetTran::new(Iden).dewoodify(v)
+	}
+}
+
+/// Translates HashSets. By default duplicates in the wood are silently merged, `SetTran::rejecting_duplicates` makes them an error instead.
+#[derive(Copy, Clone)]
+pub struct SetTran<SubTran>{
+	pub sub:SubTran,
+	pub reject_duplicates:bool,
+}
+impl<SubTran> SetTran<SubTran> {
+	pub const fn new(sub:SubTran)-> Self { SetTran{ sub, reject_duplicates:false } }
+	pub const fn rejecting_duplicates(sub:SubTran)-> Self { SetTran{ sub, reject_duplicates:true } }
+}
+impl<T, SubTran> Wooder<HashSet<T>> for SetTran<SubTran> where SubTran:Wooder<T>, T: Eq + Hash {
+	fn woodify(&self, v:&HashSet<T>) -> Wood {
+		let mut ret = Vec::new();
+		woodify_seq_into(&self.sub, v.iter(), &mut ret);
+		ret.into()
+	}
+}
+impl<T, SubTran> Dewooder<HashSet<T>> for SetTran<SubTran> where SubTran:Dewooder<T>, T: Eq + Hash {
+	fn dewoodify(&self, v:&Wood) -> Result<HashSet<T>, DewoodifyError> {
+		let mut ret = HashSet::new();
+		for vi in v.contents() {
+			if !ret.insert(self.sub.dewoodify(vi)?) && self.reject_duplicates {
+				return Err(DewoodifyError::new(vi, format!("duplicate element \"{}\" in set", vi.to_string())));
+			}
+		}
+		Ok(ret)
+	}
+}
+
+
+fn format_seconds(secs:u64, nanos:u32)-> String {
+	if nanos == 0 {
+		secs.to_string()
+	}else{
+		let fraction = format!("{:09}", nanos);
+		format!("{}.{}", secs, fraction.trim_end_matches('0'))
+	}
+}
+/// parses things like `5` or `1.25` without going through floats, so that no precision is lost
+fn parse_seconds(s:&str)-> Option<(u64, u32)> {
+	let (whole, fraction) = match s.find('.') {
+		Some(i)=> (&s[..i], &s[i+1..]),
+		None=> (s, "0"),
+	};
+	if whole.is_empty() || fraction.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) || fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+		return None;
+	}
+	let secs = u64::from_str(whole).ok()?;
+	let mut nanos = 0u32;
+	for (i, b) in fraction.bytes().enumerate() {
+		nanos += (b - b'0') as u32 * 10u32.pow(8 - i as u32);
+	}
+	Some((secs, nanos))
+}
+
+/// Whole seconds become a plain leaf, otherwise `secs:… nanos:…`. Dewoodifies from either of those, or from fractional seconds like `1.5`
+impl Woodable for Duration {
+	fn woodify(&self) -> Wood {
+		if self.subsec_nanos() == 0 {
+			self.as_secs().woodify()
+		}else{
+			branch!(branch!("secs", self.as_secs().woodify()), branch!("nanos", self.subsec_nanos().woodify()))
+		}
+	}
+}
+impl Dewoodable for Duration {
+	fn dewoodify(v:&Wood) -> Result<Duration, DewoodifyError> {
+		match *v {
+			Leafv(ref l)=> {
+				parse_seconds(l.v.as_str()).map(|(s, n)| Duration::new(s, n)).ok_or_else(||{
+					DewoodifyError::new(v, format!("expected a number of seconds, but there was \"{}\"", l.v))
+				})
+			}
+			Branchv(_)=> {
+				let mut scanning = FieldScanning::new_headless(v);
+				let secs = u64::dewoodify(scanning.seek("secs")?)?;
+				let nanos_wood = scanning.seek("nanos")?;
+				let nanos = u32::dewoodify(nanos_wood)?;
+				if nanos >= 1_000_000_000 {
+					return Err(DewoodifyError::new(nanos_wood, format!("nanos must be less than 1000000000, but it was {}", nanos)));
+				}
+				Ok(Duration::new(secs, nanos))
+			}
+		}
+	}
+}
+
+/// Seconds since the unix epoch, as a leaf, eg `1700000000.5`. Times before the epoch are negative.
+impl Woodable for SystemTime {
+	fn woodify(&self) -> Wood {
+		match self.duration_since(UNIX_EPOCH) {
+			Ok(d)=> format_seconds(d.as_secs(), d.subsec_nanos()).into(),
+			Err(e)=> {
+				let d = e.duration();
+				format!("-{}", format_seconds(d.as_secs(), d.subsec_nanos())).into()
+			}
+		}
+	}
+}
+impl Dewoodable for SystemTime {
+	fn dewoodify(v:&Wood) -> Result<SystemTime, DewoodifyError> {
+		let s = v.initial_str();
+		let (negative, digits) = if s.starts_with('-') { (true, &s[1..]) } else { (false, s) };
+		let fail = || DewoodifyError::new(v, format!("expected seconds since the unix epoch, but there was \"{}\"", s));
+		let (secs, nanos) = parse_seconds(digits).ok_or_else(fail)?;
+		let d = Duration::new(secs, nanos);
+		if negative { UNIX_EPOCH.checked_sub(d) } else { UNIX_EPOCH.checked_add(d) }.ok_or_else(fail)
+	}
+}
+
+
+impl<T> Woodable for Range<T> where T:Woodable {
+	fn woodify(&self) -> Wood { RangeTran(Iden).woodify(self) }
+}
+impl<T> Dewoodable for Range<T> where T:Dewoodable {
+	fn dewoodify(v:&Wood) -> Result<Range<T>, DewoodifyError> { RangeTran(Iden).dewoodify(v) }
+}
+impl<T> Woodable for RangeInclusive<T> where T:Woodable {
+	fn woodify(&self) -> Wood { RangeTran(Iden).woodify(self) }
+}
+impl<T> Dewoodable for RangeInclusive<T> where T:Dewoodable {
+	fn dewoodify(v:&Wood) -> Result<RangeInclusive<T>, DewoodifyError> { RangeTran(Iden).dewoodify(v) }
+}
+
+/// splits a leaf like `3..5` into two leaves that have the original's position
+fn split_range_leaf(v:&Wood, separator:&str)-> Option<(Wood, Wood)> {
+	if let Leafv(ref l) = *v {
+		if let Some(i) = l.v.find(separator) {
+			let after = i + separator.len();
+			//neither half is the whole leaf's extent
+			let meta = |comments:Vec<Comment>|{
+				let provenance = l.meta().provenance.clone();
+				if provenance.is_none() && comments.is_empty() { None }else{ Some(Box::new(NodeMeta{ provenance, comments, ..NodeMeta::default() })) }
+			};
+			return Some((
+				Leafv(Leaf{ line:l.line, column:l.column, meta:meta(l.meta().comments.clone()), v:l.v[..i].to_string() }),
+				Leafv(Leaf{ line:l.line, column:l.column + after as isize, meta:meta(Vec::new()), v:l.v[after..].to_string() }),
+			));
+		}
+	}
+	None
+}
+
+fn dewoodify_range_ends<T, SubTran>(sub:&SubTran, v:&Wood, separator:&str)-> Result<(T, T), DewoodifyError> where SubTran:Dewooder<T> {
+	if let Some((start, end)) = split_range_leaf(v, separator) {
+		Ok((sub.dewoodify(&start)?, sub.dewoodify(&end)?))
+	}else if v.is_branch() {
+		dewoodify_pair(sub, sub, v)
+	}else{
+		Err(DewoodifyError::new(v, format!("expected a range, either as a pair or as a leaf like start{}end", separator)))
+	}
+}
+
+/// Ranges woodify as a two element branch `(start end)`. Leaves like `start..end` (or `start..=end` for RangeInclusive) are also accepted when dewoodifying.
+#[derive(Copy, Clone)]
+pub struct RangeTran<SubTran>(pub SubTran);
+impl<T, SubTran> Wooder<Range<T>> for RangeTran<SubTran> where SubTran:Wooder<T> {
+	fn woodify(&self, v:&Range<T>) -> Wood {
+		branch!(self.0.woodify(&v.start), self.0.woodify(&v.end))
+	}
+}
+impl<T, SubTran> Dewooder<Range<T>> for RangeTran<SubTran> where SubTran:Dewooder<T> {
+	fn dewoodify(&self, v:&Wood) -> Result<Range<T>, DewoodifyError> {
+		let (start, end) = dewoodify_range_ends(&self.0, v, "..")?;
+		Ok(start..end)
+	}
+}
+impl<T, SubTran> Wooder<RangeInclusive<T>> for RangeTran<SubTran> where SubTran:Wooder<T> {
+	fn woodify(&self, v:&RangeInclusive<T>) -> Wood {
+		branch!(self.0.woodify(v.start()), self.0.woodify(v.end()))
+	}
+}
+impl<T, SubTran> Dewooder<RangeInclusive<T>> for RangeTran<SubTran> where SubTran:Dewooder<T> {
+	fn dewoodify(&self, v:&Wood) -> Result<RangeInclusive<T>, DewoodifyError> {
+		let (start, end) = dewoodify_range_ends(&self.0, v, "..=")?;
+		Ok(start..=end)
+	}
+}
+
+
+fn push_hex_byte(out:&mut String, b:u8){
+	const DIGITS:&[u8] = b"0123456789abcdef";
+	out.push(DIGITS[(b >> 4) as usize] as char);
+	out.push(DIGITS[(b & 15) as usize] as char);
+}
+fn hex_digit_value(c:u8)-> Option<u8> {
+	match c {
+		b'0'..=b'9'=> Some(c - b'0'),
+		b'a'..=b'f'=> Some(c - b'a' + 10),
+		b'A'..=b'F'=> Some(c - b'A' + 10),
+		_=> None,
+	}
+}
+fn decode_hex(v:&str)-> Option<Vec<u8>> {
+	let bytes = v.as_bytes();
+	if bytes.len() % 2 != 0 { return None; }
+	bytes.chunks(2).map(|p| Some((hex_digit_value(p[0])? << 4) | hex_digit_value(p[1])?)).collect()
+}
+
+/// Translates OsStrings. Anything that's valid unicode becomes an ordinary leaf. Anything that isn't is, in strict mode, encoded losslessly: on unix as `(bytes <hex>)`, on windows as `(wide <hex of each u16>)`. The byte representation of one platform can't be decoded on the other, so in lossy mode invalid sequences are just replaced with U+FFFD, which at least produces the same output everywhere.
+#[derive(Copy, Clone)]
+pub struct OsStrTran{ pub lossy:bool }
+pub const OS_STR_STRICT:OsStrTran = OsStrTran{ lossy:false };
+pub const OS_STR_LOSSY:OsStrTran = OsStrTran{ lossy:true };
+
+impl OsStrTran {
+	pub fn woodify_os_str(&self, v:&OsStr)-> Wood {
+		if let Some(s) = v.to_str() {
+			return s.into();
+		}
+		if !self.lossy {
+			#[cfg(unix)]
+			{
+				use std::os::unix::ffi::OsStrExt;
+				let mut hex = String::new();
+				for b in v.as_bytes() { push_hex_byte(&mut hex, *b); }
+				return branch!("bytes", hex);
+			}
+			#[cfg(windows)]
+			{
+				use std::os::windows::ffi::OsStrExt;
+				let mut hex = String::new();
+				for w in v.encode_wide() { push_hex_byte(&mut hex, (w >> 8) as u8); push_hex_byte(&mut hex, w as u8); }
+				return branch!("wide", hex);
+			}
+		}
+		v.to_string_lossy().woodify()
 	}
 	
-	#[test]
-	fn automatic_deserialize() {
-		let hm:HashMap<String, String> = deserialize("a:b c:d").unwrap();
-		assert!(hm == give_hm());
+	fn encoded_units(&self, v:&Wood, tag:&str)-> Result<Vec<u8>, DewoodifyError> {
+		let hw = single_after_tag(v, tag)?;
+		decode_hex(hw.initial_str()).ok_or_else(|| DewoodifyError::new(hw, format!("expected hexadecimal after \"{}\"", tag)))
+	}
+}
+impl Wooder<OsString> for OsStrTran {
+	fn woodify(&self, v:&OsString) -> Wood { self.woodify_os_str(v.as_os_str()) }
+}
+impl Dewooder<OsString> for OsStrTran {
+	fn dewoodify(&self, v:&Wood) -> Result<OsString, DewoodifyError> {
+		match *v {
+			Leafv(ref l)=> Ok(OsString::from(l.v.clone())),
+			Branchv(_)=> {
+				match v.initial_str() {
+					"bytes"=> {
+						let bytes = self.encoded_units(v, "bytes")?;
+						#[cfg(unix)]
+						{
+							use std::os::unix::ffi::OsStringExt;
+							return Ok(OsString::from_vec(bytes));
+						}
+						#[allow(unreachable_code)]
+						{
+							if self.lossy {
+								Ok(OsString::from(String::from_utf8_lossy(&bytes).into_owned()))
+							}else{
+								Err(DewoodifyError::new(v, "unix bytes can't be represented in an OsString on this platform".into()))
+							}
+						}
+					}
+					"wide"=> {
+						let bytes = self.encoded_units(v, "wide")?;
+						if bytes.len() % 2 != 0 {
+							return Err(DewoodifyError::new(v, "wide string has an odd number of bytes".into()));
+						}
+						let wide:Vec<u16> = bytes.chunks(2).map(|p| ((p[0] as u16) << 8) | p[1] as u16).collect();
+						#[cfg(windows)]
+						{
+							use std::os::windows::ffi::OsStringExt;
+							return Ok(OsString::from_wide(&wide));
+						}
+						#[allow(unreachable_code)]
+						{
+							if self.lossy {
+								Ok(OsString::from(String::from_utf16_lossy(&wide)))
+							}else{
+								Err(DewoodifyError::new(v, "windows wide strings can't be represented in an OsString on this platform".into()))
+							}
+						}
+					}
+					other=> Err(DewoodifyError::new(v, format!("expected a string, or (bytes …) or (wide …), but there was \"{}\"", other))),
+				}
+			}
+		}
+	}
+}
+
+impl Woodable for OsStr {
+	fn woodify(&self) -> Wood { OS_STR_STRICT.woodify_os_str(self) }
+}
+impl Woodable for OsString {
+	fn woodify(&self) -> Wood { OS_STR_STRICT.woodify(self) }
+}
+impl Dewoodable for OsString {
+	fn dewoodify(v:&Wood) -> Result<OsString, DewoodifyError> { OS_STR_STRICT.dewoodify(v) }
+}
+
+
+/// Translates binary data as a single leaf of lowercase hexadecimal. Uppercase is also accepted when dewoodifying.
+#[derive(Copy, Clone)]
+pub struct HexBytesBi;
+impl Wooder<[u8]> for HexBytesBi {
+	fn woodify(&self, v:&[u8]) -> Wood {
+		let mut ret = String::with_capacity(v.len()*2);
+		for b in v { push_hex_byte(&mut ret, *b); }
+		ret.into()
+	}
+}
+impl Wooder<Vec<u8>> for HexBytesBi {
+	fn woodify(&self, v:&Vec<u8>) -> Wood { Wooder::<[u8]>::woodify(self, v.as_slice()) }
+}
+impl Dewooder<Vec<u8>> for HexBytesBi {
+	fn dewoodify(&self, v:&Wood) -> Result<Vec<u8>, DewoodifyError> {
+		let s = <&str>::dewoodify_ref(v)?;
+		decode_hex(s).ok_or_else(|| DewoodifyError::new(v, "expected an even number of hexadecimal digits".into()))
+	}
+}
+
+
+const BASE64_ALPHABET:&[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+fn base64_digit_value(c:u8)-> Option<u32> {
+	match c {
+		b'A'..=b'Z'=> Some((c - b'A') as u32),
+		b'a'..=b'z'=> Some((c - b'a') as u32 + 26),
+		b'0'..=b'9'=> Some((c - b'0') as u32 + 52),
+		b'+'=> Some(62),
+		b'/'=> Some(63),
+		_=> None,
+	}
+}
+
+/// Translates binary data as a single leaf of standard, padded base64. Padding is optional when dewoodifying.
+#[derive(Copy, Clone)]
+pub struct Base64BytesBi;
+impl Wooder<[u8]> for Base64BytesBi {
+	fn woodify(&self, v:&[u8]) -> Wood {
+		let mut ret = String::with_capacity((v.len() + 2)/3*4);
+		for chunk in v.chunks(3) {
+			let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8*i));
+			for i in 0..4 {
+				if i <= chunk.len() {
+					ret.push(BASE64_ALPHABET[(n >> (18 - 6*i) & 63) as usize] as char);
+				}else{
+					ret.push('=');
+				}
+			}
+		}
+		ret.into()
+	}
+}
+impl Wooder<Vec<u8>> for Base64BytesBi {
+	fn woodify(&self, v:&Vec<u8>) -> Wood { Wooder::<[u8]>::woodify(self, v.as_slice()) }
+}
+impl Dewooder<Vec<u8>> for Base64BytesBi {
+	fn dewoodify(&self, v:&Wood) -> Result<Vec<u8>, DewoodifyError> {
+		let s = <&str>::dewoodify_ref(v)?.trim_end_matches('=').as_bytes();
+		if s.len() % 4 == 1 {
+			return Err(DewoodifyError::new(v, "base64 has an impossible length".into()));
+		}
+		let mut ret = Vec::with_capacity(s.len()*3/4);
+		for chunk in s.chunks(4) {
+			let mut n = 0u32;
+			for (i, c) in chunk.iter().enumerate() {
+				let d = base64_digit_value(*c).ok_or_else(|| DewoodifyError::new(v, format!("'{}' is not a base64 character", *c as char)))?;
+				n |= d << (18 - 6*i);
+			}
+			for i in 0..chunk.len() - 1 {
+				ret.push((n >> (16 - 8*i)) as u8);
+			}
+		}
+		Ok(ret)
+	}
+}
+
+
+impl<T> Woodable for Option<T> where T:Woodable {
+	fn woodify(&self) -> Wood {
+		OptionTran(Iden).woodify(self)
+	}
+}
+impl<T> Dewoodable for Option<T> where T:Dewoodable {
+	fn dewoodify(v:&Wood) -> Result<Option<T>, DewoodifyError> {
+		OptionTran(Iden).dewoodify(v)
+	}
+}
+impl<T> DewoodableOwned for Option<T> where T:DewoodableOwned {
+	fn dewoodify_owned(v:Wood) -> Result<Option<T>, DewoodifyError> {
+		if v.is_leaf() {
+			//there's nothing to move out of a leaf, so it's checked just as dewoodify would
+			return OptionTran(Iden).dewoodify(&v).map(|_:Option<()>| None);
+		}
+		single_after_tag(&v, "some")?;
+		Ok(Some(T::dewoodify_owned(v.into_iter().nth(1).unwrap())?))
+	}
+}
+
+/// None becomes the leaf `none`, Some(v) becomes `(some v)`
+#[derive(Copy, Clone)]
+pub struct OptionTran<SubTran>(pub SubTran);
+impl<T, SubTran> Wooder<Option<T>> for OptionTran<SubTran> where SubTran:Wooder<T> {
+	fn woodify(&self, v:&Option<T>) -> Wood {
+		match *v {
+			Some(ref vi)=> branch!("some", self.0.woodify(vi)),
+			None=> "none".into(),
+		}
+	}
+}
+impl<T, SubTran> Dewooder<Option<T>> for OptionTran<SubTran> where SubTran:Dewooder<T> {
+	fn dewoodify(&self, v:&Wood) -> Result<Option<T>, DewoodifyError> {
+		match *v {
+			Leafv(ref l)=> {
+				if l.v == "none" {
+					Ok(None)
+				}else{
+					Err(DewoodifyError::new(v, format!("expected \"none\" or (some ...), but instead there was \"{}\"", l.v)))
+				}
+			}
+			Branchv(_)=> {
+				Ok(Some(self.0.dewoodify(single_after_tag(v, "some")?)?))
+			}
+		}
+	}
+}
+
+
+impl<T, E> Woodable for Result<T, E> where T:Woodable, E:Woodable {
+	fn woodify(&self) -> Wood {
+		ResultTran(Iden, Iden).woodify(self)
+	}
+}
+impl<T, E> Dewoodable for Result<T, E> where T:Dewoodable, E:Dewoodable {
+	fn dewoodify(v:&Wood) -> Result<Result<T, E>, DewoodifyError> {
+		ResultTran(Iden, Iden).dewoodify(v)
+	}
+}
+
+/// Ok(v) becomes `(ok v)`, Err(e) becomes `(err e)`
+#[derive(Copy, Clone)]
+pub struct ResultTran<OkTran, ErrTran>(pub OkTran, pub ErrTran);
+impl<T, E, OkTran, ErrTran> Wooder<Result<T, E>> for ResultTran<OkTran, ErrTran> where OkTran:Wooder<T>, ErrTran:Wooder<E> {
+	fn woodify(&self, v:&Result<T, E>) -> Wood {
+		match *v {
+			Ok(ref vi)=> branch!("ok", self.0.woodify(vi)),
+			Err(ref ei)=> branch!("err", self.1.woodify(ei)),
+		}
+	}
+}
+impl<T, E, OkTran, ErrTran> Dewooder<Result<T, E>> for ResultTran<OkTran, ErrTran> where OkTran:Dewooder<T>, ErrTran:Dewooder<E> {
+	fn dewoodify(&self, v:&Wood) -> Result<Result<T, E>, DewoodifyError> {
+		match v.initial_str() {
+			"ok"=> Ok(Ok(self.0.dewoodify(single_after_tag(v, "ok")?)?)),
+			"err"=> Ok(Err(self.1.dewoodify(single_after_tag(v, "err")?)?)),
+			other=> Err(DewoodifyError::new(v, format!("expected \"ok\" or \"err\" here, but instead there was \"{}\"", other))),
+		}
+	}
+}
+
+
+
+
+/// one field of a StructBi
+trait StructField<T> {
+	fn woodify_into(&self, v:&T, out:&mut Vec<Wood>);
+	fn dewoodify_into(&self, v:&mut T, scanning:&mut FieldScanning) -> Result<(), DewoodifyError>;
+}
+
+struct FieldEntry<'a, F, Tran, G, S> {
+	key:&'a str,
+	tran:Tran,
+	get:G,
+	set:S,
+	default:Option<F>,
+}
+impl<'a, T, F, Tran, G, S> StructField<T> for FieldEntry<'a, F, Tran, G, S>
+	where Tran:Biwooder<F>, G:Fn(&T)-> &F, S:Fn(&mut T, F), F:Clone
+{
+	fn woodify_into(&self, v:&T, out:&mut Vec<Wood>){
+		out.push(branch!(self.key, self.tran.woodify((self.get)(v))));
+	}
+	fn dewoodify_into(&self, v:&mut T, scanning:&mut FieldScanning) -> Result<(), DewoodifyError> {
+		let fv = match scanning.seek_opt(self.key)? {
+			Some(fw)=> {
+				self.tran.dewoodify(fw).map_err(|e|{
+					DewoodifyError::new_with_cause(fw, format!("in field \"{}\": {}", self.key, e.msg), Some(Box::new(e)))
+				})?
+			}
+			None=> {
+				match self.default {
+					Some(ref d)=> d.clone(),
+					None=> return Err(DewoodifyError::new(scanning.v, format!("could not find key \"{}\"", self.key))),
+				}
+			}
+		};
+		(self.set)(v, fv);
+		Ok(())
+	}
+}
+
+/// Declares how a struct with named fields should be translated, for when you'd rather not write a Woodable/Dewoodable impl, or need a different translation than the derived one.
+/// ```
+/// # use wood::wooder::{StructBi, Iden};
+/// #[derive(Default)]
+/// struct Server { name:String, port:u16 }
+/// let bi = StructBi::new("server")
+/// 	.field("name", Iden, |s:&Server| &s.name, |s, v| s.name = v)
+/// 	.optional_field("port", Iden, 8080, |s:&Server| &s.port, |s, v| s.port = v);
+/// ```
+/// woodifies to `server name:… port:…`. Dewoodification starts from `T::default()` and then sets each field. Fields can be in any order, an optional field that's missing is set to the given default.
+pub struct StructBi<'a, T> {
+	tag:&'a str,
+	fields:Vec<Box<dyn StructField<T> + 'a>>,
+}
+impl<'a, T> StructBi<'a, T> {
+	pub fn new(tag:&'a str)-> Self { StructBi{ tag, fields:Vec::new() } }
+	pub fn field<F, Tran, G, S>(mut self, key:&'a str, tran:Tran, get:G, set:S)-> Self
+		where Tran:Biwooder<F> + 'a, G:Fn(&T)-> &F + 'a, S:Fn(&mut T, F) + 'a, F:Clone + 'a
+	{
+		self.fields.push(Box::new(FieldEntry{ key, tran, get, set, default:None }));
+		self
+	}
+	pub fn optional_field<F, Tran, G, S>(mut self, key:&'a str, tran:Tran, default:F, get:G, set:S)-> Self
+		where Tran:Biwooder<F> + 'a, G:Fn(&T)-> &F + 'a, S:Fn(&mut T, F) + 'a, F:Clone + 'a
+	{
+		self.fields.push(Box::new(FieldEntry{ key, tran, get, set, default:Some(default) }));
+		self
+	}
+	/// the fields of the nested value are written directly into this struct's branch, rather than under a key of their own, and are scanned back out of it the same way
+	pub fn flatten<F, Inner, G, S>(mut self, inner:Inner, get:G, set:S)-> Self
+		where Inner:FieldsBi<F> + 'a, G:Fn(&T)-> &F + 'a, S:Fn(&mut T, F) + 'a, F:'a
+	{
+		self.fields.push(Box::new(FlattenEntry{ inner, get, set, _f:PhantomData }));
+		self
+	}
+}
+/// For translators of things that can be expressed as a list of `key value` fields, which can then be flattened into a parent struct. See StructBi::flatten
+pub trait FieldsBi<T> {
+	fn woodify_fields(&self, v:&T, output:&mut Vec<Wood>);
+	fn dewoodify_fields(&self, scanning:&mut FieldScanning) -> Result<T, DewoodifyError>;
+}
+impl<'a, T> FieldsBi<T> for StructBi<'a, T> where T:Default {
+	fn woodify_fields(&self, v:&T, output:&mut Vec<Wood>){
+		for f in self.fields.iter() { f.woodify_into(v, output); }
+	}
+	fn dewoodify_fields(&self, scanning:&mut FieldScanning) -> Result<T, DewoodifyError> {
+		let mut ret = T::default();
+		for f in self.fields.iter() { f.dewoodify_into(&mut ret, scanning)?; }
+		Ok(ret)
+	}
+}
+struct FlattenEntry<F, Inner, G, S> {
+	inner:Inner,
+	get:G,
+	set:S,
+	_f:PhantomData<fn(F)-> F>,
+}
+impl<T, F, Inner, G, S> StructField<T> for FlattenEntry<F, Inner, G, S>
+	where Inner:FieldsBi<F>, G:Fn(&T)-> &F, S:Fn(&mut T, F)
+{
+	fn woodify_into(&self, v:&T, out:&mut Vec<Wood>){
+		self.inner.woodify_fields((self.get)(v), out);
+	}
+	fn dewoodify_into(&self, v:&mut T, scanning:&mut FieldScanning) -> Result<(), DewoodifyError> {
+		(self.set)(v, self.inner.dewoodify_fields(scanning)?);
+		Ok(())
+	}
+}
+impl<'a, T> Wooder<T> for StructBi<'a, T> {
+	fn woodify(&self, v:&T) -> Wood {
+		let mut ret = vec!(self.tag.into());
+		for f in self.fields.iter() { f.woodify_into(v, &mut ret); }
+		ret.into()
+	}
+}
+impl<'a, T> StructBi<'a, T> where T:Default {
+	/// like dewoodify, but rather than stopping at the first field that fails, tries every field and returns all of the errors
+	pub fn dewoodify_all(&self, v:&Wood) -> Result<T, Vec<DewoodifyError>> {
+		let _ = ensure_tag(v, self.tag).map_err(|e| vec!(e))?;
+		let mut scanning = FieldScanning::new(v);
+		let mut ret = T::default();
+		let mut errors = Vec::new();
+		for f in self.fields.iter() {
+			if let Err(e) = f.dewoodify_into(&mut ret, &mut scanning) { errors.push(e); }
+		}
+		if errors.len() == 0 { Ok(ret) } else { Err(errors) }
+	}
+}
+impl<'a, T> Dewooder<T> for StructBi<'a, T> where T:Default {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let _ = ensure_tag(v, self.tag)?;
+		self.dewoodify_fields(&mut FieldScanning::new(v))
+	}
+}
+
+
+
+/// one variant of an EnumBi
+trait EnumVariant<T> {
+	fn tag(&self)-> &str;
+	fn woodify_if_matching(&self, v:&T)-> Option<Wood>;
+	fn dewoodify_args(&self, args:&Wood)-> Result<T, DewoodifyError>;
+}
+
+struct VariantEntry<'a, Tran, G, M> {
+	tag:&'a str,
+	tran:Tran,
+	get:G,
+	make:M,
+}
+impl<'a, T, F, Tran, G, M> EnumVariant<T> for VariantEntry<'a, Tran, G, M>
+	where Tran:Biwooder<F>, G:Fn(&T)-> Option<F>, M:Fn(F)-> T
+{
+	fn tag(&self)-> &str { self.tag }
+	fn woodify_if_matching(&self, v:&T)-> Option<Wood> {
+		(self.get)(v).map(|f|{
+			let ret = spliced_after_tag(self.tag, self.tran.woodify(&f));
+			if ret.len() == 1 { self.tag.into() } else { ret.into() }
+		})
+	}
+	fn dewoodify_args(&self, args:&Wood)-> Result<T, DewoodifyError> {
+		self.tran.dewoodify(args).map(&self.make)
+	}
+}
+
+/// Translates enums as `variantname args…`, dispatching on the initial leaf. Each variant is registered with its tag, a translator for its payload, a closure that returns the payload if the value is that variant, and a closure that constructs the variant from a payload.
+/// ```
+/// # use wood::wooder::{EnumBi, Iden};
+/// enum Shape { Circle(f64), Rect(f64, f64), Empty }
+/// let bi = EnumBi::new()
+/// 	.variant("circle", Iden, |s:&Shape| match *s { Shape::Circle(r)=> Some(r), _=> None }, Shape::Circle)
+/// 	.variant("rect", Iden, |s:&Shape| match *s { Shape::Rect(w, h)=> Some((w, h)), _=> None }, |(w, h)| Shape::Rect(w, h))
+/// 	.variant("empty", Iden, |s:&Shape| match *s { Shape::Empty=> Some(()), _=> None }, |()| Shape::Empty);
+/// ```
+/// woodifies to `circle 2`, `rect 2 3` and `empty`. A payload that woodifies to a branch has its elements spliced in after the tag, so when dewoodifying, the payload translator receives the arguments as a branch, or, if there's exactly one argument and it's a leaf, that leaf alone.
+pub struct EnumBi<'a, T> {
+	variants:Vec<Box<dyn EnumVariant<T> + 'a>>,
+}
+impl<'a, T> EnumBi<'a, T> {
+	pub fn new()-> Self { EnumBi{ variants:Vec::new() } }
+	pub fn variant<F, Tran, G, M>(mut self, tag:&'a str, tran:Tran, get:G, make:M)-> Self
+		where Tran:Biwooder<F> + 'a, G:Fn(&T)-> Option<F> + 'a, M:Fn(F)-> T + 'a
+	{
+		self.variants.push(Box::new(VariantEntry{ tag, tran, get, make }));
+		self
+	}
+}
+impl<'a, T> Wooder<T> for EnumBi<'a, T> {
+	fn woodify(&self, v:&T) -> Wood {
+		for var in self.variants.iter() {
+			if let Some(w) = var.woodify_if_matching(v) { return w; }
+		}
+		panic!("EnumBi: none of the registered variants matched the value")
+	}
+}
+impl<'a, T> Dewooder<T> for EnumBi<'a, T> {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let (tag_wood, args) = match *v {
+			Leafv(_)=> (v, Wood::branch(Vec::new())),
+			Branchv(ref b)=> {
+				match b.v.first() {
+					Some(f)=> (f, args_after_tag(&b.v[1..])),
+					None=> return Err(DewoodifyError::new(v, "expected a variant, but the branch was empty".into())),
+				}
+			}
+		};
+		let tag = match *tag_wood {
+			Leafv(ref l)=> l.v.as_str(),
+			Branchv(_)=> return Err(DewoodifyError::new(tag_wood, "expected a variant name here, but found a branch".into())),
+		};
+		match self.variants.iter().find(|var| var.tag() == tag) {
+			Some(var)=> var.dewoodify_args(&args).map_err(|e|{
+				DewoodifyError::new_with_cause(v, format!("in variant \"{}\": {}", tag, e.msg), Some(Box::new(e)))
+			}),
+			None=> {
+				let expected:Vec<&str> = self.variants.iter().map(|var| var.tag()).collect();
+				Err(DewoodifyError::new(tag_wood, format!("\"{}\" isn't a known variant, expected one of: {}", tag, expected.join(", "))))
+			}
+		}
+	}
+}
+
+
+
+/// Falls back to `T::default()` when the wood is empty (an empty branch or an empty leaf) or missing entirely, otherwise defers to SubTran.
+pub struct DefaultFill<SubTran>(pub SubTran);
+impl<SubTran> DefaultFill<SubTran> {
+	/// for values that might not be there at all, EG, the result of `FieldScanning::seek_opt`
+	pub fn dewoodify_opt<T>(&self, v:Option<&Wood>) -> Result<T, DewoodifyError> where SubTran:Dewooder<T>, T:Default {
+		match v {
+			Some(w)=> self.dewoodify(w),
+			None=> Ok(T::default()),
+		}
+	}
+}
+impl<T, SubTran> Wooder<T> for DefaultFill<SubTran> where SubTran:Wooder<T> {
+	fn woodify(&self, v:&T) -> Wood { self.0.woodify(v) }
+}
+impl<T, SubTran> Dewooder<T> for DefaultFill<SubTran> where SubTran:Dewooder<T>, T:Default {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let empty = match *v {
+			Leafv(ref l)=> l.v.is_empty(),
+			Branchv(ref b)=> b.v.is_empty(),
+		};
+		if empty { Ok(T::default()) } else { self.0.dewoodify(v) }
+	}
+}
+
+
+
+/// Runs a check after SubTran has dewoodified the value. An Err from the check becomes a DewoodifyError anchored at the wood the value came from, EG `Validated(Iden, |p:&u32| if *p < 65536 { Ok(()) }else{ Err("port must be < 65536".into()) })`
+pub struct Validated<SubTran, F>(pub SubTran, pub F);
+impl<T, SubTran, F> Wooder<T> for Validated<SubTran, F> where SubTran:Wooder<T> {
+	fn woodify(&self, v:&T) -> Wood { self.0.woodify(v) }
+}
+impl<T, SubTran, F> Dewooder<T> for Validated<SubTran, F> where SubTran:Dewooder<T>, F:Fn(&T)-> Result<(), String> {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let ret = self.0.dewoodify(v)?;
+		match (self.1)(&ret) {
+			Ok(())=> Ok(ret),
+			Err(msg)=> Err(DewoodifyError::new(v, msg)),
+		}
+	}
+}
+
+
+
+/// Tries each Dewooder in order and returns the first success. If none succeed, the error lists each alternative's complaint.
+pub struct AnyOf<'a, T>(pub Vec<Box<dyn Dewooder<T> + 'a>>);
+impl<'a, T> AnyOf<'a, T> {
+	pub fn new()-> Self { AnyOf(Vec::new()) }
+	pub fn or<D>(mut self, d:D)-> Self where D:Dewooder<T> + 'a {
+		self.0.push(Box::new(d));
+		self
+	}
+}
+impl<'a, T> Dewooder<T> for AnyOf<'a, T> {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let mut msgs = String::new();
+		for (i, d) in self.0.iter().enumerate() {
+			match d.dewoodify(v) {
+				Ok(r)=> return Ok(r),
+				Err(e)=> {
+					msgs.push_str(&format!("\n  {}) {}", i + 1, e.msg));
+				}
+			}
+		}
+		Err(DewoodifyError::new(v, format!("none of the {} alternatives could dewoodify this:{}", self.0.len(), msgs)))
+	}
+}
+
+
+
+/// Like SequenceBi, but insists on there being between `min` and `max` elements (inclusive). Use `usize::max_value()` for no upper bound.
+#[derive(Copy, Clone)]
+pub struct BoundedSeqBi<SubTran> {
+	pub sub:SubTran,
+	pub min:usize,
+	pub max:usize,
+}
+impl<SubTran> BoundedSeqBi<SubTran> {
+	pub fn new(sub:SubTran, min:usize, max:usize)-> Self { BoundedSeqBi{ sub, min, max } }
+}
+impl<T, SubTran> Wooder<Vec<T>> for BoundedSeqBi<SubTran> where SubTran:Wooder<T> {
+	fn woodify(&self, v:&Vec<T>) -> Wood {
+		debug_assert!(self.min <= v.len() && v.len() <= self.max, "BoundedSeqBi: woodifying {} elements, but the bounds are {}..={}", v.len(), self.min, self.max);
+		let mut ret = Vec::new();
+		woodify_seq_into(&self.sub, v.iter(), &mut ret);
+		ret.into()
+	}
+}
+impl<T, SubTran> Dewooder<Vec<T>> for BoundedSeqBi<SubTran> where SubTran:Dewooder<T> {
+	fn dewoodify(&self, v:&Wood) -> Result<Vec<T>, DewoodifyError> {
+		let n = v.contents().len();
+		if n < self.min {
+			return Err(DewoodifyError::new(v, format!("expected at least {} elements, but there were {}", self.min, n)));
+		}
+		if n > self.max {
+			return Err(DewoodifyError::new(v, format!("expected at most {} elements, but there were {}", self.max, n)));
+		}
+		let mut ret = Vec::with_capacity(n);
+		dewoodify_seq_into(&self.sub, v.contents(), &mut ret)?;
+		Ok(ret)
+	}
+}
+
+
+
+/// one element of a PositionalBi
+trait PositionalElement<T> {
+	fn woodify_into(&self, v:&T, out:&mut Vec<Wood>);
+	fn dewoodify_into(&self, v:&mut T, w:&Wood) -> Result<(), DewoodifyError>;
+}
+
+struct ElementEntry<Tran, G, S> {
+	tran:Tran,
+	get:G,
+	set:S,
+}
+impl<T, F, Tran, G, S> PositionalElement<T> for ElementEntry<Tran, G, S>
+	where Tran:Biwooder<F>, G:Fn(&T)-> &F, S:Fn(&mut T, F)
+{
+	fn woodify_into(&self, v:&T, out:&mut Vec<Wood>){
+		out.push(self.tran.woodify((self.get)(v)));
+	}
+	fn dewoodify_into(&self, v:&mut T, w:&Wood) -> Result<(), DewoodifyError> {
+		(self.set)(v, self.tran.dewoodify(w)?);
+		Ok(())
+	}
+}
+
+/// For tuple structs and other types made of a fixed number of positional elements, each with its own translator. Woodifies to a branch of the elements in the order they were registered. The name is only used in error messages, EG "element 3 of quaternion".
+/// ```
+/// # use wood::wooder::{PositionalBi, Iden};
+/// #[derive(Default)]
+/// struct Quaternion(f64, f64, f64, f64);
+/// let bi = PositionalBi::new("quaternion")
+/// 	.element(Iden, |q:&Quaternion| &q.0, |q, v| q.0 = v)
+/// 	.element(Iden, |q:&Quaternion| &q.1, |q, v| q.1 = v)
+/// 	.element(Iden, |q:&Quaternion| &q.2, |q, v| q.2 = v)
+/// 	.element(Iden, |q:&Quaternion| &q.3, |q, v| q.3 = v);
+/// ```
+pub struct PositionalBi<'a, T> {
+	name:&'a str,
+	elements:Vec<Box<dyn PositionalElement<T> + 'a>>,
+}
+impl<'a, T> PositionalBi<'a, T> {
+	pub fn new(name:&'a str)-> Self { PositionalBi{ name, elements:Vec::new() } }
+	pub fn element<F, Tran, G, S>(mut self, tran:Tran, get:G, set:S)-> Self
+		where Tran:Biwooder<F> + 'a, G:Fn(&T)-> &F + 'a, S:Fn(&mut T, F) + 'a
+	{
+		self.elements.push(Box::new(ElementEntry{ tran, get, set }));
+		self
+	}
+}
+impl<'a, T> Wooder<T> for PositionalBi<'a, T> {
+	fn woodify(&self, v:&T) -> Wood {
+		let mut ret = Vec::with_capacity(self.elements.len());
+		for e in self.elements.iter() { e.woodify_into(v, &mut ret); }
+		ret.into()
+	}
+}
+impl<'a, T> Dewooder<T> for PositionalBi<'a, T> where T:Default {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let li = v.contents().as_slice();
+		if li.len() != self.elements.len() {
+			return Err(DewoodifyError::new(v, format!("{} has {} elements, but there were {}", self.name, self.elements.len(), li.len())));
+		}
+		let mut ret = T::default();
+		for (i, (e, w)) in self.elements.iter().zip(li.iter()).enumerate() {
+			e.dewoodify_into(&mut ret, w).map_err(|er|{
+				DewoodifyError::new_with_cause(w, format!("element {} of {}: {}", i, self.name, er.msg), Some(Box::new(er)))
+			})?;
+		}
+		Ok(ret)
+	}
+}
+
+
+
+/// Translates a document that carries a `version N` field. Woodification always writes the current version, inserting `(version N)` just after the tag, or at the front of a branch that doesn't start with one, or putting it in front of a leaf, as `((version N) leaf)`. Dewoodification takes the field out again before handing the rest on, so the inner dewooder sees what the inner wooder wrote. Dewoodification reads the version and uses the dewooder registered for it, so documents written by older versions of a program can still be read. Each historical version is registered with a closure that upgrades what was read to the current type, these can be chained, EG `|v1| v2_to_v3(v1_to_v2(v1))`.
+pub struct VersionedBi<'a, T, SubTran> {
+	pub current_version:u64,
+	pub current:SubTran,
+	historical:Vec<(u64, Box<dyn Dewooder<T> + 'a>)>,
+}
+impl<'a, T, SubTran> VersionedBi<'a, T, SubTran> {
+	pub fn new(current_version:u64, current:SubTran)-> Self { VersionedBi{ current_version, current, historical:Vec::new() } }
+	pub fn historical<Old, D, M>(mut self, version:u64, dewooder:D, migrate:M)-> Self
+		where D:Dewooder<Old> + 'a, M:Fn(Old)-> T + 'a, Old:'a, T:'a
+	{
+		self.historical.push((version, Box::new(Migrated(dewooder, migrate, PhantomData))));
+		self
+	}
+}
+struct Migrated<D, M, Old>(D, M, PhantomData<fn(Old)-> Old>);
+impl<T, Old, D, M> Dewooder<T> for Migrated<D, M, Old> where D:Dewooder<Old>, M:Fn(Old)-> T {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> { self.0.dewoodify(v).map(&self.1) }
+}
+impl<'a, T, SubTran> Wooder<T> for VersionedBi<'a, T, SubTran> where SubTran:Wooder<T> {
+	fn woodify(&self, v:&T) -> Wood {
+		let version_field = branch!("version", self.current_version.woodify());
+		match self.current.woodify(v) {
+			Branchv(mut b)=> {
+				let at = if b.v.first().map_or(false, |f| f.is_leaf()) { 1 } else { 0 };
+				b.v.insert(at, version_field);
+				Branchv(b)
+			}
+			leaf=> branch!(version_field, leaf),
+		}
+	}
+}
+//the version field's value, and v without the field, as it was before VersionedBi's woodify put it there
+fn take_version(v:&Wood)-> Result<(&Wood, Wood), DewoodifyError> {
+	let b = match *v {
+		Branchv(ref b)=> b,
+		Leafv(_)=> return Err(DewoodifyError::new(v, "expected a version field".into())),
+	};
+	let tagged = b.v.first().map_or(false, |f| f.is_leaf());
+	let i = match b.v.iter().enumerate().skip(if tagged { 1 }else{ 0 }).find(|&(_, c)| c.is_branch() && c.initial_str() == "version") {
+		Some((i, _))=> i,
+		None=> return Err(DewoodifyError::new(v, "expected a version field".into())),
+	};
+	let vw = match b.v[i].tail().next() {
+		Some(vw)=> vw,
+		None=> return Err(DewoodifyError::new(&b.v[i], "expected a subwood, but the wood has no tail".into())),
+	};
+	let mut rest = b.clone();
+	rest.v.remove(i);
+	//a leaf is written after the field, a tagged branch's first element is a leaf that comes before it
+	if !tagged && rest.v.len() == 1 && rest.v[0].is_leaf() {
+		return Ok((vw, rest.v.pop().unwrap()));
+	}
+	Ok((vw, Branchv(rest)))
+}
+impl<'a, T, SubTran> Dewooder<T> for VersionedBi<'a, T, SubTran> where SubTran:Dewooder<T> {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let (vw, rest) = take_version(v)?;
+		let version = u64::dewoodify(vw)?;
+		if version == self.current_version {
+			return self.current.dewoodify(&rest);
+		}
+		match self.historical.iter().find(|&&(hv, _)| hv == version) {
+			Some(&(_, ref d))=> d.dewoodify(&rest).map_err(|e|{
+				DewoodifyError::new_with_cause(v, format!("reading a version {} document: {}", version, e.msg), Some(Box::new(e)))
+			}),
+			None=> Err(DewoodifyError::new(vw, format!("unsupported version {}, the current version is {}", version, self.current_version))),
+		}
+	}
+}
+
+
+
+/// For maps where the order of the entries matters. Translates a `Vec<(K, V)>` as a branch of `(key value)` pairs in the order they appear, and doesn't require anything of K.
+#[derive(Copy, Clone)]
+pub struct OrderedMapBi<KeyTran, ValTran>(pub KeyTran, pub ValTran);
+impl<K, V, KeyTran, ValTran> Wooder<Vec<(K, V)>> for OrderedMapBi<KeyTran, ValTran> where KeyTran:Wooder<K>, ValTran:Wooder<V> {
+	fn woodify(&self, v:&Vec<(K, V)>) -> Wood {
+		let mut ret = Vec::new();
+		woodify_map(&self.0, &self.1, v.iter().map(|&(ref k, ref v)| (k, v)), &mut ret);
+		ret.into()
+	}
+}
+impl<K, V, KeyTran, ValTran> Dewooder<Vec<(K, V)>> for OrderedMapBi<KeyTran, ValTran> where KeyTran:Dewooder<K>, ValTran:Dewooder<V> {
+	fn dewoodify(&self, v:&Wood) -> Result<Vec<(K, V)>, DewoodifyError> {
+		let mut ret = Vec::new();
+		dewoodify_map(&self.0, &self.1, v.contents(), &mut ret)?;
+		Ok(ret)
+	}
+}
+
+
+
+/// Translates bools using configurable vocabularies. The first word of each list is the one that gets written.
+#[derive(Copy, Clone)]
+pub struct BoolWords<'a> {
+	truthy:&'a [&'a str],
+	falsy:&'a [&'a str],
+}
+impl<'a> BoolWords<'a> {
+	/// panics if either list is empty, as there'd be nothing to write
+	pub const fn new(truthy:&'a [&'a str], falsy:&'a [&'a str])-> Self {
+		assert!(!truthy.is_empty() && !falsy.is_empty(), "BoolWords needs at least one word for each of true and false");
+		BoolWords{ truthy, falsy }
+	}
+	pub fn truthy(&self)-> &'a [&'a str] { self.truthy }
+	pub fn falsy(&self)-> &'a [&'a str] { self.falsy }
+}
+pub const ON_OFF:BoolWords<'static> = BoolWords::new(&["on"], &["off"]);
+pub const ENABLED_DISABLED:BoolWords<'static> = BoolWords::new(&["enabled"], &["disabled"]);
+pub const ONE_ZERO:BoolWords<'static> = BoolWords::new(&["1"], &["0"]);
+impl<'a> Wooder<bool> for BoolWords<'a> {
+	fn woodify(&self, v:&bool) -> Wood {
+		let words = if *v { self.truthy } else { self.falsy };
+		words[0].into()
+	}
+}
+impl<'a> Dewooder<bool> for BoolWords<'a> {
+	fn dewoodify(&self, v:&Wood) -> Result<bool, DewoodifyError> {
+		let s = v.initial_str();
+		if self.truthy.contains(&s) {
+			Ok(true)
+		}else if self.falsy.contains(&s) {
+			Ok(false)
+		}else{
+			Err(DewoodifyError::new(v, format!("expected one of {} or {} here", self.truthy.join(", "), self.falsy.join(", "))))
+		}
+	}
+}
+
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Radix { Binary, Octal, Decimal, Hex }
+impl Radix {
+	fn prefix(&self)-> &'static str {
+		match *self { Radix::Binary=> "0b", Radix::Octal=> "0o", Radix::Decimal=> "", Radix::Hex=> "0x" }
+	}
+}
+
+/// strips the sign, radix prefix and _ separators, returning (negative, radix, digits)
+fn split_radix_literal(s:&str)-> (bool, u32, String) {
+	let (neg, s) = if s.starts_with('-') { (true, &s[1..]) } else if s.starts_with('+') { (false, &s[1..]) } else { (false, s) };
+	let lower = s.get(..2).map(|p| p.to_ascii_lowercase());
+	let (radix, s) = match lower.as_ref().map(|p| p.as_str()) {
+		Some("0x")=> (16, &s[2..]),
+		Some("0o")=> (8, &s[2..]),
+		Some("0b")=> (2, &s[2..]),
+		_=> (10, s),
+	};
+	(neg, radix, s.chars().filter(|c| *c != '_').collect())
+}
+
+/// Integers that can be written as `0x…`, `0o…` or `0b…` as well as in decimal, and with `_` separators between digits. Emits in the given radix.
+#[derive(Copy, Clone)]
+pub struct RadixInt(pub Radix);
+macro_rules! do_radix_int_for {
+	($Type:ident, $Unsigned:ident) => (
+		impl Wooder<$Type> for RadixInt {
+			fn woodify(&self, v:&$Type) -> Wood {
+				#[allow(unused_comparisons)]
+				let neg = *v < 0;
+				let mag = if neg { (*v as $Unsigned).wrapping_neg() } else { *v as $Unsigned };
+				let digits = match self.0 {
+					Radix::Binary=> format!("{:b}", mag),
+					Radix::Octal=> format!("{:o}", mag),
+					Radix::Decimal=> format!("{}", mag),
+					Radix::Hex=> format!("{:x}", mag),
+				};
+				format!("{}{}{}", if neg { "-" } else { "" }, self.0.prefix(), digits).into()
+			}
+		}
+		impl Dewooder<$Type> for RadixInt {
+			fn dewoodify(&self, v:&Wood) -> Result<$Type, DewoodifyError> {
+				let (neg, radix, digits) = split_radix_literal(v.initial_str());
+				//with the sign always written, from_str_radix takes no second one from the digits, so +-5 or 0x-5 don't get through
+				let literal = format!("{}{}", if neg { "-" } else { "+" }, digits);
+				$Type::from_str_radix(&literal, radix).map_err(|e|{
+					DewoodifyError::new_with_cause(v, format!("couldn't parse {}: {}", stringify!($Type), e), Some(Box::new(e)))
+				})
+			}
+		}
+	)
+}
+do_radix_int_for!(u8, u8);
+do_radix_int_for!(i8, u8);
+do_radix_int_for!(u16, u16);
+do_radix_int_for!(i16, u16);
+do_radix_int_for!(u32, u32);
+do_radix_int_for!(i32, u32);
+do_radix_int_for!(u64, u64);
+do_radix_int_for!(i64, u64);
+do_radix_int_for!(u128, u128);
+do_radix_int_for!(i128, u128);
+do_radix_int_for!(usize, usize);
+do_radix_int_for!(isize, usize);
+
+
+
+/// Maps leaves to unit-like values and back, EG `StringEnumBi(&[("red", Color::Red), ("green", Color::Green)])`. If a value appears more than once, the first name listed for it is the one that gets written.
+#[derive(Copy, Clone)]
+pub struct StringEnumBi<'a, T:'a>(pub &'a [(&'a str, T)]);
+impl<'a, T> Wooder<T> for StringEnumBi<'a, T> where T:PartialEq {
+	fn woodify(&self, v:&T) -> Wood {
+		match self.0.iter().find(|&&(_, ref c)| c == v) {
+			Some(&(name, _))=> name.into(),
+			None=> panic!("StringEnumBi: the value has no name in the table"),
+		}
+	}
+}
+impl<'a, T> Dewooder<T> for StringEnumBi<'a, T> where T:Clone {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let s = v.initial_str();
+		match self.0.iter().find(|&&(name, _)| name == s) {
+			Some(&(_, ref c))=> Ok(c.clone()),
+			None=> {
+				let names:Vec<&str> = self.0.iter().map(|&(name, _)| name).collect();
+				Err(DewoodifyError::new(v, format!("\"{}\" isn't one of the accepted values: {}", s, names.join(", "))))
+			}
+		}
+	}
+}
+
+
+
+/// For translating recursive types. The closure is given a RecursiveRef, a stand-in for the biwooder being defined, which it can use inside the biwooder it returns.
+/// ```
+/// # use wood::wooder::{RecursiveBi, StructBi, SequenceBi, Iden};
+/// #[derive(Default, Clone)]
+/// struct Menu { name:String, items:Vec<Menu> }
+/// let bi = RecursiveBi::new(|menu| {
+/// 	StructBi::new("menu")
+/// 		.field("name", Iden, |m:&Menu| &m.name, |m, v| m.name = v)
+/// 		.field("items", SequenceBi(menu), |m:&Menu| &m.items, |m, v| m.items = v)
+/// });
+/// ```
+pub struct RecursiveBi<'a, T> {
+	inner:Rc<OnceCell<Box<dyn Biwooder<T> + 'a>>>,
+}
+/// see RecursiveBi. Only holds a weak reference, so it can't keep the RecursiveBi alive, or stop it from being dropped.
+pub struct RecursiveRef<'a, T> {
+	inner:Weak<OnceCell<Box<dyn Biwooder<T> + 'a>>>,
+}
+impl<'a, T> Clone for RecursiveRef<'a, T> {
+	fn clone(&self)-> Self { RecursiveRef{ inner:self.inner.clone() } }
+}
+impl<'a, T> RecursiveBi<'a, T> {
+	pub fn new<F, B>(f:F)-> Self where F:FnOnce(RecursiveRef<'a, T>)-> B, B:Biwooder<T> + 'a {
+		let inner = Rc::new(OnceCell::new());
+		let b = f(RecursiveRef{ inner:Rc::downgrade(&inner) });
+		let _ = inner.set(Box::new(b) as Box<dyn Biwooder<T> + 'a>);
+		RecursiveBi{ inner }
+	}
+	/// another reference to this biwooder, for use in other biwooders
+	pub fn reference(&self)-> RecursiveRef<'a, T> { RecursiveRef{ inner:Rc::downgrade(&self.inner) } }
+}
+impl<'a, T> Wooder<T> for RecursiveBi<'a, T> {
+	fn woodify(&self, v:&T) -> Wood { self.inner.get().unwrap().woodify(v) }
+}
+impl<'a, T> Dewooder<T> for RecursiveBi<'a, T> {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> { self.inner.get().unwrap().dewoodify(v) }
+}
+impl<'a, T> Wooder<T> for RecursiveRef<'a, T> {
+	fn woodify(&self, v:&T) -> Wood {
+		let inner = self.inner.upgrade().expect("RecursiveRef used after its RecursiveBi was dropped");
+		inner.get().expect("RecursiveRef used before RecursiveBi::new finished").woodify(v)
+	}
+}
+impl<'a, T> Dewooder<T> for RecursiveRef<'a, T> {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		let inner = self.inner.upgrade().expect("RecursiveRef used after its RecursiveBi was dropped");
+		inner.get().expect("RecursiveRef used before RecursiveBi::new finished").dewoodify(v)
+	}
+}
+
+
+
+fn rename_field_keys<'t>(v:&Wood, rename:&dyn Fn(&str)-> Option<&'t str>)-> Wood {
+	match *v {
+		Branchv(ref b)=> {
+			let renamed = b.v.iter().enumerate().map(|(i, f)|{
+				match *f {
+					Branchv(ref fb) if i > 0 || !b.v[0].is_leaf() => {
+						match fb.v.first() {
+							Some(&Leafv(ref key))=> {
+								if let Some(to) = rename(&key.v) {
+									let mut nfb = fb.clone();
+									nfb.v[0] = Leafv(Leaf{ line:key.line, column:key.column, meta:key.meta.clone(), v:to.to_string() });
+									return Branchv(nfb);
+								}
+								f.clone()
+							}
+							_=> f.clone(),
+						}
+					}
+					_=> f.clone(),
+				}
+			}).collect();
+			Branchv(Branch{ line:b.line, column:b.column, meta:b.meta.clone(), v:renamed })
+		}
+		Leafv(_)=> v.clone(),
+	}
+}
+
+/// Renames the keys of a struct's fields between the names used in the code and the names used in the document, so that, EG, a document's `max-connections` can be read by a translator that looks for `max_connections`. The table is of `(code name, document name)` pairs. Only the fields directly under the wood are renamed, not the fields of nested structs.
+#[derive(Copy, Clone)]
+pub struct RenameFields<'a, SubTran> {
+	pub table:&'a [(&'a str, &'a str)],
+	pub sub:SubTran,
+}
+impl<'a, SubTran> RenameFields<'a, SubTran> {
+	pub fn new(table:&'a [(&'a str, &'a str)], sub:SubTran)-> Self { RenameFields{ table, sub } }
+}
+impl<'a, T, SubTran> Wooder<T> for RenameFields<'a, SubTran> where SubTran:Wooder<T> {
+	fn woodify(&self, v:&T) -> Wood {
+		rename_field_keys(&self.sub.woodify(v), &|k| self.table.iter().find(|p| p.0 == k).map(|p| p.1))
+	}
+}
+impl<'a, T, SubTran> Dewooder<T> for RenameFields<'a, SubTran> where SubTran:Dewooder<T> {
+	fn dewoodify(&self, v:&Wood) -> Result<T, DewoodifyError> {
+		self.sub.dewoodify(&rename_field_keys(v, &|k| self.table.iter().find(|p| p.1 == k).map(|p| p.0)))
+	}
+}
+
+
+
+/// For newtypes, EG `UserId(u64)`, that should be written as just their inner value. Unlike Biwooder::map, woodification only borrows the inner value.
+/// `TransparentBi::new(|u:&UserId| &u.0, UserId)`
+pub struct TransparentBi<SubTran, Get, Make, Inner> {
+	pub sub:SubTran,
+	pub get:Get,
+	pub make:Make,
+	_inner:PhantomData<fn(Inner)-> Inner>,
+}
+impl<Get, Make, Inner> TransparentBi<Iden, Get, Make, Inner> {
+	pub fn new<Outer>(get:Get, make:Make)-> Self where Get:Fn(&Outer)-> &Inner, Make:Fn(Inner)-> Outer {
+		TransparentBi{ sub:Iden, get, make, _inner:PhantomData }
+	}
+}
+impl<SubTran, Get, Make, Inner> TransparentBi<SubTran, Get, Make, Inner> {
+	/// translates the inner value with sub rather than with its Woodable and Dewoodable impls
+	pub fn with<Outer>(sub:SubTran, get:Get, make:Make)-> Self where Get:Fn(&Outer)-> &Inner, Make:Fn(Inner)-> Outer {
+		TransparentBi{ sub, get, make, _inner:PhantomData }
+	}
+}
+impl<Outer, Inner, SubTran, Get, Make> Wooder<Outer> for TransparentBi<SubTran, Get, Make, Inner> where SubTran:Wooder<Inner>, Get:Fn(&Outer)-> &Inner {
+	fn woodify(&self, v:&Outer) -> Wood { self.sub.woodify((self.get)(v)) }
+}
+impl<Outer, Inner, SubTran, Get, Make> Dewooder<Outer> for TransparentBi<SubTran, Get, Make, Inner> where SubTran:Dewooder<Inner>, Make:Fn(Inner)-> Outer {
+	fn dewoodify(&self, v:&Wood) -> Result<Outer, DewoodifyError> { self.sub.dewoodify(v).map(&self.make) }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	#[test]
+	fn idempotent_int() {
+		let back:isize = Iden.dewoodify(&Iden.woodify(&90isize)).unwrap();
+		assert!(90isize == back);
+	}
+	
+	#[test]
+	fn tricky_branch_parse() {
+		let brancho = branch!(branch!("tricky", "branch"), branch!("parse"));
+		let tranner:SequenceBi<SequenceBi<Iden>> = SequenceBi(SequenceBi(Iden));
+		let lv:Vec<Vec<String>> = tranner.dewoodify(&brancho).unwrap();
+		assert!(lv.len() == 2);
+		assert!(lv[0].len() == 2);
+		assert!(lv[0][0].len() == 6);
+		assert!(tranner.woodify(&lv).to_string() == "((tricky branch) (parse))");
+	}
+	
+	#[test]
+	fn do_hash_map() {
+		let t = parse_termpose("a:b c:d d:e e:f").unwrap();
+		let bt = TaggedHashMapBi("ob", Iden, Iden);
+		let utr: Result<HashMap<String, String>, DewoodifyError> = bt.dewoodify(&t);
+		assert!(utr.is_err());
+		let tt = parse_termpose("ob a:b c:d d:e e:f").unwrap();
+		let hm:HashMap<String, String> = TaggedHashMapBi("ob", Iden, Iden).dewoodify(&tt).unwrap();
+		assert!(hm.get("a").unwrap() == "b");
+		assert!(hm.get("d").unwrap() == "e");
+	}
+	
+	fn give_hm() -> HashMap<String, String> {
+		[
+			("a".into(), "b".into()),
+			("c".into(), "d".into()),
+		].iter().cloned().collect()
+	}
+	
+	#[test]
+	fn implicit_biwooders() {
+		let t = parse_termpose("a:b c:d").unwrap();
+		let exh = give_hm();
+		
+		let exu:HashMap<String, String> = dewoodify(&t).unwrap();
+		
+		assert!(exu == exh)
+	}
+	
+	const SPECIAL_SEQ_BIWOODER:SequenceBi<Iden> =
+		SequenceBi(Iden);
+	#[test]
+	fn static_biwooder() {
+		let r:Result<Wood, _> = parse_termpose("c c c c c a");
+		let v:Vec<char> = SPECIAL_SEQ_BIWOODER.dewoodify(&r.unwrap()).unwrap();
+		assert_eq!(v.len(), 6usize);
+		assert_eq!(v[5], 'a');
+	}
+	
+	#[test]
+	fn automatic_deserialize() {
+		let hm:HashMap<String, String> = deserialize("a:b c:d").unwrap();
+		assert!(hm == give_hm());
+	}
+	
+	#[test]
+	fn option_round_trip() {
+		let v:Vec<Option<u32>> = vec!(Some(3), None, Some(0));
+		let w = v.woodify();
+		assert_eq!(w.to_string(), "((some 3) none (some 0))");
+		let back:Vec<Option<u32>> = dewoodify(&w).unwrap();
+		assert_eq!(v, back);
+		let bad:Result<Option<u32>, _> = dewoodify(&parse_termpose("nothing").unwrap());
+		assert!(bad.is_err());
+		let long:Result<Option<u32>, _> = OptionTran(Iden).dewoodify(&parse_termpose("some 1 2").unwrap());
+		assert!(long.is_err());
+	}
+	
+	#[test]
+	fn result_round_trip() {
+		let v:Vec<Result<u32, String>> = vec!(Ok(3), Err("too big".into()));
+		let w = v.woodify();
+		assert_eq!(w.to_string(), "((ok 3) (err \"too big\"))");
+		let back:Vec<Result<u32, String>> = dewoodify(&w).unwrap();
+		assert_eq!(v, back);
+		let bad:Result<Result<u32, String>, _> = dewoodify(&parse_termpose("maybe 3").unwrap());
+		assert!(bad.is_err());
+	}
+	
+	#[test]
+	fn tuples() {
+		let t:(u32, String, bool) = dewoodify(&parse_termpose("3 three true").unwrap()).unwrap();
+		assert_eq!(t, (3, "three".to_string(), true));
+		assert_eq!(t.woodify().to_string(), "(3 three true)");
+		let short:Result<(u32, u32, u32), _> = dewoodify(&parse_termpose("1 2").unwrap());
+		assert!(short.is_err());
+		let wrong:DewoodifyError = dewoodify::<(u32, u32, u32)>(&parse_termpose("1 2 x").unwrap()).unwrap_err();
+		assert!(wrong.msg.contains("element 2"));
+		assert_eq!(wrong.column, 5);
+	}
+	
+	#[test]
+	fn arrays() {
+		let a:[u32; 3] = dewoodify(&parse_termpose("1 2 3").unwrap()).unwrap();
+		assert_eq!(a, [1, 2, 3]);
+		assert_eq!(a.woodify().to_string(), "(1 2 3)");
+		let e = dewoodify::<[u32; 4]>(&parse_termpose("1 2 3").unwrap()).unwrap_err();
+		assert!(e.msg.contains("exactly 4") && e.msg.contains("there are 3"));
+	}
+	
+	#[test]
+	fn btrees() {
+		let m:BTreeMap<String, u32> = dewoodify(&parse_termpose("c:3 a:1 b:2").unwrap()).unwrap();
+		assert_eq!(m.woodify().to_string(), "((a 1) (b 2) (c 3))");
+		let s:BTreeSet<u32> = BTreeSetBi(Iden).dewoodify(&parse_termpose("3 1 2 1").unwrap()).unwrap();
+		assert_eq!(s.len(), 3);
+		assert_eq!(s.woodify().to_string(), "(1 2 3)");
+	}
+	
+	#[test]
+	fn hash_sets() {
+		let w = parse_termpose("a b c b").unwrap();
+		let s:HashSet<String> = dewoodify(&w).unwrap();
+		assert_eq!(s.len(), 3);
+		let strict:Result<HashSet<String>, _> = SetTran::rejecting_duplicates(Iden).dewoodify(&w);
+		let e = strict.unwrap_err();
+		assert_eq!(e.column, 7);
+		assert!(e.msg.contains("duplicate"));
+	}
+	
+	#[test]
+	fn other_sequences() {
+		use std::collections::{VecDeque, LinkedList, BinaryHeap};
+		let w = parse_termpose("3 1 2").unwrap();
+		let d:VecDeque<u32> = dewoodify(&w).unwrap();
+		assert_eq!(d.woodify(), w);
+		let l:LinkedList<u32> = dewoodify(&w).unwrap();
+		assert_eq!(l.woodify(), w);
+		let h:BinaryHeap<u32> = dewoodify(&w).unwrap();
+		assert_eq!(h.into_sorted_vec(), vec!(1, 2, 3));
+	}
+	
+	#[derive(Debug, PartialEq)]
+	enum Expr { Num(u32), Add(Box<Expr>, Box<Expr>) }
+	impl Woodable for Expr {
+		fn woodify(&self) -> Wood {
+			match *self {
+				Expr::Num(n)=> n.woodify(),
+				Expr::Add(ref a, ref b)=> branch!("+", a.woodify(), b.woodify()),
+			}
+		}
+	}
+	impl Dewoodable for Expr {
+		fn dewoodify(v:&Wood) -> Result<Expr, DewoodifyError> {
+			if v.is_leaf() {
+				Ok(Expr::Num(dewoodify(v)?))
+			}else{
+				let (_, a, b):(String, Box<Expr>, Box<Expr>) = dewoodify(v)?;
+				Ok(Expr::Add(a, b))
+			}
+		}
+	}
+	
+	#[test]
+	fn smart_pointers() {
+		let w = parse_termpose("+ 1 (+ 2 3)").unwrap();
+		let e:Box<Expr> = dewoodify(&w).unwrap();
+		assert_eq!(e.woodify(), w);
+		let r:std::rc::Rc<String> = dewoodify(&parse_termpose("shared").unwrap()).unwrap();
+		assert_eq!(r.woodify().initial_str(), "shared");
+		let a:std::sync::Arc<u32> = dewoodify(&parse_termpose("9").unwrap()).unwrap();
+		assert_eq!(*a, 9);
+	}
+	
+	#[test]
+	fn borrowed_strings() {
+		use std::borrow::Cow;
+		let w = parse_termpose("a bb ccc").unwrap();
+		let strs:Vec<&str> = dewoodify_ref(&w).unwrap();
+		assert_eq!(strs, vec!("a", "bb", "ccc"));
+		let c:Cow<str> = dewoodify_ref(w.tail().next().unwrap()).unwrap();
+		assert!(match c { Cow::Borrowed("bb")=> true, _=> false });
+		let o:Cow<str> = dewoodify(&"owned".into()).unwrap();
+		assert_eq!(o.woodify().initial_str(), "owned");
+		assert!(dewoodify_ref::<&str>(&w).is_err());
+	}
+	
+	#[test]
+	fn owned_strings() {
+		let w = parse_termpose("a bb ccc").unwrap();
+		let ptr = w.tail().next().unwrap().initial_str().as_ptr();
+		let strs:Vec<String> = dewoodify_owned(w).unwrap();
+		assert_eq!(strs, vec!("a", "bb", "ccc"));
+		assert_eq!(strs[1].as_ptr(), ptr); //moved, not copied
+		let nested:Vec<Spanned<Vec<u8>>> = dewoodify_owned(parse_termpose("(1 2) (3)").unwrap()).unwrap();
+		assert_eq!(*nested[1], vec!(3));
+		assert_eq!(nested[1].column, 7);
+		let e = dewoodify_owned::<Vec<String>>(parse_termpose("a (b)").unwrap()).unwrap_err();
+		assert_eq!(e.column, 3);
+		
+		let text = "(some (a 1)) none (b:2 c:3) ((x (y z)))";
+		let w = parse_termpose(text).unwrap();
+		type Mixed = (Option<(String, u8)>, Option<String>, HashMap<String, u32>, BTreeMap<String, Vec<String>>);
+		let owned:Mixed = dewoodify_owned(w.clone()).unwrap();
+		assert_eq!(owned, dewoodify::<Mixed>(&w).unwrap());
+		assert_eq!(owned.0, Some(("a".to_string(), 1)));
+		for bad in ["(some) none () ()", "maybe none () ()", "none none (b:x) ()", "none none (b) ()", "none none () () extra", "none none () (((x) y))"].iter() {
+			let w = parse_termpose(bad).unwrap();
+			let (o, b) = (dewoodify_owned::<Mixed>(w.clone()).unwrap_err(), dewoodify::<Mixed>(&w).unwrap_err());
+			assert_eq!((o.line, o.column, o.msg), (b.line, b.column, b.msg));
+		}
+	}
+	
+	#[test]
+	fn integer_widths() {
+		let v:(u8, i8, u16, i16, u128, i128) = dewoodify(&parse_termpose("255 -128 65535 -32768 340282366920938463463374607431768211455 -5").unwrap()).unwrap();
+		assert_eq!(v, (255, -128, 65535, -32768, u128::MAX, -5));
+		let e = dewoodify::<Vec<u8>>(&parse_termpose("1 256").unwrap()).unwrap_err();
+		assert!(e.msg.contains("u8"));
+		assert!(e.msg.contains("out of range"));
+		assert_eq!(e.column, 3);
+	}
+	
+	#[test]
+	fn nonzero() {
+		use std::num::NonZeroU32;
+		let n:NonZeroU32 = dewoodify(&parse_termpose("7").unwrap()).unwrap();
+		assert_eq!(n.get(), 7);
+		assert_eq!(n.woodify().initial_str(), "7");
+		let e = dewoodify::<NonZeroU32>(&parse_termpose("0").unwrap()).unwrap_err();
+		assert!(e.msg.contains("NonZeroU32 must not be zero"));
+	}
+	
+	#[test]
+	fn durations() {
+		assert_eq!(Duration::from_secs(90).woodify().to_string(), "90");
+		let d = Duration::new(1, 500);
+		assert_eq!(d, dewoodify::<Duration>(&d.woodify()).unwrap());
+		assert_eq!(Duration::from_millis(1500), dewoodify::<Duration>(&parse_termpose("1.5").unwrap()).unwrap());
+		assert_eq!(Duration::new(3, 7), dewoodify::<Duration>(&parse_termpose("secs:3 nanos:7").unwrap()).unwrap());
+		assert!(dewoodify::<Duration>(&parse_termpose("1.5s").unwrap()).is_err());
+		assert!(dewoodify::<Duration>(&parse_termpose("5.").unwrap()).is_err());
+		assert!(dewoodify::<Duration>(&parse_termpose(".5").unwrap()).is_err());
+		let t = UNIX_EPOCH + Duration::new(1700000000, 250_000_000);
+		assert_eq!(t.woodify().to_string(), "1700000000.25");
+		assert_eq!(t, dewoodify::<SystemTime>(&t.woodify()).unwrap());
+		let before = UNIX_EPOCH - Duration::from_secs(5);
+		assert_eq!(before, dewoodify::<SystemTime>(&before.woodify()).unwrap());
+	}
+	
+	#[test]
+	fn paths() {
+		use std::path::{Path, PathBuf};
+		let paths:Vec<PathBuf> = vec!("/home/a b/c:d".into(), "C:\\Program Files\\thing".into(), "plain".into());
+		let text = pretty_termpose(&paths.woodify());
+		let back:Vec<PathBuf> = dewoodify(&parse_termpose(&text).unwrap()).unwrap();
+		assert_eq!(paths, back);
+		assert_eq!(Path::new("x/y").woodify().initial_str(), "x/y");
+		assert_eq!((&Path::new("x/y")).woodify().initial_str(), "x/y");
+	}
+	
+	#[cfg(unix)]
+	#[test]
+	fn non_unicode_paths() {
+		use std::path::PathBuf;
+		use std::os::unix::ffi::OsStringExt;
+		let raw = PathBuf::from(OsString::from_vec(vec!(b'/', b'a', 0xff)));
+		assert_eq!(raw.woodify().to_string(), "(bytes 2f61ff)");
+		assert_eq!(dewoodify::<PathBuf>(&raw.woodify()).unwrap(), raw);
+	}
+	
+	#[test]
+	fn net_addresses() {
+		use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+		let a:SocketAddr = dewoodify(&parse_termpose("\"[::1]:8080\"").unwrap()).unwrap();
+		assert_eq!(a, SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 8080));
+		let addrs:Vec<SocketAddr> = vec!(a, "127.0.0.1:80".parse().unwrap(), "[fe80::1%2]:443".parse().unwrap());
+		let back:Vec<SocketAddr> = dewoodify(&parse_termpose(&pretty_termpose(&addrs.woodify())).unwrap()).unwrap();
+		assert_eq!(addrs, back);
+		let e = dewoodify::<Vec<IpAddr>>(&parse_termpose("10.0.0.1 10.0.0.256").unwrap()).unwrap_err();
+		assert!(e.msg.contains("IpAddr"));
+		assert_eq!(e.column, 10);
+	}
+	
+	#[test]
+	fn ranges() {
+		let r:Range<u32> = dewoodify(&parse_termpose("3..8").unwrap()).unwrap();
+		assert_eq!(r, 3..8);
+		assert_eq!(r.woodify().to_string(), "(3 8)");
+		assert_eq!(r, dewoodify(&r.woodify()).unwrap());
+		let ri:RangeInclusive<i32> = dewoodify(&parse_termpose("-2..=2").unwrap()).unwrap();
+		assert_eq!(ri, -2..=2);
+		let e = dewoodify::<Range<u32>>(&parse_termpose("3..x").unwrap()).unwrap_err();
+		assert_eq!(e.column, 4);
+	}
+	
+	#[test]
+	fn units() {
+		use std::marker::PhantomData;
+		let t:(u32, (), PhantomData<String>) = dewoodify(&parse_termpose("1 () ()").unwrap()).unwrap();
+		assert_eq!(t.woodify().to_string(), "(1 () ())");
+		assert!(dewoodify::<()>(&parse_termpose("anything").unwrap()).is_ok());
+	}
+	
+	#[cfg(unix)]
+	#[test]
+	fn os_strings() {
+		use std::os::unix::ffi::OsStringExt;
+		let plain = OsString::from("PATH=/bin");
+		assert_eq!(plain.woodify().initial_str(), "PATH=/bin");
+		let raw = OsString::from_vec(vec!(b'a', 0xff, b'b'));
+		let w = raw.woodify();
+		assert_eq!(w.to_string(), "(bytes 61ff62)");
+		assert_eq!(raw, dewoodify::<OsString>(&w).unwrap());
+		assert_eq!(OS_STR_LOSSY.woodify(&raw).initial_str(), "a\u{FFFD}b");
+		let wide:Result<OsString, _> = dewoodify(&parse_termpose("wide 0041").unwrap());
+		assert!(wide.is_err());
+		assert_eq!(OS_STR_LOSSY.dewoodify(&parse_termpose("wide 0041").unwrap()).unwrap(), OsString::from("A"));
+	}
+	
+	#[test]
+	fn unhashable_map_values() {
+		let hf:HashMap<String, f64> = TaggedHashMapBi("ob", Iden, Iden).dewoodify(&parse_termpose("ob a:0.5").unwrap()).unwrap();
+		assert_eq!(hf["a"], 0.5);
+		let hv:HashMap<String, Vec<u32>> = dewoodify(&parse_termpose("b:(1 2 3) c:(4)").unwrap()).unwrap();
+		assert_eq!(hv["b"], vec!(1, 2, 3));
+		assert_eq!(hv, dewoodify(&hv.woodify()).unwrap());
+	}
+	
+	#[test]
+	fn byte_encodings() {
+		let data:Vec<u8> = vec!(0, 1, 0xfe, 0xff, b'w', b'o', b'o', b'd');
+		assert_eq!(HexBytesBi.woodify(&data).initial_str(), "0001feff776f6f64");
+		assert_eq!(data, HexBytesBi.dewoodify(&HexBytesBi.woodify(&data)).unwrap());
+		assert!(HexBytesBi.dewoodify(&"abc".into()).is_err());
+		for (raw, encoded) in vec!(("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")) {
+			assert_eq!(Base64BytesBi.woodify(raw.as_bytes()).initial_str(), encoded);
+			assert_eq!(Base64BytesBi.dewoodify(&encoded.into()).unwrap(), raw.as_bytes());
+		}
+		assert_eq!(data, Base64BytesBi.dewoodify(&Base64BytesBi.woodify(&data)).unwrap());
+		assert_eq!(Base64BytesBi.dewoodify(&"Zm8".into()).unwrap(), b"fo");
+		assert!(Base64BytesBi.dewoodify(&"Zm9v!".into()).is_err());
+	}
+	
+	#[derive(Default, Debug, PartialEq)]
+	struct Server { name:String, port:u16, tags:Vec<String> }
+	
+	fn server_bi()-> StructBi<'static, Server> {
+		StructBi::new("server")
+			.field("name", Iden, |s:&Server| &s.name, |s, v| s.name = v)
+			.optional_field("port", Iden, 8080, |s:&Server| &s.port, |s, v| s.port = v)
+			.field("tags", SequenceBi(Iden), |s:&Server| &s.tags, |s, v| s.tags = v)
+	}
+	
+	#[test]
+	fn struct_bi() {
+		let bi = server_bi();
+		let s = Server{ name:"a".into(), port:81, tags:vec!("x".into()) };
+		assert_eq!(bi.woodify(&s).to_string(), "(server (name a) (port 81) (tags (x)))");
+		assert_eq!(s, bi.dewoodify(&bi.woodify(&s)).unwrap());
+		let d = bi.dewoodify(&parse_termpose("server tags:(p q) name:b").unwrap()).unwrap();
+		assert_eq!(d, Server{ name:"b".into(), port:8080, tags:vec!("p".into(), "q".into()) });
+		let e = bi.dewoodify(&parse_termpose("server name:b port:big tags:()").unwrap()).unwrap_err();
+		assert!(e.msg.contains("in field \"port\""));
+		assert!(bi.dewoodify(&parse_termpose("client name:b tags:()").unwrap()).is_err());
+		assert!(bi.dewoodify(&parse_termpose("server port:1 tags:()").unwrap()).is_err());
+	}
+	
+	#[derive(Debug, PartialEq)]
+	enum Shape { Circle(f64), Rect(f64, f64), Named(Vec<String>), Empty }
+	
+	#[test]
+	fn enum_bi() {
+		let bi = EnumBi::new()
+			.variant("circle", Iden, |s:&Shape| match *s { Shape::Circle(r)=> Some(r), _=> None }, Shape::Circle)
+			.variant("rect", Iden, |s:&Shape| match *s { Shape::Rect(w, h)=> Some((w, h)), _=> None }, |(w, h)| Shape::Rect(w, h))
+			.variant("named", SequenceBi(Iden), |s:&Shape| match *s { Shape::Named(ref n)=> Some(n.clone()), _=> None }, Shape::Named)
+			.variant("empty", Iden, |s:&Shape| match *s { Shape::Empty=> Some(()), _=> None }, |()| Shape::Empty);
+		assert_eq!(bi.woodify(&Shape::Circle(2.0)).to_string(), "(circle 2)");
+		assert_eq!(bi.woodify(&Shape::Rect(2.0, 3.5)).to_string(), "(rect 2 3.5)");
+		assert_eq!(bi.woodify(&Shape::Empty).to_string(), "empty");
+		for s in vec!(Shape::Circle(2.0), Shape::Rect(1.0, 0.5), Shape::Named(vec!("a".into())), Shape::Named(vec!("a".into(), "b".into())), Shape::Empty) {
+			assert_eq!(s, bi.dewoodify(&bi.woodify(&s)).unwrap());
+		}
+		let e = bi.dewoodify(&parse_termpose("square 2").unwrap()).unwrap_err();
+		assert!(e.msg.contains("circle, rect, named, empty"));
+		assert!(bi.dewoodify(&parse_termpose("rect 2").unwrap()).unwrap_err().msg.contains("in variant \"rect\""));
+	}
+	
+	#[test]
+	fn optional_fields() {
+		let w = parse_termpose("config name:a retries:3 empty").unwrap();
+		let mut scanning = FieldScanning::new(&w);
+		assert_eq!(scanning.seek_opt("missing").unwrap(), None);
+		assert!(scanning.seek_opt("empty").is_err());
+		let retries:Option<u32> = OptionalField::new("retries", Iden).dewoodify_from(&mut scanning).unwrap();
+		assert_eq!(retries, Some(3));
+		let timeout:u32 = OptionalField::new("timeout", Iden).dewoodify_or(&mut scanning, 30).unwrap();
+		assert_eq!(timeout, 30);
+		let mut out = Vec::new();
+		OptionalField::new("timeout", Iden).woodify_into(&None::<u32>, &mut out);
+		OptionalField::new("retries", Iden).woodify_into(&Some(3u32), &mut out);
+		assert_eq!(Wood::branch(out).to_string(), "((retries 3))");
+	}
+	
+	#[test]
+	fn default_fill() {
+		let df = DefaultFill(Iden);
+		let n:u32 = df.dewoodify(&Wood::branch(vec!())).unwrap();
+		assert_eq!(n, 0);
+		let s:String = df.dewoodify(&"".into()).unwrap();
+		assert_eq!(s, "");
+		let v:Vec<u32> = df.dewoodify(&parse_termpose("1 2").unwrap()).unwrap();
+		assert_eq!(v, vec!(1, 2));
+		let w = parse_termpose("config name:a").unwrap();
+		let mut scanning = FieldScanning::new(&w);
+		let retries:u32 = df.dewoodify_opt(scanning.seek_opt("retries").unwrap()).unwrap();
+		assert_eq!(retries, 0);
+		assert!(Dewooder::<u32>::dewoodify(&df, &"x".into()).is_err());
+	}
+	
+	#[test]
+	fn validated() {
+		let port = Validated(Iden, |p:&u32| if *p < 65536 { Ok(()) }else{ Err("port must be < 65536".into()) });
+		assert_eq!(port.dewoodify(&"8080".into()).unwrap(), 8080);
+		let w = parse_termpose("server\n port 70000").unwrap();
+		let e = port.dewoodify(FieldScanning::new(&w).seek("port").unwrap()).unwrap_err();
+		assert_eq!(e.msg, "port must be < 65536");
+		assert_eq!((e.line, e.column), (1, 7));
+		assert!(port.dewoodify(&"x".into()).is_err());
+	}
+	
+	#[derive(Debug, PartialEq)]
+	struct Port(u16);
+	
+	#[test]
+	fn mapped() {
+		let bi = Iden.map(|a:u16| Port(a), |b:&Port| b.0);
+		assert_eq!(bi.woodify(&Port(80)).to_string(), "80");
+		assert_eq!(bi.dewoodify(&"443".into()).unwrap(), Port(443));
+		let ports = SequenceBi(Iden.map(|a:u16| Port(a), |b:&Port| b.0));
+		assert_eq!(ports.dewoodify(&parse_termpose("1 2").unwrap()).unwrap(), vec!(Port(1), Port(2)));
+	}
+	
+	#[test]
+	fn any_of() {
+		//a host may be given either as a bare leaf or as an expanded branch
+		let host = AnyOf::new()
+			.or(LambdaDewooder(|w:&Wood| Ok((String::dewoodify(w)?, 80))))
+			.or(StructBi::new("host")
+				.field("name", Iden, |h:&(String, u16)| &h.0, |h, v| h.0 = v)
+				.field("port", Iden, |h:&(String, u16)| &h.1, |h, v| h.1 = v));
+		assert_eq!(host.dewoodify(&"a.com".into()).unwrap(), ("a.com".into(), 80));
+		assert_eq!(host.dewoodify(&parse_termpose("host name:b.com port:81").unwrap()).unwrap(), ("b.com".into(), 81));
+		let e = host.dewoodify(&parse_termpose("host name:b.com").unwrap()).unwrap_err();
+		assert!(e.msg.starts_with("none of the 2 alternatives"));
+		assert!(e.msg.contains("2) could not find key \"port\""));
+	}
+	
+	#[test]
+	fn bounded_seq() {
+		let bi = BoundedSeqBi::new(Iden, 2, 3);
+		let v:Vec<u32> = bi.dewoodify(&parse_termpose("1 2 3").unwrap()).unwrap();
+		assert_eq!(bi.woodify(&v).to_string(), "(1 2 3)");
+		let e = Dewooder::<Vec<u32>>::dewoodify(&bi, &"1".into()).unwrap_err();
+		assert_eq!(e.msg, "expected at least 2 elements, but there were 1");
+		let e = Dewooder::<Vec<u32>>::dewoodify(&bi, &parse_termpose("1 2 3 4").unwrap()).unwrap_err();
+		assert_eq!(e.msg, "expected at most 3 elements, but there were 4");
+	}
+	
+	#[derive(Default, Debug, PartialEq)]
+	struct Quaternion(f64, f64, f64, f64);
+	
+	#[test]
+	fn positional() {
+		let bi = PositionalBi::new("quaternion")
+			.element(Iden, |q:&Quaternion| &q.0, |q, v| q.0 = v)
+			.element(Iden, |q:&Quaternion| &q.1, |q, v| q.1 = v)
+			.element(Iden, |q:&Quaternion| &q.2, |q, v| q.2 = v)
+			.element(Iden, |q:&Quaternion| &q.3, |q, v| q.3 = v);
+		let q = Quaternion(1.0, 0.5, 0.0, 2.0);
+		assert_eq!(bi.woodify(&q).to_string(), "(1 0.5 0 2)");
+		assert_eq!(bi.dewoodify(&bi.woodify(&q)).unwrap(), q);
+		assert_eq!(bi.dewoodify(&parse_termpose("1 2 3 x").unwrap()).unwrap_err().msg, "element 3 of quaternion: couldn't parse f64");
+		assert_eq!(bi.dewoodify(&parse_termpose("1 2 3").unwrap()).unwrap_err().msg, "quaternion has 4 elements, but there were 3");
+	}
+	
+	#[derive(Default, Debug, PartialEq)]
+	struct Deployment { server:Server, replicas:u32 }
+	
+	#[test]
+	fn flatten() {
+		let bi = StructBi::new("deployment")
+			.flatten(server_bi(), |d:&Deployment| &d.server, |d, v| d.server = v)
+			.field("replicas", Iden, |d:&Deployment| &d.replicas, |d, v| d.replicas = v);
+		let d = Deployment{ server:Server{ name:"a".into(), port:81, tags:vec!() }, replicas:2 };
+		assert_eq!(bi.woodify(&d).to_string(), "(deployment (name a) (port 81) (tags ()) (replicas 2))");
+		assert_eq!(bi.dewoodify(&bi.woodify(&d)).unwrap(), d);
+		let d2 = bi.dewoodify(&parse_termpose("deployment replicas:3 tags:() name:b").unwrap()).unwrap();
+		assert_eq!((d2.server.name.as_str(), d2.server.port, d2.replicas), ("b", 8080, 3));
+	}
+	
+	#[test]
+	fn versioned() {
+		//version 1 only had a name, version 2 had a name and a port, version 3 added tags
+		let v1 = StructBi::new("server").field("name", Iden, |s:&String| s, |s, v| *s = v);
+		let v2 = StructBi::new("server")
+			.field("name", Iden, |s:&(String, u16)| &s.0, |s, v| s.0 = v)
+			.field("port", Iden, |s:&(String, u16)| &s.1, |s, v| s.1 = v);
+		let v2_to_v3 = |(name, port):(String, u16)| Server{ name, port, tags:vec!() };
+		let bi = VersionedBi::new(3, server_bi())
+			.historical(1, v1, move |name| v2_to_v3((name, 80)))
+			.historical(2, v2, v2_to_v3);
+		let s = Server{ name:"a".into(), port:81, tags:vec!("x".into()) };
+		assert_eq!(bi.woodify(&s).to_string(), "(server (version 3) (name a) (port 81) (tags (x)))");
+		assert_eq!(bi.dewoodify(&bi.woodify(&s)).unwrap(), s);
+		assert_eq!(bi.dewoodify(&parse_termpose("server version:1 name:b").unwrap()).unwrap(), Server{ name:"b".into(), port:80, tags:vec!() });
+		assert_eq!(bi.dewoodify(&parse_termpose("server version:2 name:b port:9").unwrap()).unwrap(), Server{ name:"b".into(), port:9, tags:vec!() });
+		assert_eq!(bi.dewoodify(&parse_termpose("server version:4 name:b").unwrap()).unwrap_err().msg, "unsupported version 4, the current version is 3");
+		assert!(bi.dewoodify(&parse_termpose("server name:b").unwrap()).is_err());
+		//nothing to tag it with
+		let pair = VersionedBi::new(2, PositionalBi::new("pair")
+			.element(Iden, |p:&(Vec<u32>, u32)| &p.0, |p, v| p.0 = v)
+			.element(Iden, |p:&(Vec<u32>, u32)| &p.1, |p, v| p.1 = v));
+		let p = (vec!(1, 2), 3);
+		assert_eq!(pair.woodify(&p).to_string(), "((version 2) (1 2) 3)");
+		assert_eq!(pair.dewoodify(&pair.woodify(&p)).unwrap(), p);
+		let leaf = VersionedBi::new(1, Iden);
+		assert_eq!(leaf.woodify(&5u32).to_string(), "((version 1) 5)");
+		assert_eq!(leaf.dewoodify(&leaf.woodify(&5u32)).unwrap(), 5u32);
+	}
+	
+	#[test]
+	fn accumulating_errors() {
+		let es = dewoodify_all::<u32>(&parse_termpose("1 x 3 y").unwrap()).unwrap_err();
+		assert_eq!(es.len(), 2);
+		assert_eq!(es[0].column, 3);
+		assert_eq!(dewoodify_all::<u32>(&parse_termpose("1 2").unwrap()).unwrap(), vec!(1, 2));
+		let es = server_bi().dewoodify_all(&parse_termpose("server port:x tags:()").unwrap()).unwrap_err();
+		assert_eq!(es.len(), 2);
+		assert!(es[0].msg.contains("\"name\""));
+		assert!(es[1].msg.contains("\"port\""));
+	}
+	
+	#[test]
+	fn ordered_maps() {
+		let w = parse_termpose("zeta:1 alpha:2 mid:3").unwrap();
+		let m:Vec<(String, u32)> = OrderedMapBi(Iden, Iden).dewoodify(&w).unwrap();
+		assert_eq!(m, vec!(("zeta".into(), 1), ("alpha".into(), 2), ("mid".into(), 3)));
+		assert_eq!(OrderedMapBi(Iden, Iden).woodify(&m), w);
+	}
+	
+	#[test]
+	fn bool_words() {
+		assert_eq!(ON_OFF.woodify(&true).to_string(), "on");
+		assert_eq!(ON_OFF.dewoodify(&"off".into()).unwrap(), false);
+		assert!(ON_OFF.dewoodify(&"true".into()).is_err());
+		let lenient = BoolWords::new(&["yes", "y", "1"], &["no", "n", "0"]);
+		assert_eq!(lenient.woodify(&false).to_string(), "no");
+		assert_eq!(lenient.dewoodify(&"y".into()).unwrap(), true);
+		assert_eq!(lenient.dewoodify(&"maybe".into()).unwrap_err().msg, "expected one of yes, y, 1 or no, n, 0 here");
+		assert!(::std::panic::catch_unwind(|| BoolWords::new(&["yes"], &[])).is_err());
+	}
+	
+	#[test]
+	fn radix_ints() {
+		let hex = RadixInt(Radix::Hex);
+		assert_eq!(hex.woodify(&0xff00ffu32).to_string(), "0xff00ff");
+		assert_eq!(hex.woodify(&-16i8).to_string(), "-0x10");
+		assert_eq!(hex.woodify(&i8::min_value()).to_string(), "-0x80");
+		assert_eq!(RadixInt(Radix::Binary).woodify(&5u8).to_string(), "0b101");
+		for (s, n) in vec!(("0xFF_FF", 0xffff), ("0o17", 15), ("0b1010_1010", 170), ("1_000", 1000), ("-0x10", -16), ("42", 42)) {
+			let r:i64 = hex.dewoodify(&s.into()).unwrap();
+			assert_eq!(r, n);
+		}
+		assert_eq!(Dewooder::<i8>::dewoodify(&hex, &"-0x80".into()).unwrap(), -128);
+		assert!(Dewooder::<u8>::dewoodify(&hex, &"0x100".into()).is_err());
+		assert!(Dewooder::<u8>::dewoodify(&hex, &"0xg".into()).is_err());
+		assert!(Dewooder::<u8>::dewoodify(&hex, &"-1".into()).is_err());
+		assert_eq!(Dewooder::<u8>::dewoodify(&hex, &"+5".into()).unwrap(), 5);
+		for s in ["+-5", "-+5", "--5", "++5", "0x-5"].iter() {
+			assert!(Dewooder::<i32>::dewoodify(&hex, &(*s).into()).is_err(), "{}", s);
+		}
+	}
+	
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	enum Color { Red, Green, Blue }
+	
+	#[test]
+	fn string_enums() {
+		let bi = StringEnumBi(&[("red", Color::Red), ("green", Color::Green), ("blue", Color::Blue), ("azure", Color::Blue)]);
+		assert_eq!(bi.woodify(&Color::Blue).to_string(), "blue");
+		assert_eq!(bi.dewoodify(&"azure".into()).unwrap(), Color::Blue);
+		let cs:Vec<Color> = SequenceBi(bi).dewoodify(&parse_termpose("red green").unwrap()).unwrap();
+		assert_eq!(cs, vec!(Color::Red, Color::Green));
+		assert_eq!(bi.dewoodify(&"mauve".into()).unwrap_err().msg, "\"mauve\" isn't one of the accepted values: red, green, blue, azure");
+	}
+	
+	#[derive(Default, Debug, Clone, PartialEq)]
+	struct Menu { name:String, items:Vec<Menu> }
+	
+	#[test]
+	fn recursive() {
+		let bi = RecursiveBi::new(|menu| {
+			StructBi::new("menu")
+				.field("name", Iden, |m:&Menu| &m.name, |m, v| m.name = v)
+				.optional_field("items", SequenceBi(menu), vec!(), |m:&Menu| &m.items, |m, v| m.items = v)
+		});
+		let m = Menu{ name:"file".into(), items:vec!(
+			Menu{ name:"open".into(), items:vec!() },
+			Menu{ name:"recent".into(), items:vec!(Menu{ name:"a".into(), items:vec!() }) },
+		)};
+		assert_eq!(bi.dewoodify(&bi.woodify(&m)).unwrap(), m);
+		let d = bi.dewoodify(&parse_termpose("menu name:edit items:((menu name:undo) (menu name:redo))").unwrap()).unwrap();
+		assert_eq!(d.items[1].name, "redo");
+	}
+	
+	static PORTS:LazyBiwooder<BoundedSeqBi<Iden>> = LazyBiwooder::new(|| BoundedSeqBi::new(Iden, 1, 8));
+	
+	#[test]
+	fn lazy_biwooder() {
+		let ps:Vec<u16> = PORTS.dewoodify(&parse_termpose("80 443").unwrap()).unwrap();
+		assert_eq!(PORTS.woodify(&ps).to_string(), "(80 443)");
+		assert!(Dewooder::<Vec<u16>>::dewoodify(&PORTS, &Wood::branch(vec!())).is_err());
+	}
+	
+	#[test]
+	fn renamed_fields() {
+		let bi = RenameFields::new(&[("max_connections", "max-connections"), ("name", "server-name")], StructBi::new("server")
+			.field("name", Iden, |s:&(String, u32)| &s.0, |s, v| s.0 = v)
+			.field("max_connections", Iden, |s:&(String, u32)| &s.1, |s, v| s.1 = v));
+		let w = parse_termpose("server server-name:a max-connections:8").unwrap();
+		let s:(String, u32) = bi.dewoodify(&w).unwrap();
+		assert_eq!(s, ("a".into(), 8));
+		assert_eq!(bi.woodify(&s).to_string(), "(server (server-name a) (max-connections 8))");
+		let e = bi.dewoodify(&parse_termpose("server server-name:a max-connections:lots").unwrap()).unwrap_err();
+		assert_eq!(e.column, 38);
+	}
+	
+	#[test]
+	fn tagged() {
+		let bi = Tagged("list", SequenceBi(Iden));
+		let v:Vec<u32> = bi.dewoodify(&parse_termpose("list 1 2").unwrap()).unwrap();
+		assert_eq!(bi.woodify(&v), TaggedSequenceBi("list", Iden).woodify(&v));
+		let one:Vec<u32> = bi.dewoodify(&parse_termpose("list 1").unwrap()).unwrap();
+		assert_eq!(one, vec!(1));
+		let port = Tagged("port", Iden);
+		assert_eq!(port.woodify(&80u16).to_string(), "(port 80)");
+		assert_eq!(Dewooder::<u16>::dewoodify(&port, &parse_termpose("port 80").unwrap()).unwrap(), 80);
+		assert!(Dewooder::<u16>::dewoodify(&port, &parse_termpose("host 80").unwrap()).is_err());
+		let abc = parse_termpose("a b c").unwrap();
+		assert_eq!(ensure_tag(&abc, "a").unwrap().len(), 2);
+	}
+	
+	#[derive(Debug, PartialEq)]
+	struct UserId(u64);
+	
+	#[test]
+	fn transparent() {
+		let bi = TransparentBi::new(|u:&UserId| &u.0, UserId);
+		assert_eq!(bi.woodify(&UserId(7)).to_string(), "7");
+		assert_eq!(bi.dewoodify(&"8".into()).unwrap(), UserId(8));
+		let hex = TransparentBi::with(RadixInt(Radix::Hex), |u:&UserId| &u.0, UserId);
+		assert_eq!(hex.woodify(&UserId(255)).to_string(), "0xff");
+	}
+	
+	#[test]
+	fn wood_macro() {
+		let port = 8080u16;
+		let tags = vec!("a", "b");
+		let w = wood!{ server (host "example.com") (port {port}) (tags ..{&tags}) (ratio -0.5) () };
+		assert_eq!(w.to_string(), "(server (host example.com) (port 8080) (tags a b) (ratio -0.5) ())");
+		assert_eq!(w, parse_termpose("server host:example.com port:8080 tags(a b) ratio:-0.5 ()").unwrap());
+		assert_eq!(wood!(lone).to_string(), "lone");
+		assert_eq!(wood!({port + 1}).to_string(), "8081");
+		assert_eq!(wood!((..{tags.iter().rev()})).to_string(), "(b a)");
+		assert_eq!(wood!().to_string(), "()");
+	}
+	
+	#[test]
+	fn building() {
+		let w = Wood::branch_builder()
+			.leaf("server")
+			.pair("port", 8080)
+			.child(Wood::branch_builder().leaf("tags").values(vec!("a", "b")))
+			.child(branch!())
+			.build();
+		assert_eq!(w, wood!{ server (port 8080) (tags a b) () });
+		assert_eq!(WoodBuilder::new().value(true).build().to_string(), "(true)");
+	}
+	
+	#[test]
+	fn provenance() {
+		let here = line!() + 1;
+		let w = wood!{ server (port eighty) };
+		let e = dewoodify::<u16>(&w[1][1]).unwrap_err();
+		assert_eq!(e.line, -1);
+		assert_eq!(e.provenance.as_ref().unwrap().to_string(), format!("synthetic at {}:{}", file!(), here));
+		let parsed = parse_termpose("port 80").unwrap();
+		let mixed = Wood::branch_builder().leaf("server").child(parsed.clone()).provenance(provenance!()).build();
+		assert!(mixed[0].provenance().is_some());
+		assert_eq!(mixed[1][1].provenance(), None);
+		let src = Provenance::Source{ name:"defaults.term".into(), span:parsed.span() };
+		let built = Wood::branch_builder().pair("port", "x").provenance(src.clone()).build();
+		assert_eq!(built[0][1].provenance(), Some(&src));
+		assert_eq!(dewoodify::<u16>(&built[0][1]).unwrap_err().provenance.unwrap().to_string(), "defaults.term:1:1");
+		assert_eq!(branch!("a").provenance(), None);
+	}
+	
+	#[test]
+	fn files() {
+		let dir = ::std::env::temp_dir().join(format!("wood_files_test_{}", ::std::process::id()));
+		::std::fs::create_dir_all(&dir).unwrap();
+		let good = dir.join("good.term");
+		::std::fs::write(&good, "server\n  port 80\n").unwrap();
+		assert_eq!(parse_file(&good).unwrap(), parse_termpose("server port:80").unwrap());
+		let bad = dir.join("bad.term");
+		::std::fs::write(&bad, "a\n  b)\n").unwrap();
+		assert_eq!(parse_file(&bad).unwrap_err().to_string(), format!("{}:2:4: unmatched paren", bad.display()));
+		let wrong = dir.join("wrong.term");
+		::std::fs::write(&wrong, "1\nb\n3").unwrap();
+		assert_eq!(deserialize_file::<Vec<u8>, _>(&good).map_err(|_| ()), Err(()));
+		::std::fs::write(&good, "1 2 3").unwrap();
+		assert_eq!(deserialize_file::<Vec<u8>, _>(&good).unwrap(), vec!(1, 2, 3));
+		let e = deserialize_file::<Vec<u8>, _>(&wrong).unwrap_err().to_string();
+		assert!(e.starts_with(&format!("{}:2:1: ", wrong.display())), "{}", e);
+		assert!(parse_file(dir.join("missing.term")).unwrap_err().to_string().starts_with(&dir.join("missing.term").display().to_string()));
+		assert!(parse_termpose("a)").unwrap_err().file.is_none());
+		::std::fs::remove_dir_all(&dir).unwrap();
+	}
+	
+	#[test]
+	fn mutation() {
+		let mut w = parse_termpose("server host:a port:80").unwrap();
+		assert_eq!(w.replace(&["port"], wood!(port 81)), Some(wood!(port 80)));
+		assert_eq!(w.replace(&["tags"], wood!(tags)), None);
+		w.find_mut("host").unwrap().tail_mut().next().unwrap().set_initial("b");
+		w.push_child(wood!(tags x));
+		w.insert_child(1, wood!(name n));
+		assert_eq!(w.remove_child(0), wood!(server));
+		w.set_initial("ident");
+		assert_eq!(w, parse_termpose("ident:n host:b port:81 tags:x").unwrap());
+		//leaves act like a branch containing just themselves
+		let mut l:Wood = "a".into();
+		l.push_child("b".into());
+		assert_eq!(l, wood!(a b));
+		let mut e = wood!();
+		e.set_initial("first");
+		assert_eq!(e, wood!((first)));
+	}
+	
+	#[test]
+	fn accessors() {
+		let w = parse_termpose("server host:a port:80 ratio:0.5 on:true empty() tags:(x y)").unwrap();
+		assert_eq!(w.get_str("host").unwrap(), "a");
+		assert_eq!(w.get_i64("port").unwrap(), 80);
+		assert_eq!(w.get_f64("ratio").unwrap(), 0.5);
+		assert_eq!(w.get_bool("on").unwrap(), true);
+		assert_eq!(w.get_as::<u16>("port").unwrap(), 80);
+		assert_eq!(w.get_child("tags").unwrap(), &wood!(x y));
+		let e = w.get_bool("host").unwrap_err();
+		assert_eq!((e.line, e.column), (0, 13));
+		assert_eq!(w.get_child("missing").unwrap_err().msg, "there is no \"missing\" here");
+		assert_eq!(w.get_child("empty").unwrap_err().msg, "\"empty\" has no value");
+		assert!(w.get_str("tags").is_err());
+	}
+	
+	#[test]
+	fn get_path() {
+		let w = parse_multiline_termpose("
+server
+  port 80
+  tls
+    cert a.pem
+    tls b
+").unwrap();
+		assert_eq!(w.get_path("server.tls.cert").unwrap(), &wood!(cert "a.pem"));
+		assert_eq!(w.get_path("server/tls/tls").unwrap(), &wood!(tls b));
+		assert_eq!(w.get_path("server").unwrap().get_i64("port").unwrap(), 80);
+		let e = w.get_path("server.tls.key").unwrap_err();
+		assert_eq!((e.segment, e.key.as_str(), e.line), (2, "key", 3));
+		assert!(e.to_string().starts_with("4:"));
+		assert_eq!(wood!(a b).get_path("c").unwrap_err().to_string(), "nothing named \"c\" was found at segment 0 of the path");
+		let de:DewoodifyError = w.get_path("client").unwrap_err().into();
+		assert_eq!(de.msg, "nothing named \"client\" was found at segment 0 of the path");
+	}
+	
+	#[test]
+	fn matching() {
+		let p = parse_termpose("point 1.5 -2").unwrap();
+		let (x, y) = wood_match!(p, ("point" x y) => (x, y)).unwrap();
+		let _:(f64, i32) = (x, y);
+		assert_eq!((x, y), (1.5, -2));
+		let w = parse_termpose("line (start 0 0) (end 3 4) red dashed").unwrap();
+		let len = wood_match!(w, ("line" (_ x0 y0) ("end" x1 y1) &color ..rest) => {
+			let (x0, y0, x1, y1):(f64, f64, f64, f64) = (x0, y0, x1, y1);
+			assert_eq!((color.initial_str(), rest.len()), ("red", 1));
+			((x1 - x0)*(x1 - x0) + (y1 - y0)*(y1 - y0)).sqrt()
+		}).unwrap();
+		assert_eq!(len, 5.0);
+		let e = wood_match!(p, ("line" ..) => ()).unwrap_err();
+		assert_eq!(e.msg, "expected \"line\" here");
+		let e = wood_match!(p, ("point" x) => { let _:f64 = x; }).unwrap_err();
+		assert_eq!(e.msg, "expected 2 elements, but there were 3");
+		let e = wood_match!(p, (_ x y z) => { let _:(f64, f64, f64) = (x, y, z); }).unwrap_err();
+		assert_eq!(e.msg, "expected at least 4 elements, but there were 3");
+		let e = wood_match!(p, (_ _ x) => { let _:u8 = x; }).unwrap_err();
+		assert_eq!((e.msg.as_str(), e.column), ("couldn't parse \"-2\"", 11));
+	}
+	
+	#[test]
+	fn owned_iteration() {
+		let mut w = parse_termpose("names a b c").unwrap();
+		let names:Vec<String> = w.drain_tail().map(|n| match n { Leafv(l)=> l.v, _=> panic!() }).collect();
+		assert_eq!(names, vec!("a", "b", "c"));
+		assert_eq!(w, wood!((names)));
+		assert_eq!(w.drain_tail().count(), 0);
+		let mut l:Wood = "leaf".into();
+		assert_eq!(l.drain_tail().count(), 0);
+		let all:Vec<Wood> = parse_termpose("x (y z)").unwrap().into_iter().collect();
+		assert_eq!(all, vec!(wood!(x), wood!((y z))));
+		assert_eq!(l.into_iter().collect::<Vec<Wood>>(), vec!(wood!(leaf)));
+		let mut n = 0;
+		for _ in &parse_termpose("p q").unwrap() { n += 1; }
+		assert_eq!(n, 2);
+	}
+	
+	#[test]
+	fn indexing() {
+		let w = parse_termpose("server host:a (ports 80 81)").unwrap();
+		assert_eq!(w[0], wood!(server));
+		assert_eq!(w["ports"][2], wood!(81));
+		assert_eq!(w["host"][1][0], wood!(a));
+		assert_eq!(w.get(3), None);
+		assert_eq!(w.get_key("ports").and_then(|p| p.get(1)), Some(&wood!(80)));
+		assert_eq!(w.get_key("client"), None);
+	}
+	
+	#[test]
+	fn string_equality() {
+		let w = parse_termpose("server port:8080").unwrap();
+		assert_eq!(w["port"][1], "8080");
+		assert_eq!(w[0], *"server");
+		assert_eq!(w[0], "server".to_string());
+		assert!("server" == w[0] && "8080".to_string() == w["port"][1]);
+		assert!(w["port"] != "port");
+	}
+	
+	#[test]
+	fn collecting() {
+		let w:Wood = (1..4).map(|i:u8| i.woodify()).collect();
+		assert_eq!(w, wood!(1 2 3));
+		let mut l:Wood = "a b c".split(' ').collect();
+		assert_eq!(l, wood!(a b c));
+		l.extend(w.into_iter().take(2));
+		assert_eq!(l, wood!(a b c 1 2));
+		let mut leaf = wood!(x);
+		leaf.extend(vec!(wood!(y)));
+		assert_eq!(leaf, wood!(x y));
+	}
+	
+	#[test]
+	fn leaves_from_primitives() {
+		assert_eq!(branch!("port", 8080, true, 0.5f32, 'c', -3i64).to_string(), "(port 8080 true 0.5 c -3)");
+		assert_eq!(Wood::from(u128::MAX), u128::MAX.to_string());
+	}
+	
+	#[test]
+	fn from_str() {
+		let w:Wood = "a b:c".parse().unwrap();
+		assert_eq!(w, wood!(a (b c)));
+		assert_eq!("a b)".parse::<Wood>().unwrap_err().msg, "unmatched paren");
+	}
+	
+	#[test]
+	fn ordering() {
+		let mut v:Vec<Wood> = parse_termpose("(b a) b (a c d) a () (a c)").unwrap().into_iter().collect();
+		v.sort();
+		assert_eq!(Wood::branch(v).to_string(), "(a b () (a c) (a c d) (b a))");
+		let mut counts = BTreeMap::new();
+		*counts.entry(wood!(x y)).or_insert(0) += 1;
+		*counts.entry(parse_termpose("x y").unwrap()).or_insert(0) += 1;
+		assert_eq!(counts[&wood!(x y)], 2);
+	}
+	
+	#[test]
+	fn equality() {
+		let parsed = parse_termpose("a (b c)").unwrap();
+		let built = wood!(a (b c));
+		assert!(parsed.structurally_equals(&built) && parsed == built);
+		assert!(!parsed.identical(&built));
+		assert!(parsed.identical(&parse_termpose("a (b c)").unwrap()));
+		assert!(!parsed.identical(&parse_termpose("a  (b c)").unwrap()));
+		match (&parsed[1], &built[1]) {
+			(&Branchv(ref p), &Branchv(ref b))=> assert_eq!(p, b),
+			_=> panic!(),
+		}
+	}
+	
+	#[test]
+	fn canonical_hashing() {
+		let a = parse_termpose("a (b \"c\")").unwrap();
+		let b = parse_multiline_termpose("a\n  b c").unwrap();
+		assert_eq!(a.canonical_hash(), b[0].canonical_hash());
+		assert_eq!(a.canonical_hash(), wood!(a (b c)).canonical_hash());
+		assert!(a.canonical_hash() != wood!(a b c).canonical_hash());
+		assert!(wood!(ab).canonical_hash() != wood!((a b)).canonical_hash());
+		//pinned, so that it's noticed if it ever changes
+		assert_eq!(wood!().canonical_hash(), 0x529a2cdc8ff533ac);
+		let mut set = HashSet::new();
+		set.insert(a);
+		assert!(set.contains(&b[0]));
+	}
+	
+	#[test]
+	#[should_panic(expected = "there's nothing called \"client\"")]
+	fn indexing_nothing() {
+		let _ = &parse_termpose("server host:a").unwrap()["client"];
 	}
 	
 	#[test]
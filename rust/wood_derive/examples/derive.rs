@@ -1,6 +1,6 @@
 extern crate wood;
 extern crate wood_derive;
-use wood::{parse_termpose, pretty_termpose, Woodable, Dewoodable};
+use wood::{parse_termpose, pretty_termpose, wooder, Woodable, Dewoodable};
 use wood_derive::{Woodable, Dewoodable};
 
 #[derive(Woodable, Dewoodable, PartialEq, Debug)]
@@ -9,6 +9,52 @@ struct Dato {
 	b:bool,
 }
 
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Pair<T> {
+	left:T,
+	right:Vec<T>,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+struct Wrapped(u32, String);
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+enum Shape<T> {
+	Empty,
+	Circle(T),
+	Rect{ w:T, h:T },
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(rename_all = "kebab-case")]
+struct Config {
+	max_connections:u32,
+	#[wood(rename = "host")]
+	host_name:String,
+	#[wood(skip)]
+	cache:Vec<u8>,
+	#[wood(default)]
+	verbose:bool,
+	#[wood(with = wooder::ON_OFF)]
+	logging:bool,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(rename_all = "snake_case")]
+enum Level {
+	VeryLow,
+	#[wood(rename = "hi")]
+	High,
+}
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(transparent)]
+struct UserId(u64);
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(transparent)]
+struct Name { inner:String }
+
 fn main(){
 	let od = Dato{a:"chock".into(), b:true};
 	let s = pretty_termpose(&od.woodify());
@@ -18,4 +64,36 @@ fn main(){
 	let d = Dato::dewoodify(&parse_termpose(&s).unwrap()).unwrap();
 	
 	assert_eq!(&od, &d);
-}
\ No newline at end of file
+	
+	let p = Pair{ left:1u8, right:vec!(2, 3) };
+	assert_eq!("Pair left:1 right:2:3", &pretty_termpose(&p.woodify()));
+	assert_eq!(p, Pair::dewoodify(&p.woodify()).unwrap());
+	
+	let w = Wrapped(4, "four".into());
+	assert_eq!(w, Wrapped::dewoodify(&w.woodify()).unwrap());
+	
+	//the tag is checked
+	assert!(Dato::dewoodify(&parse_termpose("Data a:chock b:true").unwrap()).is_err());
+	let e = Dato::dewoodify(&parse_termpose("Dato a:chock b:maybe").unwrap()).unwrap_err();
+	assert!(e.msg.starts_with("in field \"b\""));
+	
+	for s in vec!(Shape::Empty, Shape::Circle(2.5), Shape::Rect{ w:1.0, h:2.0 }) {
+		assert_eq!(s, Shape::dewoodify(&s.woodify()).unwrap());
+	}
+	assert_eq!("Empty", &pretty_termpose(&Shape::Empty::<f32>.woodify()));
+	assert_eq!("Circle 2.5", &pretty_termpose(&Shape::Circle(2.5f32).woodify()));
+	let e = Shape::<f32>::dewoodify(&parse_termpose("Triangle 1 2 3").unwrap()).unwrap_err();
+	assert_eq!(e.msg, "unknown variant \"Triangle\" of Shape, expected one of: Empty, Circle, Rect");
+	
+	let c = Config{ max_connections:8, host_name:"a".into(), cache:vec!(1), verbose:true, logging:false };
+	assert_eq!("Config max-connections:8 host:a verbose:true logging:off", &pretty_termpose(&c.woodify()));
+	let d = Config::dewoodify(&parse_termpose("Config host:b logging:on max-connections:2").unwrap()).unwrap();
+	assert_eq!(d, Config{ max_connections:2, host_name:"b".into(), cache:vec!(), verbose:false, logging:true });
+	
+	assert_eq!("very_low", &pretty_termpose(&Level::VeryLow.woodify()));
+	assert_eq!(Level::High, Level::dewoodify(&parse_termpose("hi").unwrap()).unwrap());
+	
+	assert_eq!("7", &pretty_termpose(&UserId(7).woodify()));
+	assert_eq!(UserId(7), UserId::dewoodify(&parse_termpose("7").unwrap()).unwrap());
+	assert_eq!(Name{ inner:"n".into() }, Name::dewoodify(&Name{ inner:"n".into() }.woodify()).unwrap());
+}
@@ -3,71 +3,395 @@
 
 extern crate proc_macro;
 extern crate proc_macro2;
-extern crate wood;
 
 use proc_macro::TokenStream;
 use proc_macro2::{Span, TokenStream as PM2TS};
 use quote::quote;
 use syn::{Ident, Data::{Struct, Enum, Union}, Fields::{Named, Unnamed, Unit}};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 
 
+//every type parameter gets the given bound, so that generic structs can be derived for
+fn bounded_generics(generics:&syn::Generics, bound:syn::TypeParamBound)-> syn::Generics {
+	let mut ret = generics.clone();
+	for p in ret.type_params_mut() {
+		p.bounds.push(bound.clone());
+	}
+	ret
+}
+
+
+//the contents of a #[wood(...)] attribute
+enum WoodAttrValue { Flag, Str(syn::LitStr), Path(syn::Path) }
+struct WoodAttr { key:Ident, value:WoodAttrValue }
+impl Parse for WoodAttr {
+	fn parse(input:ParseStream)-> syn::Result<Self> {
+		let key:Ident = input.parse()?;
+		let value = if input.peek(syn::Token![=]) {
+			input.parse::<syn::Token![=]>()?;
+			if input.peek(syn::LitStr) {
+				WoodAttrValue::Str(input.parse()?)
+			}else{
+				WoodAttrValue::Path(input.parse()?)
+			}
+		}else{
+			WoodAttrValue::Flag
+		};
+		Ok(WoodAttr{ key, value })
+	}
+}
+struct WoodAttrList(Vec<WoodAttr>);
+impl Parse for WoodAttrList {
+	fn parse(input:ParseStream)-> syn::Result<Self> {
+		let content;
+		syn::parenthesized!(content in input);
+		Ok(WoodAttrList(Punctuated::<WoodAttr, syn::Token![,]>::parse_terminated(&content)?.into_iter().collect()))
+	}
+}
+fn wood_attrs(attrs:&[syn::Attribute])-> syn::Result<Vec<WoodAttr>> {
+	let mut ret = Vec::new();
+	for a in attrs.iter().filter(|a| a.path.is_ident("wood")) {
+		if a.tts.is_empty() {
+			return Err(syn::Error::new_spanned(a, "expected #[wood(...)]"));
+		}
+		ret.extend(syn::parse2::<WoodAttrList>(a.tts.clone())?.0);
+	}
+	Ok(ret)
+}
+
+#[derive(Default)]
+struct ContainerAttrs {
+	rename_all: Option<String>,
+	//the `transparent` of #[wood(transparent)], for pointing at when it can't be done
+	transparent: Option<Ident>,
+}
+impl ContainerAttrs {
+	fn from(attrs:&[syn::Attribute])-> syn::Result<Self> {
+		let mut ret = ContainerAttrs::default();
+		for a in wood_attrs(attrs)? {
+			match (a.key.to_string().as_str(), a.value) {
+				("rename_all", WoodAttrValue::Str(s))=> {
+					if convert_case("check", &s.value()).is_none() {
+						return Err(syn::Error::new(s.span(), format!("#[wood(rename_all = \"{}\")] isn't a case wood_derive knows", s.value())));
+					}
+					ret.rename_all = Some(s.value());
+				}
+				("transparent", WoodAttrValue::Flag)=> ret.transparent = Some(a.key),
+				(k, _)=> return Err(syn::Error::new(a.key.span(), format!("unknown or malformed #[wood({})] on a type", k))),
+			}
+		}
+		Ok(ret)
+	}
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+	rename: Option<String>,
+	skip: bool,
+	default: bool,
+	with: Option<syn::Path>,
+}
+impl FieldAttrs {
+	fn from(attrs:&[syn::Attribute])-> syn::Result<Self> {
+		let mut ret = FieldAttrs::default();
+		for a in wood_attrs(attrs)? {
+			match (a.key.to_string().as_str(), a.value) {
+				("rename", WoodAttrValue::Str(s))=> ret.rename = Some(s.value()),
+				("skip", WoodAttrValue::Flag)=> ret.skip = true,
+				("default", WoodAttrValue::Flag)=> ret.default = true,
+				("with", WoodAttrValue::Path(p))=> ret.with = Some(p),
+				(k, _)=> return Err(syn::Error::new(a.key.span(), format!("unknown or malformed #[wood({})] on a field or variant", k))),
+			}
+		}
+		Ok(ret)
+	}
+	fn of_each<'a, I:Iterator<Item=&'a syn::Field>>(fields:I)-> syn::Result<Vec<FieldAttrs>> {
+		fields.map(|f| FieldAttrs::from(&f.attrs)).collect()
+	}
+	fn key(&self, ident:&Ident, rename_all:&Option<String>)-> String {
+		if let Some(ref r) = self.rename { return r.clone(); }
+		let name = ident.to_string();
+		match *rename_all {
+			Some(ref case)=> convert_case(&name, case).unwrap(),
+			None=> name,
+		}
+	}
+}
+
+//identifiers are either snake_case (fields) or PascalCase (variants), so they're split on underscores and on capitals
+fn convert_case(ident:&str, case:&str)-> Option<String> {
+	let mut words:Vec<String> = Vec::new();
+	for part in ident.split('_').filter(|p| p.len() > 0) {
+		let mut cur = String::new();
+		for c in part.chars() {
+			if c.is_uppercase() && cur.len() > 0 {
+				words.push(cur);
+				cur = String::new();
+			}
+			cur.extend(c.to_lowercase());
+		}
+		if cur.len() > 0 { words.push(cur); }
+	}
+	let capitalized = |w:&String|-> String {
+		let mut cs = w.chars();
+		match cs.next() {
+			Some(f)=> f.to_uppercase().chain(cs).collect(),
+			None=> String::new(),
+		}
+	};
+	Some(match case {
+		"lowercase"=> words.concat(),
+		"UPPERCASE"=> words.concat().to_uppercase(),
+		"PascalCase"=> words.iter().map(capitalized).collect::<Vec<_>>().concat(),
+		"camelCase"=> words.iter().enumerate().map(|(i, w)| if i == 0 { w.clone() } else { capitalized(w) }).collect::<Vec<_>>().concat(),
+		"snake_case"=> words.join("_"),
+		"SCREAMING_SNAKE_CASE"=> words.join("_").to_uppercase(),
+		"kebab-case"=> words.join("-"),
+		"SCREAMING-KEBAB-CASE"=> words.join("-").to_uppercase(),
+		_=> return None,
+	})
+}
 
-#[proc_macro_derive(Woodable)]
+
+//`(key value)` for a named field, given an expression that borrows the field. None if the field is skipped
+fn woodify_named_field(key:&str, field_ref:PM2TS, attrs:&FieldAttrs)-> Option<PM2TS> {
+	if attrs.skip { return None; }
+	let value = match attrs.with {
+		Some(ref p)=> quote!{ ::wood::Wooder::woodify(&#p, #field_ref) },
+		None=> quote!{ ::wood::Woodable::woodify(#field_ref) },
+	};
+	Some(quote!{
+		::wood::branch!(
+			#key,
+			#value,
+		)
+	})
+}
+
+//what deriving Dewoodable and DewoodableOwned differ in. The owned one takes each field's wood out of the wood it's given and hands it over, rather than lending it
+#[derive(Clone, Copy, PartialEq)]
+enum Reading { Borrowed, Owned }
+impl Reading {
+	fn trait_name(self)-> &'static str {
+		match self { Reading::Borrowed=> "Dewoodable", Reading::Owned=> "DewoodableOwned" }
+	}
+	fn trait_path(self)-> syn::TypeParamBound {
+		match self {
+			Reading::Borrowed=> syn::parse_quote!(::wood::Dewoodable),
+			Reading::Owned=> syn::parse_quote!(::wood::DewoodableOwned),
+		}
+	}
+	//the trait's method with the given body, in which the wood is `v`
+	fn method(self, body:PM2TS)-> PM2TS {
+		match self {
+			Reading::Borrowed=> quote!{ fn dewoodify(v:&::wood::Wood)-> Result<Self, ::wood::DewoodifyError> { #body } },
+			Reading::Owned=> quote!{ fn dewoodify_owned(v: ::wood::Wood)-> Result<Self, ::wood::DewoodifyError> { #body } },
+		}
+	}
+	//`v` as a &Wood
+	fn v_ref(self)-> PM2TS {
+		match self { Reading::Borrowed=> quote!(v), Reading::Owned=> quote!(&v) }
+	}
+	//dewoodifies the wood w, which is a &Wood or a Wood depending, into a ty
+	fn convert(self, ty:&syn::Type, with:&Option<syn::Path>, w:PM2TS)-> PM2TS {
+		match (self, with) {
+			(Reading::Borrowed, &Some(ref p))=> quote!{ ::wood::Dewooder::<#ty>::dewoodify(&#p, #w) },
+			(Reading::Owned, &Some(ref p))=> quote!{ ::wood::Dewooder::<#ty>::dewoodify(&#p, &#w) },
+			(Reading::Borrowed, &None)=> quote!{ <#ty as ::wood::Dewoodable>::dewoodify(#w) },
+			(Reading::Owned, &None)=> quote!{ <#ty as ::wood::DewoodableOwned>::dewoodify_owned(#w) },
+		}
+	}
+	//a FieldScanning or FieldTaking over v, which dewoodify_named_field expects to be called `scanning`
+	fn scanning(self)-> PM2TS {
+		match self {
+			Reading::Borrowed=> quote!{ ::wood::wooder::FieldScanning::new(v) },
+			Reading::Owned=> quote!{ ::wood::wooder::FieldTaking::new(v) },
+		}
+	}
+	fn scanned_len(self)-> PM2TS {
+		match self { Reading::Borrowed=> quote!(scanning.li.len()), Reading::Owned=> quote!(scanning.len()) }
+	}
+	//binds the tail of v to `li`, for tuple fields to read in order through tuple_field
+	fn tail(self)-> PM2TS {
+		match self {
+			Reading::Borrowed=> quote!{ let li = v.tail().as_slice(); },
+			Reading::Owned=> quote!{ let mut li = v.into_iter().skip(1); },
+		}
+	}
+	fn tuple_field(self, i:usize)-> PM2TS {
+		match self { Reading::Borrowed=> quote!(&li[#i]), Reading::Owned=> quote!(li.next().unwrap()) }
+	}
+}
+
+//`ident: value` for a struct literal, reading the value out of a FieldScanning or FieldTaking named `scanning`
+fn dewoodify_named_field(ident:&Ident, ty:&syn::Type, key:&str, attrs:&FieldAttrs, reading:Reading)-> PM2TS {
+	if attrs.skip {
+		return quote!{ #ident: Default::default() };
+	}
+	let conversion = reading.convert(ty, &attrs.with, quote!(fw));
+	let with_context = match reading {
+		Reading::Borrowed=> quote!{
+			#conversion.map_err(|e|{
+				::wood::DewoodifyError::new_with_cause(fw, format!("in field \"{}\": {}", #key, e.msg), Some(Box::new(e)))
+			})?
+		},
+		//fw is gone by the time there's an error, so where it was is noted first
+		Reading::Owned=> quote!{{
+			let ((line, column), provenance) = (fw.line_and_col(), fw.provenance_arc().cloned());
+			#conversion.map_err(|e|{
+				::wood::DewoodifyError::located(line, column, format!("in field \"{}\": {}", #key, e.msg)).with_provenance(provenance).with_cause(Box::new(e))
+			})?
+		}},
+	};
+	let (seek, seek_opt) = match reading {
+		Reading::Borrowed=> (quote!(seek), quote!(seek_opt)),
+		Reading::Owned=> (quote!(take), quote!(take_opt)),
+	};
+	if attrs.default {
+		quote!{
+			#ident: match scanning.#seek_opt(#key)? {
+				Some(fw)=> #with_context,
+				None=> Default::default(),
+			}
+		}
+	}else{
+		quote!{
+			#ident: {
+				let fw = scanning.#seek(#key)?;
+				#with_context
+			}
+		}
+	}
+}
+
+//checks that there are as many fields as there should be, bearing in mind that skipped fields are never there and default fields might not be
+fn field_count_check(what:PM2TS, fields:&[FieldAttrs], reading:Reading)-> PM2TS {
+	let most = fields.iter().filter(|a| !a.skip).count();
+	let least = fields.iter().filter(|a| !a.skip && !a.default).count();
+	let len = reading.scanned_len();
+	//the scanning has v by now, if it was given to it
+	let v = match reading { Reading::Borrowed=> quote!(v), Reading::Owned=> quote!(&scanning.v) };
+	if least == most {
+		quote!{
+			if #len != #most {
+				return Err(::wood::DewoodifyError::new(#v, format!("{} expected the wood to have {} elements, but it has {}", #what, #most, #len)));
+			}
+		}
+	}else{
+		quote!{
+			if #len < #least || #len > #most {
+				return Err(::wood::DewoodifyError::new(#v, format!("{} expected the wood to have between {} and {} elements, but it has {}", #what, #least, #most, #len)));
+			}
+		}
+	}
+}
+
+//for #[wood(transparent)], the one field of a newtype, as something that can follow `self.`. at is the attribute's `transparent`
+fn transparent_field(data:&syn::Data, at:&Ident)-> syn::Result<(PM2TS, syn::Type)> {
+	let fields = match *data {
+		Struct(ref s)=> &s.fields,
+		_=> return Err(syn::Error::new(at.span(), "#[wood(transparent)] can only be used on structs")),
+	};
+	if fields.iter().count() != 1 {
+		return Err(syn::Error::new(at.span(), "#[wood(transparent)] needs a struct with exactly one field"));
+	}
+	let f = fields.iter().next().unwrap();
+	let member = match f.ident {
+		Some(ref id)=> quote!{ #id },
+		None=> {
+			let id = syn::Index::from(0);
+			quote!{ #id }
+		}
+	};
+	Ok((member, f.ty.clone()))
+}
+
+fn unnamed_field_attrs(fields:&syn::FieldsUnnamed)-> syn::Result<Vec<FieldAttrs>> {
+	fields.unnamed.iter().map(|f|{
+		let a = FieldAttrs::from(&f.attrs)?;
+		if a.rename.is_some() || a.skip || a.default {
+			let at = f.attrs.iter().find(|a| a.path.is_ident("wood")).unwrap();
+			return Err(syn::Error::new_spanned(at, "only #[wood(with = ...)] is supported on tuple fields"));
+		}
+		Ok(a)
+	}).collect()
+}
+
+
+
+#[proc_macro_derive(Woodable, attributes(wood))]
 pub fn woodable_derive(input: TokenStream) -> TokenStream {
 	let ast: syn::DeriveInput = syn::parse(input).unwrap();
+	woodable_impl(&ast).unwrap_or_else(|e| e.to_compile_error()).into()
+}
 
+fn woodable_impl(ast:&syn::DeriveInput)-> syn::Result<PM2TS> {
 	let name = &ast.ident;
+	let generics = bounded_generics(&ast.generics, syn::parse_quote!(::wood::Woodable));
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+	let container = ContainerAttrs::from(&ast.attrs)?;
 	
-	let ret: TokenStream = match ast.data {
+	if let Some(ref at) = container.transparent {
+		let (field, _) = transparent_field(&ast.data, at)?;
+		return Ok(quote! {
+			impl #impl_generics ::wood::Woodable for #name #ty_generics #where_clause {
+				fn woodify(&self)-> ::wood::Wood {
+					::wood::Woodable::woodify(&self.#field)
+				}
+			}
+		});
+	}
+	
+	Ok(match ast.data {
 		Struct(ref s)=> {
 			let woodify_branch_contents:Vec<PM2TS> = match s.fields {
 				Named(ref n)=> {
-					n.named.iter().map(|m:&syn::Field|{
-						let mid = &m.ident.as_ref().unwrap();
-						quote!{
-							wood::branch!(
-								stringify!(#mid),
-								self.#mid.woodify(),
-							)
-						}
+					n.named.iter().zip(FieldAttrs::of_each(n.named.iter())?).filter_map(|(m, attrs)|{
+						let mid = m.ident.as_ref().unwrap();
+						woodify_named_field(&attrs.key(mid, &container.rename_all), quote!{ &self.#mid }, &attrs)
 					}).collect()
 				},
 				Unnamed(ref n)=> {
-					n.unnamed.iter().enumerate().map(|(i, _m)|{
+					n.unnamed.iter().zip(unnamed_field_attrs(n)?).enumerate().map(|(i, (_m, attrs))|{
 						let id = syn::Index::from(i);
-						quote!{ self.#id.woodify() }
+						match attrs.with {
+							Some(ref p)=> quote!{ ::wood::Wooder::woodify(&#p, &self.#id) },
+							None=> quote!{ ::wood::Woodable::woodify(&self.#id) },
+						}
 					}).collect()
 				},
 				Unit=> {
-					vec!(quote!{})
+					vec!()
 				}
 			};
 			
 			let tit = woodify_branch_contents.into_iter();
 			
-			(quote! {
-				impl wood::Woodable for #name {
-					fn woodify(&self)-> wood::Wood {
-						wood::branch!(
+			quote! {
+				impl #impl_generics ::wood::Woodable for #name #ty_generics #where_clause {
+					fn woodify(&self)-> ::wood::Wood {
+						::wood::branch!(
 							stringify!(#name),
 							#(#tit),*
 						)
 					}
 				}
-			}).into()
+			}
 		},
 		
 		
 		Enum(ref e)=> {
 			let variant_cases:Vec<PM2TS> = e.variants.iter().map(|m:&syn::Variant|{
 				let variant_name = &m.ident;
+				let variant_key = FieldAttrs::from(&m.attrs)?.key(variant_name, &container.rename_all);
 				
-				//left is just the name, right is the 
+				//left is just the name, right is the
 				let has_fields = |bindings: PM2TS, woodifications:PM2TS|{
 					quote!{
 						#name::#variant_name #bindings => {
-							wood::branch!(
-								stringify!(#variant_name),
+							::wood::branch!(
+								#variant_key,
 								#woodifications
 							)
 						}
@@ -75,33 +399,35 @@ pub fn woodable_derive(input: TokenStream) -> TokenStream {
 				};
 				
 				
-				match m.fields {
+				Ok(match m.fields {
 					Named(ref fs)=>{
-						let bindvec:Vec<PM2TS> = fs.named.iter().map(|m:&syn::Field|{
+						let attrs = FieldAttrs::of_each(fs.named.iter())?;
+						let bindvec:Vec<PM2TS> = fs.named.iter().zip(attrs.iter()).filter(|&(_, a)| !a.skip).map(|(m, _)|{
 							let id = &m.ident;
 							quote!{ ref #id }
 						}).collect();
-						let woodvec:Vec<PM2TS> = fs.named.iter().map(|m:&syn::Field|{
+						let woodvec:Vec<PM2TS> = fs.named.iter().zip(attrs.iter()).filter_map(|(m, a)|{
 							let fid = m.ident.as_ref().unwrap();
-							quote!{
-								wood::branch!(
-									stringify!(#fid),
-									#fid.woodify(),
-								)
-							}
+							woodify_named_field(&a.key(fid, &container.rename_all), quote!{ #fid }, a)
 						}).collect();
 						
 						has_fields(
-							quote!{ { #(#bindvec),* } },
+							quote!{ { #(#bindvec,)* .. } },
 							quote!{ #(#woodvec),* }
 						)
 					},
 					Unnamed(ref fs)=>{
+						let attrs = unnamed_field_attrs(fs)?;
 						let names:Vec<Ident> = (0..fs.unnamed.len()).map(|i|{
 							Ident::new(format!("v{}", i).as_str(), Span::call_site())
 						}).collect();
 						let nam = names.iter().map(|id| quote!{ ref #id });
-						let woodvec = names.iter().map(|s| quote!{ #s.woodify() });
+						let woodvec = names.iter().zip(attrs.iter()).map(|(s, a)|{
+							match a.with {
+								Some(ref p)=> quote!{ ::wood::Wooder::woodify(&#p, #s) },
+								None=> quote!{ ::wood::Woodable::woodify(#s) },
+							}
+						});
 						has_fields(
 							quote!{ ( #(#nam),* ) },
 							quote!{ #(#woodvec),* },
@@ -109,28 +435,26 @@ pub fn woodable_derive(input: TokenStream) -> TokenStream {
 					},
 					Unit =>{
 						quote! {
-							#name::#variant_name => stringify!(#variant_name).into()
+							#name::#variant_name => #variant_key.into()
 						}
 					}
-				}
-			}).collect();
+				})
+			}).collect::<syn::Result<_>>()?;
 			
-			(quote! {
-				impl wood::Woodable for #name {
-					fn woodify(&self)-> wood::Wood {
+			quote! {
+				impl #impl_generics ::wood::Woodable for #name #ty_generics #where_clause {
+					fn woodify(&self)-> ::wood::Wood {
 					 	match *self {
 							#(#variant_cases),*
 						}
 					}
 				}
-			}).into()
+			}
 		},
-		Union(_)=> {
-			panic!("derive(Woodable) cannot support untagged unions. It would have no way of knowing which variant to read from")
+		Union(ref u)=> {
+			return Err(syn::Error::new_spanned(u.union_token, "derive(Woodable) cannot support untagged unions. It would have no way of knowing which variant to read from"));
 		}
-	};
-	
-	ret
+	})
 }
 
 
@@ -143,19 +467,54 @@ pub fn woodable_derive(input: TokenStream) -> TokenStream {
 #[proc_macro_derive(Dewoodable, attributes(wood))]
 pub fn dewoodable_derive(input: TokenStream) -> TokenStream {
 	let ast: syn::DeriveInput = syn::parse(input).unwrap();
+	dewoodable_impl(&ast, Reading::Borrowed).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+#[proc_macro_derive(DewoodableOwned, attributes(wood))]
+pub fn dewoodable_owned_derive(input: TokenStream) -> TokenStream {
+	let ast: syn::DeriveInput = syn::parse(input).unwrap();
+	dewoodable_impl(&ast, Reading::Owned).unwrap_or_else(|e| e.to_compile_error()).into()
+}
 
+fn dewoodable_impl(ast:&syn::DeriveInput, reading:Reading)-> syn::Result<PM2TS> {
 	let name = &ast.ident;
+	let generics = bounded_generics(&ast.generics, reading.trait_path());
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+	let trait_path = reading.trait_path();
+	let v = reading.v_ref();
+	let container = ContainerAttrs::from(&ast.attrs)?;
+	
+	if let Some(ref at) = container.transparent {
+		let (field, ty) = transparent_field(&ast.data, at)?;
+		let conversion = reading.convert(&ty, &None, quote!(v));
+		let method = reading.method(quote!{ Ok(#name{ #field: #conversion? }) });
+		return Ok(quote! {
+			impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+				#method
+			}
+		});
+	}
+	
+	//the fields of a tuple struct or variant, which are read in order out of `li`
+	let tuple_fields = |n:&syn::FieldsUnnamed|-> syn::Result<Vec<PM2TS>> {
+		Ok(n.unnamed.iter().zip(unnamed_field_attrs(n)?).enumerate().map(|(i, (m, a))|{
+			let conversion = reading.convert(&m.ty, &a.with, reading.tuple_field(i));
+			quote!{ #conversion? }
+		}).collect())
+	};
 	
-	let ret = TokenStream::from( match ast.data {
+	Ok(match ast.data {
 		Struct(ref s)=> {
 			
 			let with_body = |method_body|-> PM2TS {
 				//it seeks over the contents of the wood branch in such way where if the items are in order it will find each one immediately
+				let method = reading.method(quote!{
+					let _ = ::wood::wooder::ensure_tag(#v, stringify!(#name))?;
+					#method_body
+				});
 				quote! {
-					impl wood::Dewoodable for #name {
-						fn dewoodify(v:&wood::Wood)-> Result<Self, wood::DewoodifyError> {
-							#method_body
-						}
+					impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+						#method
 					}
 				}
 			};
@@ -164,25 +523,23 @@ pub fn dewoodable_derive(input: TokenStream) -> TokenStream {
 				Named(ref n)=> {
 					
 					if n.named.len() == 0 {
-						with_body(quote! { Self{} })
+						with_body(quote! { Ok(Self{}) })
 					}else{
-					
-						let var_parsing:Vec<PM2TS> = n.named.iter().map(|m:&syn::Field|{
-							let var_ident = &m.ident.as_ref().unwrap();
-							let var_type = &m.ty;
-							quote!{
-								
-								#var_ident: {type T = #var_type; T::dewoodify(scanning.seek(stringify!(#var_ident))?)?}
-							}
+						
+						let attrs = FieldAttrs::of_each(n.named.iter())?;
+						
+						let var_parsing:Vec<PM2TS> = n.named.iter().zip(attrs.iter()).map(|(m, a)|{
+							let var_ident = m.ident.as_ref().unwrap();
+							dewoodify_named_field(var_ident, &m.ty, &a.key(var_ident, &container.rename_all), a, reading)
 						}).collect();
 						
-						let number_of_fields = var_parsing.len();
+						let count_check = field_count_check(quote!{ stringify!(#name) }, &attrs, reading);
+						let scanning = reading.scanning();
 						
 						with_body(quote!{
-							let mut scanning = wood::wooder::FieldScanning::new(v);
-							if scanning.li.len() != #number_of_fields {
-								return Err(wood::DewoodifyError::new(v, format!("{} expected the wood to have {} elements, but it has {}", stringify!(#name), #number_of_fields, scanning.li.len())));
-							}
+							#[allow(unused_mut, unused_variables)]
+							let mut scanning = #scanning;
+							#count_check
 							
 							Ok(Self{
 								#(#var_parsing),*
@@ -194,19 +551,16 @@ pub fn dewoodable_derive(input: TokenStream) -> TokenStream {
 				Unnamed(ref n)=> {
 					
 					let number_of_fields = n.unnamed.len();
-					
-					let each_field = n.unnamed.iter().enumerate().map(|(i, m)|{
-						let ty = &m.ty;
-						quote!{#ty::dewoodify(&li[#i])?}
-					});
+					let each_field = tuple_fields(n)?;
+					let tail = reading.tail();
 					
 					with_body(quote!{
-						let li = v.tail().as_slice();
-						if li.len() == #number_of_fields {
-							Ok(Self(#(#each_field),*))
-						}else{
-							Err(wood::DewoodifyError::new(v, format!("{} expected the wood to have {} fields, but it has {}", stringify!(#name), #number_of_fields, li.len())))
+						let len = v.tail().len();
+						if len != #number_of_fields {
+							return Err(::wood::DewoodifyError::new(#v, format!("{} expected the wood to have {} fields, but it has {}", stringify!(#name), #number_of_fields, len)));
 						}
+						#tail
+						Ok(Self(#(#each_field),*))
 					})
 				},
 				Unit=> {
@@ -220,24 +574,26 @@ pub fn dewoodable_derive(input: TokenStream) -> TokenStream {
 		
 		Enum(ref e)=> {
 			
-			let variant_cases:Vec<PM2TS> = e.variants.iter().map(|m:&syn::Variant|{
+			let variant_keys:Vec<String> = e.variants.iter().map(|m| Ok(FieldAttrs::from(&m.attrs)?.key(&m.ident, &container.rename_all))).collect::<syn::Result<_>>()?;
+			
+			let variant_cases:Vec<PM2TS> = e.variants.iter().zip(variant_keys.iter()).map(|(m, variant_key)|{
 				let variant_name = &m.ident;
 				
 				let for_fields:PM2TS = match m.fields {
 					Named(ref n)=> {
-						let each_feild:Vec<PM2TS> = n.named.iter().map(|f:&syn::Field|{
-							let id = &f.ident;
-							let ty = &f.ty;
-							quote!{ #id: #ty::dewoodify(scanning.seek(stringify!(#id))?)? }
+						let attrs = FieldAttrs::of_each(n.named.iter())?;
+						let each_feild:Vec<PM2TS> = n.named.iter().zip(attrs.iter()).map(|(f, a)|{
+							let id = f.ident.as_ref().unwrap();
+							dewoodify_named_field(id, &f.ty, &a.key(id, &container.rename_all), a, reading)
 						}).collect();
 						
-						let number_of_fields = each_feild.len();
+						let count_check = field_count_check(quote!{ format!("variant {}", #variant_key) }, &attrs, reading);
+						let scanning = reading.scanning();
 						
 						quote!{
-							let mut scanning = wooder::FieldScanning::new(v);
-							if scanning.li.len() != #number_of_fields {
-								return Err(wood::DewoodifyError::new(v, format!("variant {} expected {} elements, found {}", stringify!(#variant_name), #number_of_fields, scanning.li.len())));
-							}
+							#[allow(unused_mut, unused_variables)]
+							let mut scanning = #scanning;
+							#count_check
 							Ok(#name::#variant_name {
 								#(#each_feild),*
 							})
@@ -245,20 +601,18 @@ pub fn dewoodable_derive(input: TokenStream) -> TokenStream {
 					},
 					Unnamed(ref n)=> {
 						
-						let each_feild = n.unnamed.iter().enumerate().map(|(i,m)|{
-							let ty = &m.ty;
-							quote!{ #ty::dewoodify(&li[#i])? }
-						});
-						
-						let number_of_fields = each_feild.len();
+						let each_feild = tuple_fields(n)?;
+						let number_of_fields = n.unnamed.len();
+						let tail = reading.tail();
 						
 						quote!{
-							let li = v.tail().as_slice();
+							let len = v.tail().len();
 							
-							if li.len() != #number_of_fields {
-								return Err(wood::DewoodifyError::new(v, format!("variant {} expected {} elements, found {}", stringify!(#variant_name), #number_of_fields, li.len())));
+							if len != #number_of_fields {
+								return Err(::wood::DewoodifyError::new(#v, format!("variant {} expected {} elements, found {}", #variant_key, #number_of_fields, len)));
 							}
 							
+							#tail
 							Ok(#name::#variant_name(
 								#(#each_feild),*
 							))
@@ -269,32 +623,64 @@ pub fn dewoodable_derive(input: TokenStream) -> TokenStream {
 					}
 				};
 				
-				quote!{
-					stringify!(#variant_name)=> {
+				Ok(quote!{
+					#variant_key=> {
 						#for_fields
 					}
+				})
+			}).collect::<syn::Result<_>>()?;
+			
+			let variant_names = variant_keys.join(", ");
+			let method = reading.method(quote!{
+				match v.initial_str() {
+					#(#variant_cases,)*
+					erc => Err(::wood::DewoodifyError::new(#v, format!("unknown variant \"{}\" of {}, expected one of: {}", erc, stringify!(#name), #variant_names))),
 				}
-			}).collect();
+			});
 			
 			quote!{
-				impl Dewoodable for #name {
-					fn dewoodify(v:&wood::Wood)-> Result<Self, wood::DewoodifyError> {
-						match v.initial_str() {
-							#(#variant_cases,)*
-							erc => Err(wood::DewoodifyError::new(v, format!("expected a {}, but no variant of {} is called {}", stringify!(#name), stringify!(#name), erc))),
-						}
-					}
+				impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+					#method
 				}
 			}
 		},
 		
 		
-		Union(_)=> {
-			panic!("derive(Dewoodable) doesn't yet support unions")
+		Union(ref u)=> {
+			return Err(syn::Error::new_spanned(u.union_token, format!("derive({}) doesn't yet support unions", reading.trait_name())));
 		}
-	});
-	
-	ret
+	})
 }
 
 
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn errors(src:&str)-> (String, String) {
+		let ast:syn::DeriveInput = syn::parse_str(src).unwrap();
+		let w = woodable_impl(&ast).err().expect("Woodable should have been refused");
+		let d = dewoodable_impl(&ast, Reading::Borrowed).err().expect("Dewoodable should have been refused");
+		let o = dewoodable_impl(&ast, Reading::Owned).err().expect("DewoodableOwned should have been refused");
+		assert_eq!(d.to_string(), o.to_string());
+		assert!(w.to_compile_error().to_string().contains("compile_error"));
+		(w.to_string(), d.to_string())
+	}
+	
+	#[test]
+	fn bad_attributes() {
+		let (w, d) = errors("#[wood(rename_all = \"sPoNgEcAsE\")] struct A{ a:u8 }");
+		assert_eq!(w, "#[wood(rename_all = \"sPoNgEcAsE\")] isn't a case wood_derive knows");
+		assert_eq!(w, d);
+		assert_eq!(errors("#[wood(colour)] struct A{ a:u8 }").0, "unknown or malformed #[wood(colour)] on a type");
+		assert_eq!(errors("struct A{ #[wood(rename)] a:u8 }").1, "unknown or malformed #[wood(rename)] on a field or variant");
+		assert_eq!(errors("enum A{ B{ #[wood(skip = \"yes\")] b:u8 } }").0, "unknown or malformed #[wood(skip)] on a field or variant");
+		assert_eq!(errors("struct A(#[wood(skip)] u8);").0, "only #[wood(with = ...)] is supported on tuple fields");
+		assert_eq!(errors("#[wood(transparent)] struct A(u8, u8);").1, "#[wood(transparent)] needs a struct with exactly one field");
+		assert_eq!(errors("#[wood(transparent)] enum A{ B }").0, "#[wood(transparent)] can only be used on structs");
+		assert_eq!(errors("#[wood] struct A{ a:u8 }").0, "expected #[wood(...)]");
+		errors("struct A{ #[wood(rename = )] a:u8 }");
+		let union:syn::DeriveInput = syn::parse_str("union A{ a:u8 }").unwrap();
+		assert_eq!(dewoodable_impl(&union, Reading::Owned).err().unwrap().to_string(), "derive(DewoodableOwned) doesn't yet support unions");
+	}
+}
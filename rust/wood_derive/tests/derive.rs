@@ -0,0 +1,55 @@
+extern crate wood;
+extern crate wood_derive;
+use wood::{parse_termpose, dewoodify_owned, Woodable, Dewoodable};
+use wood_derive::{Woodable, Dewoodable, DewoodableOwned};
+
+#[derive(Woodable, Dewoodable, PartialEq, Debug)]
+#[wood(rename_all = "kebab-case")]
+enum Event {
+	KeyDown{ key_code:u32, #[wood(rename = "shift")] with_shift:bool },
+	Resize(u32, u32),
+}
+
+#[test]
+fn variant_fields_follow_rename_all() {
+	let e = Event::KeyDown{ key_code:4, with_shift:true };
+	assert_eq!(e.woodify(), parse_termpose("key-down key-code:4 shift:true").unwrap());
+	assert_eq!(Event::dewoodify(&e.woodify()).unwrap(), e);
+	let r = Event::Resize(3, 4);
+	assert_eq!(r.woodify(), parse_termpose("resize 3 4").unwrap());
+	assert_eq!(Event::dewoodify(&r.woodify()).unwrap(), r);
+	assert!(Event::dewoodify(&parse_termpose("key-down key_code:4 shift:true").unwrap()).is_err());
+}
+
+#[derive(DewoodableOwned, PartialEq, Debug)]
+#[wood(rename_all = "kebab-case")]
+struct Config {
+	name:String,
+	#[wood(default)] aliases:Vec<String>,
+	#[wood(skip)] loaded:bool,
+	bounds:Bounds,
+	shape:Shape,
+}
+#[derive(DewoodableOwned, PartialEq, Debug)]
+struct Bounds(u32, String);
+#[derive(DewoodableOwned, PartialEq, Debug)]
+enum Shape {
+	Circle{ radius:u32 },
+	Label(String),
+	Empty,
+}
+
+#[test]
+fn owned() {
+	let w = parse_termpose("Config name:main (bounds (Bounds 3 wide)) (shape (Circle radius:2))").unwrap();
+	assert_eq!(dewoodify_owned::<Config>(w).unwrap(), Config{ name:"main".into(), aliases:vec!(), loaded:false, bounds:Bounds(3, "wide".into()), shape:Shape::Circle{ radius:2 } });
+	let w = parse_termpose("Config shape:Empty (aliases (a b)) (bounds (Bounds 3 wide)) name:main").unwrap();
+	let c:Config = dewoodify_owned(w).unwrap();
+	assert_eq!((c.aliases, c.shape), (vec!("a".to_string(), "b".to_string()), Shape::Empty));
+	assert_eq!(dewoodify_owned::<Shape>(parse_termpose("Label \"x y\"").unwrap()).unwrap(), Shape::Label("x y".into()));
+	let e = dewoodify_owned::<Config>(parse_termpose("Config\n  name main\n  bounds (Bounds 3 wide)\n  shape (Circle radius:x)").unwrap()).unwrap_err();
+	assert_eq!((e.line, e.column), (3, 9));
+	assert!(e.msg.starts_with("in field \"shape\": "), "{}", e.msg);
+	assert!(dewoodify_owned::<Shape>(parse_termpose("Square 2").unwrap()).unwrap_err().msg.starts_with("unknown variant \"Square\""));
+	assert!(dewoodify_owned::<Bounds>(parse_termpose("Bounds 3").unwrap()).is_err());
+}